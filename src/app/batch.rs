@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::app::{AppError, Overwrite, resolve_output_file, validate_input_file};
+use crate::app::output::{OutputMode, write_generated};
+use crate::app::converter::check_mig_file;
+use crate::app::converter::mig::Schema;
+use crate::app::framework::Framework;
+use crate::app::generator::{generate_with_naming, GeneratedFile};
+use crate::app::helper::file_helper::{
+    generate_migration_timestamp, generate_timestamp, is_extension, latest_timestamp, to_down_file,
+};
+use crate::app::logger::print_summary;
+
+// batch conversion of many mig-files into the directory
+
+// errors with the paths of the mig-files
+type FileErrors = Vec<(String, AppError)>;
+
+/// the input is a directory or a glob pattern, not one file
+pub fn is_batch_input(input: &str) -> bool {
+    return Path::new(input).is_dir() || input.contains(['*', '?', '[']);
+}
+
+/// mig-files in the directory or matched with the glob pattern in order of the path
+pub fn collect_mig_files(input: &str) -> Result<Vec<PathBuf>, AppError> {
+    let mut paths: Vec<PathBuf> = if Path::new(input).is_dir() {
+        fs::read_dir(input)
+            .map_err(|e| AppError::FailedReadDirectory(input.to_string(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect()
+    } else {
+        glob::glob(input)
+            .map_err(|e| AppError::InvalidGlobPattern(input.to_string(), e.msg))?
+            .filter_map(|path| path.ok())
+            .collect()
+    };
+    paths.retain(|path| path.is_file() && is_extension(path, "mig"));
+    paths.sort();
+    if paths.is_empty() {
+        return Err(AppError::NoMigFile(input.to_string()));
+    }
+    return Ok(paths);
+}
+
+/// how to convert and write the files
+#[derive(Clone, Debug)]
+pub struct ConvertOptions {
+    pub framework: Framework,
+    pub out_dir: PathBuf,
+    pub overwrite: Overwrite,
+    pub mode: OutputMode,
+    // timestamp of the first file
+    pub now: NaiveDateTime,
+    // template of the migration name
+    pub migration_name: String,
+}
+
+/// convert all tables of the files into out_dir of each target. the files are parsed once.
+/// referenced tables are converted first and the timestamps of the files are increased by a second in the order.
+/// Ok has the summary. the failed files and tables are in Err with the paths of the mig-files.
+/// input is the argument which the files are collected from. it is the path of the schema dump.
+pub fn convert_mig_files(input: &str, inputs: &[PathBuf], targets: &[ConvertOptions]) -> Result<String, AppError> {
+    let (schema, sources, mut errors) = load_schema(inputs)?;
+    let mut written = 0;
+    for options in targets.iter() {
+        written += write_migrations(&schema, options, &mut errors, |table| sources.get(table).map_or(input, |s| s))?;
+    }
+
+    let summary = format!(
+        "converted {} table(s) from {} file(s) into {} file(s)",
+        schema.tables.len(), inputs.len(), written
+    );
+    if !errors.is_empty() {
+        print_summary(&summary);
+        return Err(AppError::InvalidFiles(errors));
+    }
+    return Ok(summary);
+}
+
+/// all tables of the mig-file, or the mig-files in the directory or matched with the glob pattern.
+/// Err has the errors of the files if some of them are invalid.
+pub fn load_mig_files(input: &str) -> Result<Schema, AppError> {
+    let inputs = if is_batch_input(input) {
+        collect_mig_files(input)?
+    } else {
+        let path = PathBuf::from(input);
+        validate_input_file(&path).map_err(|e| AppError::InvalidFiles(vec!((input.to_string(), e))))?;
+        vec!(path)
+    };
+    let (schema, _, errors) = load_schema(&inputs)?;
+    if !errors.is_empty() {
+        return Err(AppError::InvalidFiles(errors));
+    }
+    return Ok(schema);
+}
+
+/// paths of the files as new migrations. the existing migrations are kept because each of them is a step.
+/// the timestamps are after the existing migrations to be applied later.
+pub fn to_new_migration_paths(files: &[GeneratedFile], options: &ConvertOptions) -> Result<Vec<PathBuf>, AppError> {
+    let framework = options.framework;
+    // the timestamps have no fraction of the second
+    let start = match latest_timestamp(&options.out_dir, &framework).map(|latest| latest + Duration::seconds(1)) {
+        Some(next) if next > options.now => next,
+        _ => options.now,
+    };
+    let mut paths = vec!();
+    for (i, file) in files.iter().enumerate() {
+        let time = start + Duration::seconds(i as i64);
+        let timestamp = generate_migration_timestamp(&framework, &time);
+        let output = options.out_dir.join(format!("{}{}", timestamp, file.file_name));
+        let exists = output.exists() || (file.down.is_some() && to_down_file(&output).exists());
+        if options.mode == OutputMode::Write && exists {
+            return Err(AppError::NewMigrationIsExist(output));
+        }
+        paths.push(output);
+    }
+    return Ok(paths);
+}
+
+/// all tables of the files in order of the references and the paths of the tables.
+/// invalid files and tables are in the errors with the paths.
+fn load_schema(inputs: &[PathBuf]) -> Result<(Schema, HashMap<String, String>, FileErrors), AppError> {
+    let mut errors: FileErrors = vec!();
+
+    // all tables of the files. later file cannot redefine the table
+    let mut schema = Schema::default();
+    let mut sources: Vec<String> = vec!();
+    for input in inputs.iter() {
+        let path = input.display().to_string();
+        match check_mig_file(input) {
+            Ok(file_schema) => {
+                for table in file_schema.tables {
+                    if schema.get_table(&table.name).is_some() {
+                        errors.push((path.clone(), AppError::DuplicatedTable(table.name)));
+                        continue;
+                    }
+                    sources.push(path.clone());
+                    schema.tables.push(table);
+                }
+            }
+            Err(e) => errors.push((path, AppError::Converter(e))),
+        }
+    }
+
+    let sorted = match schema.sort_by_references() {
+        Ok(sorted) => sorted.into_iter().cloned().collect(),
+        Err(cyclic) => {
+            // tables in the cycle cannot be created in any order
+            let mut sorted = vec!();
+            for (table, source) in schema.tables.iter().zip(sources.iter()) {
+                if cyclic.contains(&table.name) {
+                    errors.push((source.clone(), AppError::CyclicReference(table.name.clone())));
+                } else {
+                    sorted.push(table.clone());
+                }
+            }
+            Schema { tables: sorted }.sort_by_references()
+                .map_err(|_| AppError::UnreachedError)?
+                .into_iter().cloned().collect()
+        }
+    };
+    let sources = schema.tables.iter()
+        .map(|table| table.name.clone())
+        .zip(sources)
+        .collect();
+    return Ok((Schema { tables: sorted }, sources, errors));
+}
+
+/// write the files of the target. returns the number of the written files.
+/// the errors have the mig-file of the table which source returns.
+fn write_migrations<'a>(
+    schema: &Schema,
+    options: &ConvertOptions,
+    errors: &mut FileErrors,
+    source: impl Fn(&str) -> &'a str,
+) -> Result<usize, AppError> {
+    let framework = options.framework;
+    let files = generate_with_naming(framework, schema, &options.migration_name);
+    let mut written = 0;
+    for (i, file) in files.iter().enumerate() {
+        let time = options.now + Duration::seconds(i as i64);
+        let output = options.out_dir.join(format!("{}{}", generate_timestamp(&framework, &time), file.file_name));
+        let output = match resolve_output_file(output, &framework, options.overwrite, options.mode) {
+            Ok(output) => output,
+            Err(e) => {
+                errors.push((source(&file.table_name).to_string(), e));
+                continue;
+            }
+        };
+        write_generated(&output, file, options.mode)?;
+        written += 1;
+    }
+    return Ok(written);
+}
@@ -0,0 +1,230 @@
+use std::{fmt, fs};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, Utc};
+use serde::Deserialize;
+
+use crate::app::framework::{Framework, to_framework_type, to_framework_types};
+use crate::app::generator::DEFAULT_MIGRATION_NAME;
+use crate::app::generator::sql::Dialect;
+use crate::app::lint::Lint;
+
+// project configuration in mig.toml. the options of the command line override it.
+
+pub const CONFIG_FILE_NAME: &str = "mig.toml";
+
+/// default snapshot of `mig generate`
+pub const STATE_FILE_NAME: &str = ".mig-state.json";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    FailedReadConfigFile(PathBuf, std::io::Error),
+    InvalidConfig(PathBuf, toml::de::Error),
+    InvalidMigrationName(String),
+    UnknownTarget(String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ConfigError::FailedReadConfigFile(path, io_e) =>
+                write!(f, "failed read config file {},: {}", path.display(), io_e),
+            ConfigError::InvalidConfig(path, toml_e) =>
+                write!(f, "config file {} is invalid,: {}", path.display(), toml_e),
+            ConfigError::InvalidMigrationName(name) =>
+                write!(f, "migration name {} must have {{table}}", name),
+            ConfigError::UnknownTarget(name) => write!(f, "{} in mig.toml is not supported target", name),
+        }
+    }
+}
+
+/// ```toml
+/// target = ["laravel", "golang-migrate"]
+/// schema_dir = "schema"
+/// out_dir = "database/migrations"
+/// dialect = "postgres"
+/// timestamp_timezone = "utc"
+/// state_file = ".mig-state.json"
+///
+/// [naming]
+/// migration = "{method}_{table}_table"
+///
+/// [lint]
+/// no-primary-key = "deny"
+/// rename-hint = "allow"
+///
+/// [targets.golang-migrate]
+/// out_dir = "worker/migrations"
+/// naming = { migration = "{method}_{table}" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// default of --target
+    pub target: Option<Targets>,
+    /// default input of convert and check
+    pub schema_dir: Option<PathBuf>,
+    /// default of --out-dir
+    pub out_dir: Option<PathBuf>,
+    /// dialect of golang-migrate
+    pub dialect: Option<Dialect>,
+    pub timestamp_timezone: Timezone,
+    /// snapshot of `mig generate`
+    pub state_file: Option<PathBuf>,
+    pub naming: Naming,
+    pub lint: LintConfig,
+    /// settings for each target. the key is the name of the target.
+    pub targets: BTreeMap<String, TargetConfig>,
+}
+
+/// `target = "laravel"` or `target = ["laravel", "sql-postgres"]`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Targets {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// overrides the settings of the top level for the target
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetConfig {
+    pub out_dir: Option<PathBuf>,
+    pub dialect: Option<Dialect>,
+    pub naming: Naming,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Timezone {
+    #[default]
+    Local,
+    Utc,
+}
+
+impl Timezone {
+    /// time for the timestamp of the migration files
+    pub fn now(&self) -> NaiveDateTime {
+        return match self {
+            Timezone::Local => Local::now().naive_local(),
+            Timezone::Utc => Utc::now().naive_utc(),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Naming {
+    /// template of the migration name. {method} and {table} are replaced.
+    pub migration: Option<String>,
+}
+
+/// level of each lint. the key is the name of the lint. e.g. no-primary-key
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LintConfig {
+    pub no_primary_key: LintLevel,
+    pub rename_hint: LintLevel,
+    pub irreversible: LintLevel,
+    pub sqlite_recreate: LintLevel,
+}
+
+impl LintConfig {
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        return match lint {
+            Lint::NoPrimaryKey => self.no_primary_key,
+            Lint::RenameHint => self.rename_hint,
+            Lint::Irreversible => self.irreversible,
+            Lint::SqliteRecreate => self.sqlite_recreate,
+        };
+    }
+}
+
+/// allow drops the warning and deny makes it the error
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+impl Config {
+    pub fn get_migration_name(&self) -> &str {
+        return self.naming.migration.as_deref().unwrap_or(DEFAULT_MIGRATION_NAME);
+    }
+
+    /// comma separated names like --target
+    pub fn get_targets(&self) -> Option<String> {
+        return match &self.target {
+            Some(Targets::One(target)) => Some(target.clone()),
+            Some(Targets::Many(targets)) => Some(targets.join(",")),
+            None => None,
+        };
+    }
+
+    pub fn get_target_config(&self, framework: &Framework) -> Option<&TargetConfig> {
+        return self.targets.iter()
+            .find(|(name, _)| to_framework_type(name).map(|fw| fw.name()) == Some(framework.name()))
+            .map(|(_, target)| target);
+    }
+
+    /// the dialect of the target is prior to the top level
+    pub fn get_dialect(&self, framework: &Framework) -> Option<Dialect> {
+        return self.get_target_config(framework)
+            .and_then(|target| target.dialect)
+            .or(self.dialect);
+    }
+
+    /// .mig-state.json in the directory of mig.toml, or the current directory without mig.toml
+    pub fn get_state_file(&self) -> PathBuf {
+        return self.state_file.clone().unwrap_or_else(|| PathBuf::from(STATE_FILE_NAME));
+    }
+
+    pub fn get_target_migration_name(&self, framework: &Framework) -> &str {
+        return self.get_target_config(framework)
+            .and_then(|target| target.naming.migration.as_deref())
+            .unwrap_or_else(|| self.get_migration_name());
+    }
+}
+
+/// mig.toml in the directory or the nearest ancestor
+pub fn find_config_file(start: &Path) -> Option<PathBuf> {
+    return start.ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file());
+}
+
+/// relative paths in the config are from the directory of the config file
+pub fn load_config(path: &Path) -> Result<Config, ConfigError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfigError::FailedReadConfigFile(path.to_path_buf(), e))?;
+    let mut config: Config = toml::from_str(&content)
+        .map_err(|e| ConfigError::InvalidConfig(path.to_path_buf(), e))?;
+    let names = std::iter::once(&config.naming)
+        .chain(config.targets.values().map(|target| &target.naming))
+        .filter_map(|naming| naming.migration.as_ref());
+    for name in names {
+        if !name.contains("{table}") {
+            return Err(ConfigError::InvalidMigrationName(name.clone()));
+        }
+    }
+    if let Some(targets) = config.get_targets() {
+        to_framework_types(&targets).map_err(ConfigError::UnknownTarget)?;
+    }
+    if let Some(name) = config.targets.keys().find(|name| to_framework_type(name).is_none()) {
+        return Err(ConfigError::UnknownTarget(name.clone()));
+    }
+
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    config.schema_dir = config.schema_dir.map(|dir| base.join(dir));
+    config.out_dir = config.out_dir.map(|dir| base.join(dir));
+    config.state_file = Some(base.join(config.state_file.unwrap_or_else(|| PathBuf::from(STATE_FILE_NAME))));
+    for target in config.targets.values_mut() {
+        target.out_dir = target.out_dir.take().map(|dir| base.join(dir));
+    }
+    return Ok(config);
+}
@@ -0,0 +1,68 @@
+use crate::app::converter::token::{Comment, Token};
+
+// every node has (row, col) of its head token
+
+/// whole of mig-file:  many command
+#[derive(Debug, Clone, PartialEq)]
+pub struct File {
+    pub commands: Vec<Command>,
+    /// in order of the position. they are used only by the formatter.
+    pub comments: Vec<Comment>,
+}
+
+/// `:create members { ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub name: String,
+    pub table_name: String,
+    pub body: Block,
+    pub position: (u32, u32),
+}
+
+/// `{ ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub nodes: Vec<Node>,
+    pub position: (u32, u32),
+    /// (row, col) of `}`
+    pub end: (u32, u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Column(ColumnNode),
+    Option(OptionNode),
+}
+
+impl Node {
+    pub fn get_position(&self) -> (u32, u32) {
+        return match self {
+            Node::Column(c) => c.position,
+            Node::Option(o) => o.position,
+        };
+    }
+}
+
+/// `name { ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnNode {
+    pub name: String,
+    pub body: Block,
+    pub position: (u32, u32),
+}
+
+/// `:name arg arg { ... }` and the block is optional
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionNode {
+    pub name: String,
+    pub args: Vec<Arg>,
+    pub body: Option<Block>,
+    pub position: (u32, u32),
+}
+
+/// value or Name which is parameter of option
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    pub value: Token,
+    pub position: (u32, u32),
+}
@@ -1,71 +1,215 @@
-use std::collections::HashMap;
-use std::iter::Map;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
 
-use crate::app::converter::token::Token;
+// typed model of mig-file. it is made by semantic analysis and used by generators.
 
-#[derive(Debug, Clone)]
-pub struct Mig {
-    method: String,
-    table_name: String,
-    column_options: HashMap<String, Vec<(String, Vec<Token>)>>,
-    table_options: HashMap<String, Vec<(String, Vec<Token>)>>,
+/// all tables in mig-file(s) in order of the commands
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Schema {
+    pub tables: Vec<Table>,
 }
 
-impl Mig {
-    pub fn new() -> Self {
-        Mig {
-            method: "".to_string(),
-            table_name: "".to_string(),
-            column_options: HashMap::new(),
-            table_options: HashMap::new(),
+impl Schema {
+    pub fn get_table(&self, name: &str) -> Option<&Table> {
+        return self.tables.iter().find(|t| t.name == name);
+    }
+
+    /// tables in order that referenced tables come first.
+    /// the order of the commands is kept as far as possible. references to unknown tables are ignored.
+    /// Err has names of the tables in cyclic references.
+    pub fn sort_by_references(&self) -> Result<Vec<&Table>, Vec<String>> {
+        let mut sorted: Vec<&Table> = vec!();
+        let mut rest: Vec<&Table> = self.tables.iter().collect();
+        while !rest.is_empty() {
+            let ready = rest.iter().position(|table| {
+                table.referenced_tables().iter().all(|name| {
+                    *name == table.name
+                        || self.get_table(name).is_none()
+                        || sorted.iter().any(|t| t.name == *name)
+                })
+            });
+            match ready {
+                Some(i) => sorted.push(rest.remove(i)),
+                None => return Err(rest.iter().map(|t| t.name.clone()).collect()),
+            }
         }
+        return Ok(sorted);
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    Create,
+}
 
-    pub fn set_method(&mut self, method_name: Token) -> &mut Self {
-        match method_name {
-            Token::NameColon(s) => self.method = s.clone(),
-            _ => panic!("set method"),
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            Method::Create => "create",
         };
-        return self;
     }
+}
 
-    pub fn set_table_name(&mut self, table_name: Token) -> &mut Self {
-        match table_name {
-            Token::Name(s) => self.table_name = s.clone(),
-            _ => panic!("set table name"),
-        }
-        return self;
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub method: Method,
+    pub name: String,
+    /// in order of the mig-file
+    pub columns: Vec<Column>,
+    pub timestamps: bool,
+    pub soft_deletes: bool,
+    pub indexes: Vec<Index>,
+}
+
+impl Table {
+    pub fn new(method: Method, name: &str) -> Self {
+        return Table {
+            method,
+            name: name.to_string(),
+            columns: vec!(),
+            timestamps: false,
+            soft_deletes: false,
+            indexes: vec!(),
+        };
     }
 
-    pub fn add_column_options(&mut self, column_name: Token, options: &mut Vec<(String, Vec<Token>)>) -> &mut Self {
-        match column_name {
-            Token::Name(name) => {
-                if self.column_options.get(&name).is_none() {
-                    self.column_options.insert(name, options.clone());
-                } else {
-                    let mut tokens = self.column_options.get(&name).unwrap().clone();
-                    tokens.append(options);
-                    self.column_options.insert(name, tokens.to_vec());
-                }
-            },
-            _ => panic!("add column options"),
-        }
-        return self;
+    pub fn get_column(&self, name: &str) -> Option<&Column> {
+        return self.columns.iter().find(|c| c.name == name);
     }
 
-    pub fn add_table_options(&mut self, option_name: Token, options: &mut Vec<(String, Vec<Token>)>) -> &mut Self {
-        match option_name {
-            Token::NameColon(name) => {
-                if self.table_options.get(&name).is_none() {
-                    self.table_options.insert(name, options.clone());
-                } else {
-                    let mut tokens = self.table_options.get(&name).unwrap().clone();
-                    tokens.append(options);
-                    self.table_options.insert(name, tokens.to_vec());
+    /// names of the tables which the columns reference without duplication
+    pub fn referenced_tables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec!();
+        for column in self.columns.iter() {
+            if let Some(foreign) = &column.references {
+                if !names.contains(&foreign.table.as_str()) {
+                    names.push(&foreign.table);
                 }
-            },
-            _ => panic!("add table options"),
+            }
         }
-        return self;
+        return names;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    pub unsigned: bool,
+    pub nullable: bool,
+    pub default: Option<Value>,
+    pub comment: Option<String>,
+    // added after version 1 of the exported document
+    #[serde(default)]
+    pub references: Option<ForeignKey>,
+}
+
+impl Column {
+    pub fn new(name: &str, column_type: ColumnType) -> Self {
+        return Column {
+            name: name.to_string(),
+            column_type,
+            unsigned: false,
+            nullable: false,
+            default: None,
+            comment: None,
+            references: None,
+        };
+    }
+}
+
+/// the column references the column of the other table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub table: String,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum ColumnType {
+    Increments,
+    BigIncrements,
+    TinyInteger,
+    SmallInteger,
+    Integer,
+    BigInteger,
+    Float,
+    Double,
+    Decimal { precision: u16, scale: u16 },
+    Boolean,
+    Char { length: u16 },
+    String { length: Option<u16> },
+    Text,
+    Date,
+    Time,
+    DateTime,
+    Timestamp,
+    Json,
+    Binary,
+}
+
+impl ColumnType {
+    pub fn is_integer(&self) -> bool {
+        return matches!(self,
+            ColumnType::Increments | ColumnType::BigIncrements
+            | ColumnType::TinyInteger | ColumnType::SmallInteger
+            | ColumnType::Integer | ColumnType::BigInteger);
+    }
+
+    pub fn is_number(&self) -> bool {
+        return self.is_integer()
+            || matches!(self, ColumnType::Float | ColumnType::Double | ColumnType::Decimal { .. });
+    }
+
+    pub fn is_string(&self) -> bool {
+        return matches!(self, ColumnType::Char { .. } | ColumnType::String { .. } | ColumnType::Text);
+    }
+
+    pub fn is_auto_increment(&self) -> bool {
+        return matches!(self, ColumnType::Increments | ColumnType::BigIncrements);
     }
+
+    /// check the default value can be stored in the column of this type
+    pub fn accepts(&self, value: &Value) -> bool {
+        return match value {
+            Value::Integer(_) => self.is_number() || *self == ColumnType::Boolean,
+            Value::Double(_) => self.is_number() && !self.is_integer(),
+            Value::String(_) => self.is_string() || *self == ColumnType::Json,
+            Value::Date(_) => *self == ColumnType::Date,
+            Value::Time(_) => *self == ColumnType::Time,
+            Value::DateTime(_) | Value::DateTimeTz(_) =>
+                *self == ColumnType::DateTime || *self == ColumnType::Timestamp,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Value {
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    // date time with the timezone offset
+    DateTimeTz(DateTime<FixedOffset>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    pub kind: IndexKind,
+    pub columns: Vec<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexKind {
+    Index,
+    Unique,
+    Primary,
 }
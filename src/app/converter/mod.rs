@@ -1,16 +1,22 @@
-use std::{fmt, fs, io};
-use std::error::Error;
+use std::{fmt, fs};
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::path::Path;
 
-use crate::app::AppError::Converter;
+use log::{debug, trace};
+
+use crate::app::converter::ast::File;
+use crate::app::converter::mig::Schema;
 use crate::app::converter::parser::{lexical_analyzer, ParserError};
-use crate::app::converter::syntax::SyntaxError;
+use crate::app::converter::semantic::SemanticError;
+use crate::app::converter::syntax::{syntax_analyzer, SyntaxError};
 use crate::app::framework::Framework;
+use crate::app::generator::{generate_with_naming, GeneratedFile};
 
 pub mod token;
+pub mod ast;
 pub mod parser;
 pub mod syntax;
+pub mod semantic;
 pub mod mig;
 
 #[derive(Debug)]
@@ -18,6 +24,9 @@ pub enum ConverterError {
     FailedReadInputFile(std::io::Error),
     Parse(ParserError),
     Syntax(SyntaxError),
+    Semantic(SemanticError),
+    // one output file has one table
+    TooManyTables(usize),
 }
 
 impl From<std::io::Error> for ConverterError {
@@ -38,46 +47,80 @@ impl From<SyntaxError> for ConverterError {
     }
 }
 
+impl From<SemanticError> for ConverterError {
+    fn from(s_e: SemanticError) -> Self {
+        return ConverterError::Semantic(s_e);
+    }
+}
+
 impl Display for ConverterError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
-            ConverterError::FailedReadInputFile(io_e) => write!(f, "failed read input file,: {}", io_e.to_string()),
-            ConverterError::Parse(p_e) => write!(f, "parse error: {}", p_e.to_string()),
-            ConverterError::Syntax(s_e) => write!(f, "syntax error: {}", s_e.to_string()),
+            ConverterError::FailedReadInputFile(io_e) => write!(f, "failed read input file,: {}", io_e),
+            ConverterError::Parse(p_e) => write!(f, "parse error: {}", p_e),
+            ConverterError::Syntax(s_e) => write!(f, "syntax error: {}", s_e),
+            ConverterError::Semantic(s_e) => write!(f, "semantic error: {}", s_e),
+            ConverterError::TooManyTables(n) => write!(f, "input file has {} tables. but output file has only one table", n),
         }
     }
 }
 
-// this function is controller for convert
-pub fn convert_to_migration_file<'a, 'b>(
-    input: PathBuf,
-    output: PathBuf,
-    framework: Framework,
-) -> Result<&'a str, ConverterError> {
-    println!("reading from input file...");
-    let content = fs::read_to_string(input)?;
-    println!("finish reading file");
+impl ConverterError {
+    /// stable code for the diagnostics
+    pub fn code(&self) -> &'static str {
+        return match self {
+            ConverterError::Parse(p_e) => p_e.code(),
+            ConverterError::Syntax(s_e) => s_e.code(),
+            ConverterError::Semantic(s_e) => s_e.code(),
+            ConverterError::FailedReadInputFile(_) => "E0301",
+            ConverterError::TooManyTables(_) => "E0302",
+        };
+    }
 
-    println!("parsing content...");
+    /// (row, col) in mig-file
+    pub fn get_position(&self) -> Option<(u32, u32)> {
+        return match self {
+            ConverterError::Parse(p_e) => p_e.get_position(),
+            ConverterError::Syntax(s_e) => s_e.get_position(),
+            ConverterError::Semantic(s_e) => Some(s_e.get_position()),
+            ConverterError::FailedReadInputFile(_) | ConverterError::TooManyTables(_) => None,
+        };
+    }
+}
+
+/// lexical and syntax analysis of mig-file
+pub fn parse_mig_file(input: &Path) -> Result<File, ConverterError> {
+    let content = fs::read_to_string(input)?;
     let tokens = lexical_analyzer(content)?;
-    // println!("{:?}", tokens);
-    println!("finish parsing");
+    return Ok(syntax_analyzer(&tokens)?);
+}
 
-    //println!("{:?}", tokens);
+/// all analysis of mig-file without writing anything
+pub fn check_mig_file(input: &Path) -> Result<Schema, ConverterError> {
+    debug!("reading from {}...", input.display());
+    let content = fs::read_to_string(input)?;
 
-    println!("analyze parsing data...");
-    let mig = tokens.analyze_syntax()?;
-    println!("{}", "-".repeat(50));
-    println!("Mig:  {:?}", mig);
-    println!("finish analyzing data");
+    debug!("parsing content...");
+    let tokens = lexical_analyzer(content)?;
+    trace!("tokens: {:?}", tokens);
 
-    // TODO convert from tokens to code of target's framework
-    println!("converting checked data...");
-    println!("finish converting data");
+    debug!("analyze parsing data...");
+    let schema = tokens.analyze_syntax()?;
+    debug!("schema: {:?}", schema);
+    return Ok(schema);
+}
 
-    // TODO write token sequence in output file
-    println!("writing data in output file");
-    println!("finish writing data");
+/// migration of the mig-file which has only one table
+/// migration_name is the template of the name. e.g. "{method}_{table}_table"
+pub fn convert_mig_file(input: &Path, framework: Framework, migration_name: &str)
+                        -> Result<GeneratedFile, ConverterError> {
+    debug!("converting checked data...");
+    let schema = check_mig_file(input)?;
+    let mut files = generate_with_naming(framework, &schema, migration_name);
+    if files.len() != 1 {
+        return Err(ConverterError::TooManyTables(files.len()));
+    }
+    debug!("finish converting data");
 
-    return Ok("Success!! converted!");
+    return Ok(files.remove(0));
 }
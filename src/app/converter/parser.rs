@@ -1,25 +1,23 @@
-use std::error;
-use std::error::Error;
+use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::str::Chars;
 
-use failure::Fail;
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
 
-use crate::app::converter::token::{Sequence, Token};
+use crate::app::converter::token::{Comment, Sequence, Token};
 
-pub fn lexical_analyzer<'a>(input: String) -> Result<Sequence, ParserError> {
-    return Parser::new(input).parse();
+pub fn lexical_analyzer<S: AsRef<str>>(input: S) -> Result<Sequence, ParserError> {
+    return Parser::new(input.as_ref()).parse();
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum ParserError {
     // for Stream
-    NotGetCharacter(u16, u16),
-    NotAsciiCharacter(u16, u16),
-    UnknownToken(u16, u16),
-    NotANumber(u16, u16),
-    NumberRangeError(u16, u16),
+    NotGetCharacter(u32, u32),
+    NotAsciiCharacter(u32, u32),
+    UnknownToken(u32, u32),
+    NotANumber(u32, u32),
+    NumberRangeError(u32, u32),
     EndOfStream,
 }
 
@@ -42,14 +40,46 @@ impl Display for ParserError {
     }
 }
 
+impl ParserError {
+    /// stable code for the diagnostics
+    pub fn code(&self) -> &'static str {
+        return match self {
+            ParserError::NotGetCharacter(_, _) => "E0101",
+            ParserError::NotAsciiCharacter(_, _) => "E0102",
+            ParserError::UnknownToken(_, _) => "E0103",
+            ParserError::NotANumber(_, _) => "E0104",
+            ParserError::NumberRangeError(_, _) => "E0105",
+            ParserError::EndOfStream => "E0106",
+        };
+    }
+
+    pub fn get_position(&self) -> Option<(u32, u32)> {
+        return match *self {
+            ParserError::NotGetCharacter(row, col)
+            | ParserError::NotAsciiCharacter(row, col)
+            | ParserError::UnknownToken(row, col)
+            | ParserError::NotANumber(row, col)
+            | ParserError::NumberRangeError(row, col) => Some((row, col)),
+            ParserError::EndOfStream => None,
+        };
+    }
+}
+
 #[derive(Debug)]
 pub struct Counter {
     // (row, col)
-    cursor: (u16, u16),
+    cursor: (u32, u32),
+    // byte offset of the next character
     position: usize,
     is_new_line: bool,
 }
 
+impl Default for Counter {
+    fn default() -> Self {
+        return Counter::new();
+    }
+}
+
 impl Counter {
     pub fn new() -> Self {
         return Counter { cursor: (0, 0), position: 0, is_new_line: true };
@@ -58,13 +88,13 @@ impl Counter {
 
 #[derive(Debug)]
 struct Stream<'a> {
-    chars: Chars<'a>,
+    src: &'a str,
     counter: Counter,
 }
 
 impl<'a> Stream<'a> {
-    fn new(parser: &'a Parser) -> Self {
-        return Stream { chars: (*parser.src).chars(), counter: Counter::new() };
+    fn new(src: &'a str) -> Self {
+        return Stream { src, counter: Counter::new() };
     }
 
     fn next(&mut self) -> Result<char, ParserError> {
@@ -74,21 +104,18 @@ impl<'a> Stream<'a> {
             self.counter.cursor.1 = 0;
             self.counter.is_new_line = false;
         }
-        let ch_opt = self.chars.next();
-        if ch_opt.is_none() {
-            if self.chars.as_str() == "" {
-                return Err(ParserError::EndOfStream);
-            }
-            return Err(ParserError::NotGetCharacter(self.get_row(), self.get_col()));
-        }
-        let ch = ch_opt.unwrap();
+        let ch = match self.look() {
+            Some(ch) => ch,
+            None => return Err(ParserError::EndOfStream),
+        };
         if !ch.is_ascii() {
             return Err(ParserError::NotAsciiCharacter(self.get_row(), self.get_col()));
         }
 
+        // ascii character is one byte
+        self.counter.position += 1;
         // update is next line
         if ch == '\r' {  // for windows
-            self.counter.position += 1;
             return self.next();
         }
         if ch == '\n' {
@@ -96,70 +123,52 @@ impl<'a> Stream<'a> {
         }
 
         // update counter
-        self.counter.position += 1;
         self.counter.cursor.1 += 1;
-        // print!("{}, ", ch);
         return Ok(ch);
     }
 
-    // better? return type is Chars
-    pub fn next_while<F>(&mut self, check: F) -> Vec<char>
+    /// consume ascii characters while check is satisfied and borrow them from the source
+    pub fn next_while<F>(&mut self, check: F) -> &'a str
         where F: Fn(char) -> bool {
-        let mut v = Vec::new();
+        let start = self.counter.position;
         // look next char
-        while let Some(c) = self.look(1) {
+        while let Some(c) = self.look() {
             // c satisfy with check
-            if check(c) {
+            if c.is_ascii() && check(c) {
                 // consume stream's next char
-                v.push(self.next().unwrap());
+                let _ = self.next();
                 continue;
             }
             break;
         }
-        return v;
+        return &self.src[start..self.counter.position];
     }
 
     pub fn skip_spaces_or_newlines(&mut self) {
-        let cs = self.next_while(|c| c.is_whitespace());
+        let _ = self.next_while(|c| c.is_whitespace());
     }
 
-    /// 1 origin
-    pub fn look(&mut self, n: usize) -> Option<char> {
-        let mut i = 1usize;
-        if n == 0 { return None; }
-        let mut n_dummy = n;
-        while i != n_dummy {
-            let nth_opt = self.chars.clone().nth(i - 1);
-            if nth_opt.is_none() {
-                return None;
-            }
-            let nth_char = nth_opt.unwrap();
-            if '\r' == nth_char {
-                n_dummy += 1;
-                i += 1;
-                continue;
-            }
-            if nth_char.is_ascii() {
-                i += 1;
-                continue;
-            }
-            return None;
-        }
-        let c = self.chars.clone().nth(n_dummy - 1);
-        return c;
+    /// next character without consuming
+    pub fn look(&self) -> Option<char> {
+        return self.src[self.counter.position..].chars().next();
     }
 
-    pub fn get_row(&self) -> u16 {
+    pub fn get_row(&self) -> u32 {
         return self.counter.cursor.0;
     }
 
-    pub fn get_col(&self) -> u16 {
+    pub fn get_col(&self) -> u32 {
         return self.counter.cursor.1;
     }
 
     pub fn get_position(&self) -> usize {
         return self.counter.position;
     }
+
+    /// error at the current (row, col)
+    pub fn error(&self, e: fn(u32, u32) -> ParserError) -> ParserError {
+        return e(self.get_row(), self.get_col());
+    }
 }
 
 // check the character which we can use for option name for mig-file
@@ -168,111 +177,119 @@ fn is_mig_opt_name_char(c: char) -> bool {
 }
 
 // check the string which we can use for option name for mig-file
-fn is_mig_opt_name(cs: &Vec<char>) -> bool {
-    if cs.is_empty() { return false; }
-    if cs[0].is_ascii_digit() || cs[0] == '-' { return false; }
-    return cs.iter().fold(true, |acc, c| acc && is_mig_opt_name_char(*c));
+fn is_mig_opt_name(cs: &str) -> bool {
+    return match cs.chars().next() {
+        None => false,
+        Some(c) if c.is_ascii_digit() || c == '-' => false,
+        Some(_) => cs.chars().all(is_mig_opt_name_char),
+    };
+}
+
+// check the character which we can use for name of the table or the column
+fn is_mig_name_char(c: char) -> bool {
+    return c.is_ascii_alphanumeric() || c == '_';
+}
+
+/// check the string which is read as one Name token. e.g. user_id
+pub fn is_mig_name(cs: &str) -> bool {
+    return match cs.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => cs.chars().all(is_mig_name_char),
+        _ => false,
+    };
+}
+
+// check the character which can be after a number
+fn is_number_terminator(sym: char) -> bool {
+    return !sym.is_ascii() || sym.is_whitespace() || sym == '{' || sym == '}';
 }
 
 #[derive(Debug, Clone)]
-pub struct Parser {
-    src: String,
+pub struct Parser<'a> {
+    src: &'a str,
 }
 
-impl Parser {
-    pub fn new(input: String) -> Self {
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
         return Parser { src: input };
     }
 
-    pub fn parse(&mut self) -> Result<Sequence, ParserError> {
-        let mut parsed: Vec<Token> = Vec::new();
+    pub fn parse(&self) -> Result<Sequence, ParserError> {
+        // token and (row, col) of its head
+        let mut parsed: Vec<(Token, (u32, u32))> = Vec::new();
+        let mut comments: Vec<Comment> = Vec::new();
 
         // init
-        let parser_clone = self.clone();
-        let mut stream = Stream::new(&parser_clone);
+        let mut stream = Stream::new(self.src);
 
         loop {
             stream.skip_spaces_or_newlines();
-            let c_opt = stream.next();
-            match c_opt {
-                Ok(_) => {}
-                Err(e) => {
-                    if e != ParserError::EndOfStream {
-                        return Err(e);
-                    }
-                    break;
-                }
-            }
-            let c = c_opt.unwrap();
+            let start = stream.get_position();
+            let c = match stream.next() {
+                Ok(c) => c,
+                Err(ParserError::EndOfStream) => break,
+                Err(e) => return Err(e),
+            };
+            let position = (stream.get_row(), stream.get_col());
             match c {
                 '{' => {
-                    parsed.push(Token::LMidParen);
+                    parsed.push((Token::LMidParen, position));
                     continue;
                 }
                 '}' => {
-                    parsed.push(Token::RMidParen);
+                    parsed.push((Token::RMidParen, position));
+                    continue;
+                }
+                '#' => {
+                    let text = stream.next_while(|c| c != '\n');
+                    comments.push(Comment {
+                        text: text.trim_end().to_string(),
+                        position,
+                        trailing: parsed.last().is_some_and(|(_, p)| p.0 == position.0),
+                    });
                     continue;
                 }
                 ':' => {
-                    let cs = stream.next_while(|c| is_mig_opt_name_char(c));
-                    if is_mig_opt_name(&cs) {
-                        let cs_dummy = cs.clone();
-                        let s = cs_dummy.iter().collect();
-                        parsed.push(Token::NameColon(s));
+                    let cs = stream.next_while(is_mig_opt_name_char);
+                    if is_mig_opt_name(cs) {
+                        parsed.push((Token::NameColon(cs.to_string()), position));
                         continue;
                     }
-                    return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                    return Err(stream.error(ParserError::UnknownToken));
                 }
                 '"' => {
                     let cs = stream.next_while(|c| c != '"');
-                    let checker = stream.next();
-                    match checker {
+                    match stream.next() {
                         Ok('"') => {
-                            parsed.push(Token::String(cs.iter().collect()));
+                            parsed.push((Token::String(cs.replace('\r', "")), position));
                             continue;
                         }
                         Ok(_) => {
-                            return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                            return Err(stream.error(ParserError::UnknownToken));
                         }
                         Err(e) => {
                             return Err(e);
                         }
                     }
                 }
-                i @ '0'...'9' => {
-                    let mut v = vec!(i);
-                    let mut v_tail: Vec<char> = stream.next_while(|c| c.is_ascii_digit());
-                    v.append(&mut v_tail);
+                '0'..='9' => {
+                    let _ = stream.next_while(|c| c.is_ascii_digit());
                     // digits is unsigned integer in head of stream
-                    let digits = v;
-                    let look = stream.look(1);
+                    let digits = &self.src[start..stream.get_position()];
+                    let look = stream.look();
                     match digits.len() {
                         // Time
                         2 if look == Some(':') => {
-                            // Time is form:  00:00:00
+                            // Time is form:  00:00:00 or 00:00:00.000
 
                             // stream head is ':'
                             let _ = stream.next();
                             let cs = stream.next_while(|c| c.is_ascii_digit() || c == ':');
-                            if !(cs.len() == 5 && cs[2] == ':') {
-                                return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
-                            }
-                            let h = to_unsigned_integer(digits)
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            if h >= 24 {
-                                return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
-                            }
-                            let m = to_unsigned_integer(cs[0..2].to_vec())
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            if m >= 60 {
-                                return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
+                            if !(cs.len() == 5 && &cs[2..3] == ":") {
+                                return Err(stream.error(ParserError::UnknownToken));
                             }
-                            let s = to_unsigned_integer(cs[3..5].to_vec())
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            if s >= 60 {
-                                return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
-                            }
-                            parsed.push(Token::Time(h as u8, m as u8, s as u8));
+                            let hms = &self.src[start..stream.get_position()];
+                            let time = to_time(&mut stream, hms)?;
+                            parsed.push((Token::Time(time), position));
                             continue;
                         }
                         // Ymd or DateTime
@@ -281,60 +298,47 @@ impl Parser {
                             // md's form is 00-00
                             let md = stream.next_while(|c| c.is_ascii_digit() || c == '-');
                             if md.len() != 5 {
-                                return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
-                            }
-                            if md[2] != '-' {
-                                return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                                return Err(stream.error(ParserError::UnknownToken));
                             }
-                            let y = to_unsigned_integer(digits.to_vec())
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            let m = to_unsigned_integer(md[0..2].to_vec())
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            let d = to_unsigned_integer(md[3..5].to_vec())
-                                .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                            if m >= 13 || d >= 32 {
-                                return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
+                            if &md[2..3] != "-" {
+                                return Err(stream.error(ParserError::UnknownToken));
                             }
-
-                            let look = stream.look(1);
-                            match look {
+                            let y = to_unsigned_integer(digits)
+                                .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+                            let m = to_unsigned_integer(&md[0..2])
+                                .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+                            let d = to_unsigned_integer(&md[3..5])
+                                .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+                            // check with the real calendar. e.g. 2019-02-31 is not exist.
+                            let date = to_date(y, m, d)
+                                .ok_or_else(|| stream.error(ParserError::NumberRangeError))?;
+
+                            match stream.look() {
                                 None => {
-                                    parsed.push(Token::Ymd(y as u16, m as u8, d as u8));
+                                    parsed.push((Token::Ymd(date), position));
                                     continue;
                                 }
                                 Some(ch) if ch.is_whitespace() || ch == '{' || ch == '}' => {
-                                    parsed.push(Token::Ymd(y as u16, m as u8, d as u8));
+                                    parsed.push((Token::Ymd(date), position));
                                     continue;
                                 }
                                 Some('_') => {
                                     // stream head is '_'
                                     let _ = stream.next();
 
-                                    // Time is form:  00:00:00
+                                    // Time is form:  00:00:00 or 00:00:00.000
                                     let cs = stream.next_while(|c| c.is_ascii_digit() || c == ':');
-                                    if !(cs.len() == 8 && cs[2] == ':' && cs[5] == ':') {
-                                        return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
-                                    }
-                                    let h = to_unsigned_integer(cs[0..2].to_vec())
-                                        .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                                    if h >= 25 {
-                                        return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
-                                    }
-                                    let mi = to_unsigned_integer(cs[3..5].to_vec())
-                                        .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                                    if mi >= 60 {
-                                        return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
+                                    if !(cs.len() == 8 && &cs[2..3] == ":" && &cs[5..6] == ":") {
+                                        return Err(stream.error(ParserError::UnknownToken));
                                     }
-                                    let s = to_unsigned_integer(cs[6..8].to_vec())
-                                        .ok_or(ParserError::UnknownToken(stream.get_row(), stream.get_col()))?;
-                                    if s >= 60 {
-                                        return Err(ParserError::NumberRangeError(stream.get_row(), stream.get_col()));
-                                    }
-                                    parsed.push(Token::DateTime(y as u16, m as u8, d as u8, h as u8, mi as u8, s as u8));
+                                    let time = to_time(&mut stream, cs)?;
+                                    // offset is form:  Z or +00:00 or -00:00
+                                    let offset = to_offset(&mut stream)?;
+                                    parsed.push((Token::DateTime(date.and_time(time), offset), position));
                                     continue;
                                 }
                                 Some(_) => {
-                                    return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                                    return Err(stream.error(ParserError::UnknownToken));
                                 }
                             }
                         }
@@ -343,95 +347,74 @@ impl Parser {
                             let _ = stream.next();
                             let opt_digit = stream.next_while(|c| c.is_ascii_digit());
                             if opt_digit.is_empty() {
-                                return Err(ParserError::NotANumber(stream.get_row(), stream.get_col()));
+                                return Err(stream.error(ParserError::NotANumber));
                             }
-                            if let Some(sym) = stream.look(1) {
-                                if !(!sym.is_ascii() || sym.is_whitespace() || sym == '{' || sym == '}') {
-                                    return Err(ParserError::NotANumber(stream.get_row(), stream.get_col()));
+                            if let Some(sym) = stream.look() {
+                                if !is_number_terminator(sym) {
+                                    return Err(stream.error(ParserError::NotANumber));
                                 }
                             }
-                            let d = to_unsigned_f32(digits, opt_digit)
-                                .ok_or(ParserError::NotANumber(stream.get_row(), stream.get_col()))?;
-                            parsed.push(Token::Double(d));
-                            continue;
-                        }
-                        // 0-9
-                        _ if look == None => {
-                            let uint = to_unsigned_integer(digits).ok_or(
-                                ParserError::NotANumber(stream.get_row(), stream.get_col())
-                            )?;
-                            parsed.push(Token::Integer(uint as i16));
+                            let d: f32 = self.src[start..stream.get_position()].parse()
+                                .map_err(|_| stream.error(ParserError::NotANumber))?;
+                            parsed.push((Token::Double(d), position));
                             continue;
                         }
                         // Integer
                         _ => {
-                            let uint = to_unsigned_integer(digits).ok_or(
-                                ParserError::NotANumber(stream.get_row(), stream.get_col())
-                            )?;
                             if let Some(sym) = look {
-                                if !sym.is_ascii() || sym.is_whitespace() || sym == '{' || sym == '}' {
-                                    parsed.push(Token::Integer(uint as i16));
-                                    continue;
+                                if !is_number_terminator(sym) {
+                                    return Err(stream.error(ParserError::NotANumber));
                                 }
-                                return Err(ParserError::NotANumber(stream.get_row(), stream.get_col()));
                             }
-                            parsed.push(Token::Integer(uint as i16));
+                            let uint = to_unsigned_integer(digits)
+                                .ok_or_else(|| stream.error(ParserError::NotANumber))?;
+                            let int = i16::try_from(uint)
+                                .map_err(|_| stream.error(ParserError::NumberRangeError))?;
+                            parsed.push((Token::Integer(int), position));
                             continue;
                         }
                     }
                 }
 
                 '-' => {
-                    let digits: Vec<char> = stream.next_while(|c| c.is_ascii_digit());
+                    let digits = stream.next_while(|c| c.is_ascii_digit());
                     if digits.is_empty() {
-                        return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                        return Err(stream.error(ParserError::UnknownToken));
                     }
-                    let look = stream.look(1);
-                    match look {
+                    match stream.look() {
                         // negative double
                         Some('.') => {
                             let _ = stream.next();
                             let digits_opt = stream.next_while(|c| c.is_ascii_digit());
                             if digits_opt.is_empty() {
-                                return Err(ParserError::UnknownToken(stream.get_row(), stream.get_col()));
+                                return Err(stream.error(ParserError::UnknownToken));
                             }
-                            let d = to_unsigned_f32(digits, digits_opt)
-                                .ok_or(ParserError::NumberRangeError(stream.get_row(), stream.get_col()))?;
-                            if let Some(sym_snd) = stream.look(1) {
-                                if !sym_snd.is_ascii() || sym_snd.is_whitespace() || sym_snd == '{' || sym_snd == '}' {
-                                    parsed.push(Token::Double(-d));
-                                    continue;
-                                }
-                            }
-                            parsed.push(Token::Double(-d));
-                            continue;
-                        }
-                        // negative integer
-                        None => {
-                            let uint = to_unsigned_integer(digits).ok_or(
-                                ParserError::NotANumber(stream.get_row(), stream.get_col())
-                            )?;
-                            parsed.push(Token::Integer(-(uint as i16)));
+                            let d: f32 = self.src[start..stream.get_position()].parse()
+                                .map_err(|_| stream.error(ParserError::NumberRangeError))?;
+                            parsed.push((Token::Double(d), position));
                             continue;
                         }
                         // negative integer
-                        Some(sym) => {
-                            if !sym.is_ascii() || sym.is_whitespace() || sym == '{' || sym == '}' {
-                                let uint = to_unsigned_integer(digits).ok_or(
-                                    ParserError::NotANumber(stream.get_row(), stream.get_col())
-                                )?;
-                                parsed.push(Token::Integer(-(uint as i16)));
-                                continue;
+                        look => {
+                            if let Some(sym) = look {
+                                if !is_number_terminator(sym) {
+                                    return Err(stream.error(ParserError::NotANumber));
+                                }
                             }
-                            return Err(ParserError::NotANumber(stream.get_row(), stream.get_col()));
+                            let uint = to_unsigned_integer(digits)
+                                .ok_or_else(|| stream.error(ParserError::NotANumber))?;
+                            let int = i64::try_from(uint).ok()
+                                .and_then(|uint| i16::try_from(-uint).ok())
+                                .ok_or_else(|| stream.error(ParserError::NumberRangeError))?;
+                            parsed.push((Token::Integer(int), position));
+                            continue;
                         }
                     }
                 }
                 // string for user
                 ch if ch.is_ascii_alphabetic() => {
-                    let mut v = vec!(ch);
-                    v.append(&mut stream.next_while(|ch| ch.is_ascii_alphabetic()));
-                    parsed.push(Token::Name(v.iter().collect()))
+                    let _ = stream.next_while(is_mig_name_char);
+                    parsed.push((Token::Name(self.src[start..stream.get_position()].to_string()), position))
                 }
                 _ => { continue; /* change to ParseError::UnknownToken*/ }
             }
@@ -439,29 +422,93 @@ impl Parser {
         }
 
 
-        let seq = Sequence::from(parsed);
+        let (tokens, positions) = parsed.into_iter().unzip();
+        let seq = Sequence::new(tokens, positions).with_comments(comments);
         return Ok(seq);
     }
 }
 
-// type Data = Vec<char> とか？
-fn to_unsigned_integer(v: Vec<char>) -> Option<usize> {
-    if v.is_empty() { return None; }
-    let s = v.iter()
-        .map(|c| c.to_digit(10))
-        .fold(Some(0), |acc, d|
-            if acc.and(d).is_some() { Some(10 * acc.unwrap() + d.unwrap()) } else { None },
-        );
-    return s.and_then(|s| Some(s as usize));
+fn to_unsigned_integer(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) { return None; }
+    return s.parse().ok();
+}
+
+// the range of year which the most databases can store as DATE
+const MIN_YEAR: usize = 1000;
+const MAX_YEAR: usize = 9999;
+
+fn to_date(y: usize, m: usize, d: usize) -> Option<NaiveDate> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&y) {
+        return None;
+    }
+    return NaiveDate::from_ymd_opt(y as i32, m as u32, d as u32);
+}
+
+// hms's form is 00:00:00. Stream head is the fraction of the second if it exists.
+fn to_time(stream: &mut Stream, hms: &str) -> Result<NaiveTime, ParserError> {
+    let h = to_unsigned_integer(&hms[0..2])
+        .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+    if h >= 24 {
+        return Err(stream.error(ParserError::NumberRangeError));
+    }
+    let m = to_unsigned_integer(&hms[3..5])
+        .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+    if m >= 60 {
+        return Err(stream.error(ParserError::NumberRangeError));
+    }
+    let s = to_unsigned_integer(&hms[6..8])
+        .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+    // leap second is only at the end of the minute. e.g. 23:59:60
+    if s > 60 || (s == 60 && m != 59) {
+        return Err(stream.error(ParserError::NumberRangeError));
+    }
+
+    let mut nano = 0u32;
+    if stream.look() == Some('.') {
+        let _ = stream.next();
+        let fraction = stream.next_while(|c| c.is_ascii_digit());
+        // chrono's precision is nano second
+        if fraction.is_empty() || fraction.len() > 9 {
+            return Err(stream.error(ParserError::NotANumber));
+        }
+        let f = to_unsigned_integer(fraction)
+            .ok_or_else(|| stream.error(ParserError::NotANumber))?;
+        nano = (f * 10_usize.pow(9 - fraction.len() as u32)) as u32;
+    }
+
+    // chrono expresses the leap second as 59 second with over 1_000_000_000 nano seconds
+    let time = if s == 60 {
+        NaiveTime::from_hms_nano_opt(h as u32, m as u32, 59, 1_000_000_000 + nano)
+    } else {
+        NaiveTime::from_hms_nano_opt(h as u32, m as u32, s as u32, nano)
+    };
+    return time.ok_or_else(|| stream.error(ParserError::NumberRangeError));
 }
 
-fn to_unsigned_f32(head: Vec<char>, tail: Vec<char>) -> Option<f32> {
-    let d_h = to_unsigned_integer(head);
-    let l = tail.len();
-    let d_opt = to_unsigned_integer(tail);
-    if d_h.and(d_opt) != None {
-        let d = d_h.unwrap() as f32 + (d_opt.unwrap() as f32 / 10_i32.pow(l as u32) as f32);
-        return Some(d);
+// Stream head is the timezone offset of DateTime if it exists.
+fn to_offset(stream: &mut Stream) -> Result<Option<FixedOffset>, ParserError> {
+    let sign = match stream.look() {
+        Some('Z') => {
+            let _ = stream.next();
+            return Ok(FixedOffset::east_opt(0));
+        }
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Ok(None),
+    };
+    let _ = stream.next();
+    let cs = stream.next_while(|c| c.is_ascii_digit() || c == ':');
+    if !(cs.len() == 5 && &cs[2..3] == ":") {
+        return Err(stream.error(ParserError::UnknownToken));
+    }
+    let h = to_unsigned_integer(&cs[0..2])
+        .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+    let m = to_unsigned_integer(&cs[3..5])
+        .ok_or_else(|| stream.error(ParserError::UnknownToken))?;
+    if h >= 24 || m >= 60 {
+        return Err(stream.error(ParserError::NumberRangeError));
     }
-    return None;
+    let offset = FixedOffset::east_opt(sign * (h * 3600 + m * 60) as i32)
+        .ok_or_else(|| stream.error(ParserError::NumberRangeError))?;
+    return Ok(Some(offset));
 }
@@ -0,0 +1,499 @@
+use std::fmt::{Display, Error, Formatter};
+
+use crate::app::converter::ast::{Arg, Block, ColumnNode, Command, File, Node, OptionNode};
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Schema, Table, Value};
+use crate::app::converter::token::Token;
+
+pub fn semantic_analyzer(file: &File) -> Result<Schema, SemanticError> {
+    let mut schema = Schema::default();
+    for command in file.commands.iter() {
+        let table = analyze_command(command)?;
+        if schema.get_table(&table.name).is_some() {
+            let (row, col) = command.position;
+            return Err(SemanticError::DuplicatedTable(table.name, row, col));
+        }
+        schema.tables.push(table);
+    }
+    return Ok(schema);
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SemanticError {
+    UnknownCommand(String, u32, u32),
+    DuplicatedTable(String, u32, u32),
+    NoColumn(String, u32, u32),
+    DuplicatedColumn(String, u32, u32),
+    UnknownColumnOption(String, u32, u32),
+    UnknownTableOption(String, u32, u32),
+    UnexpectedBlock(String, u32, u32),
+    InvalidParams(String, u32, u32),
+    NoColumnType(String, u32, u32),
+    DuplicatedColumnType(String, u32, u32),
+    InvalidDefault(String, u32, u32),
+    NoTarget(String, u32, u32),
+    UnknownTargetColumn(String, u32, u32),
+    UnsignedNotNumber(String, u32, u32),
+}
+
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SemanticError::UnknownCommand(s, row, col) =>
+                write!(f, ":{} is not command in (row, col) = ({}, {})", s, row, col),
+            SemanticError::DuplicatedTable(s, row, col) =>
+                write!(f, "table {} is already defined. see (row, col) = ({}, {})", s, row, col),
+            SemanticError::NoColumn(s, row, col) =>
+                write!(f, "table {} has no column in (row, col) = ({}, {})", s, row, col),
+            SemanticError::DuplicatedColumn(s, row, col) =>
+                write!(f, "column {} is already defined. see (row, col) = ({}, {})", s, row, col),
+            SemanticError::UnknownColumnOption(s, row, col) =>
+                write!(f, "{} is not column option in (row, col) = ({}, {})", s, row, col),
+            SemanticError::UnknownTableOption(s, row, col) =>
+                write!(f, "{} is not table option in (row, col) = ({}, {})", s, row, col),
+            SemanticError::UnexpectedBlock(s, row, col) =>
+                write!(f, ":{} cannot have block in (row, col) = ({}, {})", s, row, col),
+            SemanticError::InvalidParams(s, row, col) =>
+                write!(f, "parameters of :{} are invalid in (row, col) = ({}, {})", s, row, col),
+            SemanticError::NoColumnType(s, row, col) =>
+                write!(f, "column {} has no type in (row, col) = ({}, {})", s, row, col),
+            SemanticError::DuplicatedColumnType(s, row, col) =>
+                write!(f, "column {} has many types in (row, col) = ({}, {})", s, row, col),
+            SemanticError::InvalidDefault(s, row, col) =>
+                write!(f, "default value does not match the type of column {} in (row, col) = ({}, {})", s, row, col),
+            SemanticError::NoTarget(s, row, col) =>
+                write!(f, ":{} has no :target in (row, col) = ({}, {})", s, row, col),
+            SemanticError::UnknownTargetColumn(s, row, col) =>
+                write!(f, "column {} is not defined in (row, col) = ({}, {})", s, row, col),
+            SemanticError::UnsignedNotNumber(s, row, col) =>
+                write!(f, "column {} is not number but :unsigned in (row, col) = ({}, {})", s, row, col),
+        }
+    }
+}
+
+impl SemanticError {
+    /// stable code for the diagnostics
+    pub fn code(&self) -> &'static str {
+        return match self {
+            SemanticError::UnknownColumnOption(_, _, _) | SemanticError::UnknownTableOption(_, _, _) => "E0001",
+            SemanticError::UnknownCommand(_, _, _) => "E0002",
+            SemanticError::DuplicatedTable(_, _, _) => "E0003",
+            SemanticError::NoColumn(_, _, _) => "E0004",
+            SemanticError::DuplicatedColumn(_, _, _) => "E0005",
+            SemanticError::UnexpectedBlock(_, _, _) => "E0006",
+            SemanticError::InvalidParams(_, _, _) => "E0007",
+            SemanticError::NoColumnType(_, _, _) => "E0008",
+            SemanticError::DuplicatedColumnType(_, _, _) => "E0009",
+            SemanticError::InvalidDefault(_, _, _) => "E0010",
+            SemanticError::NoTarget(_, _, _) => "E0011",
+            SemanticError::UnknownTargetColumn(_, _, _) => "E0012",
+            SemanticError::UnsignedNotNumber(_, _, _) => "E0013",
+        };
+    }
+
+    pub fn get_position(&self) -> (u32, u32) {
+        return match *self {
+            SemanticError::UnknownCommand(_, row, col)
+            | SemanticError::DuplicatedTable(_, row, col)
+            | SemanticError::NoColumn(_, row, col)
+            | SemanticError::DuplicatedColumn(_, row, col)
+            | SemanticError::UnknownColumnOption(_, row, col)
+            | SemanticError::UnknownTableOption(_, row, col)
+            | SemanticError::UnexpectedBlock(_, row, col)
+            | SemanticError::InvalidParams(_, row, col)
+            | SemanticError::NoColumnType(_, row, col)
+            | SemanticError::DuplicatedColumnType(_, row, col)
+            | SemanticError::InvalidDefault(_, row, col)
+            | SemanticError::NoTarget(_, row, col)
+            | SemanticError::UnknownTargetColumn(_, row, col)
+            | SemanticError::UnsignedNotNumber(_, row, col) => (row, col),
+        };
+    }
+}
+
+/// name, parameters and description of the option which we can use in mig-file
+pub struct OptionDoc {
+    pub name: &'static str,
+    pub params: &'static str,
+    pub doc: &'static str,
+}
+
+const fn doc(name: &'static str, params: &'static str, doc: &'static str) -> OptionDoc {
+    return OptionDoc { name, params, doc };
+}
+
+pub const COLUMN_TYPES: &[OptionDoc] = &[
+    doc("increments", "", "auto-incrementing unsigned integer primary key"),
+    doc("big-increments", "", "auto-incrementing unsigned big integer primary key"),
+    doc("tiny-int", "", "tiny integer"),
+    doc("small-int", "", "small integer"),
+    doc("int", "", "integer"),
+    doc("big-int", "", "big integer"),
+    doc("float", "", "single precision floating point number"),
+    doc("double", "", "double precision floating point number"),
+    doc("decimal", "<precision> <scale>", "fixed point number"),
+    doc("boolean", "", "boolean"),
+    doc("char", "<length>", "fixed length string"),
+    doc("string", "[<length>]", "variable length string"),
+    doc("text", "", "long text"),
+    doc("date", "", "date"),
+    doc("time", "", "time"),
+    doc("datetime", "", "date and time"),
+    doc("timestamp", "", "timestamp"),
+    doc("json", "", "json"),
+    doc("binary", "", "binary data"),
+];
+
+pub const COLUMN_MODIFIERS: &[OptionDoc] = &[
+    doc("unsigned", "", "the number column is unsigned"),
+    doc("nullable", "", "the column can be NULL"),
+    doc("non-null", "", "the column cannot be NULL. this is default"),
+    doc("default", "<value>", "default value of the column"),
+    doc("comment", "<string>", "comment of the column"),
+    doc("unique", "", "add unique index of the column"),
+    doc("index", "", "add index of the column"),
+    doc("primary", "", "the column is primary key"),
+    doc("references", "<table> [<column>]", "foreign key to the column of the table. default column is id"),
+];
+
+pub const TABLE_OPTIONS: &[OptionDoc] = &[
+    doc("timestamps", "", "add created_at and updated_at columns"),
+    doc("soft-deletes", "", "add deleted_at column"),
+    doc("index", "{ :target <column>+ :name <string> }", "add index of the columns"),
+    doc("unique-index", "{ :target <column>+ :name <string> }", "add unique index of the columns"),
+    doc("primary-key", "{ :target <column>+ :name <string> }", "the columns are primary key"),
+];
+
+pub const INDEX_OPTIONS: &[OptionDoc] = &[
+    doc("target", "<column>+", "columns of the index"),
+    doc("name", "<string>", "name of the index"),
+];
+
+fn analyze_command(command: &Command) -> Result<Table, SemanticError> {
+    let (row, col) = command.position;
+    let method = match command.name.as_str() {
+        "create" => Method::Create,
+        _ => return Err(SemanticError::UnknownCommand(command.name.clone(), row, col)),
+    };
+
+    let mut table = Table::new(method, &command.table_name);
+    // targets of the indexes are checked after all columns are defined
+    let mut targets: Vec<&Arg> = vec!();
+    for node in command.body.nodes.iter() {
+        match node {
+            Node::Column(column) => analyze_column(&mut table, column)?,
+            Node::Option(option) => analyze_table_option(&mut table, &mut targets, option)?,
+        }
+    }
+
+    if table.columns.is_empty() {
+        return Err(SemanticError::NoColumn(table.name, row, col));
+    }
+    for target in targets {
+        if let Token::Name(name) = &target.value {
+            if !has_column(&table, name) {
+                let (row, col) = target.position;
+                return Err(SemanticError::UnknownTargetColumn(name.clone(), row, col));
+            }
+        }
+    }
+    return Ok(table);
+}
+
+// columns which are made by table options are also target of index
+fn has_column(table: &Table, name: &str) -> bool {
+    return table.get_column(name).is_some()
+        || (table.timestamps && (name == "created_at" || name == "updated_at"))
+        || (table.soft_deletes && name == "deleted_at");
+}
+
+fn analyze_column(table: &mut Table, column: &ColumnNode) -> Result<(), SemanticError> {
+    let (row, col) = column.position;
+    if table.get_column(&column.name).is_some() {
+        return Err(SemanticError::DuplicatedColumn(column.name.clone(), row, col));
+    }
+
+    let mut column_type: Option<(ColumnType, &OptionNode)> = None;
+    let mut modifiers: Vec<&OptionNode> = vec!();
+    for node in column.body.nodes.iter() {
+        let option = to_option(node, SemanticError::UnknownColumnOption)?;
+        no_block(option)?;
+        match to_column_type(option)? {
+            Some(_) if column_type.is_some() => {
+                let (row, col) = option.position;
+                return Err(SemanticError::DuplicatedColumnType(column.name.clone(), row, col));
+            }
+            Some(t) => column_type = Some((t, option)),
+            None => modifiers.push(option),
+        }
+    }
+    let (column_type, type_option) = column_type
+        .ok_or_else(|| SemanticError::NoColumnType(column.name.clone(), row, col))?;
+
+    let mut c = Column::new(&column.name, column_type);
+    // the old form has the default after the type. e.g. :date 1996-07-12
+    if !column_type_has_params(&column_type) && !type_option.args.is_empty() {
+        c.default = Some(to_default(&column.name, &column_type, type_option)?);
+    }
+    for option in modifiers {
+        let kind = match option.name.as_str() {
+            "unsigned" => {
+                no_args(option)?;
+                if !column_type.is_number() {
+                    let (row, col) = option.position;
+                    return Err(SemanticError::UnsignedNotNumber(column.name.clone(), row, col));
+                }
+                c.unsigned = true;
+                continue;
+            }
+            "nullable" => {
+                no_args(option)?;
+                c.nullable = true;
+                continue;
+            }
+            "non-null" => {
+                no_args(option)?;
+                c.nullable = false;
+                continue;
+            }
+            "default" => {
+                c.default = Some(to_default(&column.name, &column_type, option)?);
+                continue;
+            }
+            "comment" => {
+                match one_arg(option)? {
+                    Token::String(s) => c.comment = Some(s.clone()),
+                    _ => return Err(invalid_params(option)),
+                }
+                continue;
+            }
+            "references" => {
+                c.references = Some(to_foreign_key(option)?);
+                continue;
+            }
+            "unique" => IndexKind::Unique,
+            "index" => IndexKind::Index,
+            "primary" => IndexKind::Primary,
+            _ => {
+                let (row, col) = option.position;
+                return Err(SemanticError::UnknownColumnOption(option.name.clone(), row, col));
+            }
+        };
+        no_args(option)?;
+        table.indexes.push(Index { kind, columns: vec!(column.name.clone()), name: None });
+    }
+    table.columns.push(c);
+    return Ok(());
+}
+
+// the value of :default or the type option
+fn to_default(column_name: &str, column_type: &ColumnType, option: &OptionNode) -> Result<Value, SemanticError> {
+    let value = to_value(one_arg(option)?)
+        .ok_or_else(|| invalid_params(option))?;
+    if !column_type.accepts(&value) {
+        let (row, col) = option.position;
+        return Err(SemanticError::InvalidDefault(column_name.to_string(), row, col));
+    }
+    return Ok(value);
+}
+
+// None if the option is not column type
+fn to_column_type(option: &OptionNode) -> Result<Option<ColumnType>, SemanticError> {
+    let column_type = match option.name.as_str() {
+        "increments" => ColumnType::Increments,
+        "big-increments" => ColumnType::BigIncrements,
+        "tiny-int" => ColumnType::TinyInteger,
+        "small-int" => ColumnType::SmallInteger,
+        "int" => ColumnType::Integer,
+        "big-int" => ColumnType::BigInteger,
+        "float" => ColumnType::Float,
+        "double" => ColumnType::Double,
+        "decimal" => {
+            let params = to_unsigned_params(option, 2, 2)?;
+            // the scale is the digits in the precision
+            if params[1] > params[0] {
+                let (row, col) = option.args[1].position;
+                return Err(SemanticError::InvalidParams(option.name.clone(), row, col));
+            }
+            ColumnType::Decimal { precision: params[0], scale: params[1] }
+        }
+        "boolean" => ColumnType::Boolean,
+        "char" => {
+            let params = to_unsigned_params(option, 1, 1)?;
+            ColumnType::Char { length: params[0] }
+        }
+        "string" => {
+            let params = to_unsigned_params(option, 0, 1)?;
+            ColumnType::String { length: params.first().cloned() }
+        }
+        "text" => ColumnType::Text,
+        // :ymd is the old name
+        "date" | "ymd" => ColumnType::Date,
+        "time" => ColumnType::Time,
+        "datetime" => ColumnType::DateTime,
+        "timestamp" => ColumnType::Timestamp,
+        "json" => ColumnType::Json,
+        "binary" => ColumnType::Binary,
+        _ => return Ok(None),
+    };
+    // the type without the params can have the default
+    if !column_type_has_params(&column_type) && option.args.len() > 1 {
+        return Err(invalid_params(option));
+    }
+    return Ok(Some(column_type));
+}
+
+fn to_foreign_key(option: &OptionNode) -> Result<ForeignKey, SemanticError> {
+    let names: Vec<&String> = option.args.iter()
+        .filter_map(|arg| match &arg.value {
+            Token::Name(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    if names.len() != option.args.len() {
+        return Err(invalid_params(option));
+    }
+    return match names.as_slice() {
+        [table] => Ok(ForeignKey { table: table.to_string(), column: "id".to_string() }),
+        [table, column] => Ok(ForeignKey { table: table.to_string(), column: column.to_string() }),
+        _ => Err(invalid_params(option)),
+    };
+}
+
+fn column_type_has_params(column_type: &ColumnType) -> bool {
+    return matches!(column_type, ColumnType::Decimal { .. } | ColumnType::Char { .. } | ColumnType::String { .. });
+}
+
+fn analyze_table_option<'a>(table: &mut Table, targets: &mut Vec<&'a Arg>, option: &'a OptionNode)
+                            -> Result<(), SemanticError> {
+    let kind = match option.name.as_str() {
+        "timestamps" => {
+            no_args(option)?;
+            no_block(option)?;
+            table.timestamps = true;
+            return Ok(());
+        }
+        "soft-deletes" => {
+            no_args(option)?;
+            no_block(option)?;
+            table.soft_deletes = true;
+            return Ok(());
+        }
+        "index" => IndexKind::Index,
+        "unique-index" => IndexKind::Unique,
+        "primary-key" => IndexKind::Primary,
+        _ => {
+            let (row, col) = option.position;
+            return Err(SemanticError::UnknownTableOption(option.name.clone(), row, col));
+        }
+    };
+    no_args(option)?;
+    let (row, col) = option.position;
+    let body = option.body.as_ref()
+        .ok_or_else(|| SemanticError::NoTarget(option.name.clone(), row, col))?;
+    let index = analyze_index(kind, targets, option, body)?;
+    table.indexes.push(index);
+    return Ok(());
+}
+
+fn analyze_index<'a>(kind: IndexKind, targets: &mut Vec<&'a Arg>, option: &OptionNode, body: &'a Block)
+                     -> Result<Index, SemanticError> {
+    let mut index = Index { kind, columns: vec!(), name: None };
+    for node in body.nodes.iter() {
+        let index_option = to_option(node, SemanticError::UnknownTableOption)?;
+        no_block(index_option)?;
+        match index_option.name.as_str() {
+            "target" => {
+                for arg in index_option.args.iter() {
+                    match &arg.value {
+                        Token::Name(name) => index.columns.push(name.clone()),
+                        _ => return Err(invalid_params(index_option)),
+                    }
+                    targets.push(arg);
+                }
+            }
+            "name" => {
+                match one_arg(index_option)? {
+                    Token::String(s) => index.name = Some(s.clone()),
+                    _ => return Err(invalid_params(index_option)),
+                }
+            }
+            _ => {
+                let (row, col) = index_option.position;
+                return Err(SemanticError::UnknownTableOption(index_option.name.clone(), row, col));
+            }
+        }
+    }
+    if index.columns.is_empty() {
+        let (row, col) = option.position;
+        return Err(SemanticError::NoTarget(option.name.clone(), row, col));
+    }
+    return Ok(index);
+}
+
+fn to_option(node: &Node, e: fn(String, u32, u32) -> SemanticError) -> Result<&OptionNode, SemanticError> {
+    return match node {
+        Node::Option(option) => Ok(option),
+        Node::Column(column) => {
+            let (row, col) = column.position;
+            Err(e(column.name.clone(), row, col))
+        }
+    };
+}
+
+fn to_value(token: &Token) -> Option<Value> {
+    return match token {
+        Token::Integer(i) => Some(Value::Integer(i64::from(*i))),
+        // through the string to keep the written digits. e.g. 0.1f32 is not 0.1f64
+        Token::Double(d) => d.to_string().parse().ok().map(Value::Double),
+        Token::String(s) => Some(Value::String(s.clone())),
+        Token::Ymd(d) => Some(Value::Date(*d)),
+        Token::Time(t) => Some(Value::Time(*t)),
+        Token::DateTime(dt, None) => Some(Value::DateTime(*dt)),
+        Token::DateTime(_, Some(_)) => token.to_date_time_with_offset().map(Value::DateTimeTz),
+        _ => None,
+    };
+}
+
+fn invalid_params(option: &OptionNode) -> SemanticError {
+    let (row, col) = option.args.first().map_or(option.position, |arg| arg.position);
+    return SemanticError::InvalidParams(option.name.clone(), row, col);
+}
+
+fn no_args(option: &OptionNode) -> Result<(), SemanticError> {
+    if option.args.is_empty() {
+        return Ok(());
+    }
+    return Err(invalid_params(option));
+}
+
+fn no_block(option: &OptionNode) -> Result<(), SemanticError> {
+    if let Some(body) = &option.body {
+        let (row, col) = body.position;
+        return Err(SemanticError::UnexpectedBlock(option.name.clone(), row, col));
+    }
+    return Ok(());
+}
+
+fn one_arg(option: &OptionNode) -> Result<&Token, SemanticError> {
+    if option.args.len() == 1 {
+        return Ok(&option.args[0].value);
+    }
+    return Err(invalid_params(option));
+}
+
+fn to_unsigned_params(option: &OptionNode, min: usize, max: usize) -> Result<Vec<u16>, SemanticError> {
+    if option.args.len() < min || option.args.len() > max {
+        return Err(invalid_params(option));
+    }
+    let mut params = vec!();
+    for arg in option.args.iter() {
+        match arg.value {
+            Token::Integer(i) if i >= 0 => params.push(i as u16),
+            _ => {
+                let (row, col) = arg.position;
+                return Err(SemanticError::InvalidParams(option.name.clone(), row, col));
+            }
+        }
+    }
+    return Ok(params);
+}
@@ -1,13 +1,16 @@
 use std::fmt::{Display, Error, Formatter};
 
-use crate::app::converter::ConverterError;
-use crate::app::converter::token::Token;
+use crate::app::converter::ast::{Arg, Block, ColumnNode, Command, File, Node, OptionNode};
+use crate::app::converter::token::{Sequence, Token};
+
+pub fn syntax_analyzer(seq: &Sequence) -> Result<File, SyntaxError> {
+    return SyntaxParser::new(seq).parse_file();
+}
 
 #[derive(Debug)]
 pub enum SyntaxError {
-    NoOption(Token),
-    UnknownOptionName(Token),
-    UnknownOptionParam(Token),
+    UnexpectedToken(Token, u32, u32),
+    NotClosedBlock(u32, u32),
     TooShort,
     UnknownError,
 }
@@ -15,11 +18,152 @@ pub enum SyntaxError {
 impl Display for SyntaxError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
-            SyntaxError::NoOption(t) => write!(f, "{:?} has no option", t),
             SyntaxError::TooShort => write!(f, "input file has not enough num of tokens"),
             SyntaxError::UnknownError => write!(f, "occurred unknown syntax error"),
-            SyntaxError::UnknownOptionName(t) => write!(f, "{:?} is not option name", t),
-            SyntaxError::UnknownOptionParam(t) => write!(f, "{:?} is not option parameter", t),
+            SyntaxError::UnexpectedToken(t, row, col) =>
+                write!(f, "{:?} is unexpected in (row, col) = ({}, {})", t, row, col),
+            SyntaxError::NotClosedBlock(row, col) =>
+                write!(f, "block is not closed from (row, col) = ({}, {})", row, col),
+        }
+    }
+}
+
+impl SyntaxError {
+    /// stable code for the diagnostics
+    pub fn code(&self) -> &'static str {
+        return match self {
+            SyntaxError::UnexpectedToken(_, _, _) => "E0201",
+            SyntaxError::NotClosedBlock(_, _) => "E0202",
+            SyntaxError::TooShort => "E0203",
+            SyntaxError::UnknownError => "E0204",
+        };
+    }
+
+    pub fn get_position(&self) -> Option<(u32, u32)> {
+        return match *self {
+            SyntaxError::UnexpectedToken(_, row, col) | SyntaxError::NotClosedBlock(row, col) =>
+                Some((row, col)),
+            SyntaxError::TooShort | SyntaxError::UnknownError => None,
+        };
+    }
+}
+
+/// recursive-descent parser from token sequence to AST
+struct SyntaxParser<'a> {
+    seq: &'a Sequence,
+    // index of the next token
+    index: usize,
+}
+
+impl<'a> SyntaxParser<'a> {
+    fn new(seq: &'a Sequence) -> Self {
+        return SyntaxParser { seq, index: 0 };
+    }
+
+    fn look(&self) -> Option<&'a Token> {
+        return self.seq.get_tokens().get(self.index);
+    }
+
+    fn look_second(&self) -> Option<&'a Token> {
+        return self.seq.get_tokens().get(self.index + 1);
+    }
+
+    fn next(&mut self) -> Option<(&'a Token, (u32, u32))> {
+        let t = self.look()?;
+        let position = self.seq.get_position(self.index);
+        self.index += 1;
+        return Some((t, position));
+    }
+
+    fn unexpected(&self) -> SyntaxError {
+        return match self.look() {
+            Some(t) => {
+                let (row, col) = self.seq.get_position(self.index);
+                SyntaxError::UnexpectedToken(t.clone(), row, col)
+            }
+            None => SyntaxError::TooShort,
+        };
+    }
+
+    // file = command+
+    fn parse_file(&mut self) -> Result<File, SyntaxError> {
+        let mut commands = vec!();
+        while self.look().is_some() {
+            commands.push(self.parse_command()?);
+        }
+        if commands.is_empty() {
+            return Err(SyntaxError::TooShort);
+        }
+        return Ok(File { commands, comments: self.seq.get_comments().to_vec() });
+    }
+
+    // command = NameColon Name block
+    fn parse_command(&mut self) -> Result<Command, SyntaxError> {
+        let (name, position) = match self.look() {
+            Some(Token::NameColon(name)) => (name.clone(), self.next().unwrap().1),
+            _ => return Err(self.unexpected()),
+        };
+        let table_name = match self.look() {
+            Some(Token::Name(table_name)) => table_name.clone(),
+            _ => return Err(self.unexpected()),
+        };
+        let _ = self.next();
+        let body = self.parse_block()?;
+        return Ok(Command { name, table_name, body, position });
+    }
+
+    // block = { (column | option)* }
+    fn parse_block(&mut self) -> Result<Block, SyntaxError> {
+        let position = match self.look() {
+            Some(Token::LMidParen) => self.next().unwrap().1,
+            _ => return Err(self.unexpected()),
+        };
+        let mut nodes = vec!();
+        loop {
+            match self.look() {
+                None => return Err(SyntaxError::NotClosedBlock(position.0, position.1)),
+                Some(Token::RMidParen) => {
+                    let end = self.next().unwrap().1;
+                    return Ok(Block { nodes, position, end });
+                }
+                Some(Token::Name(_)) => nodes.push(Node::Column(self.parse_column()?)),
+                Some(Token::NameColon(_)) => nodes.push(Node::Option(self.parse_option()?)),
+                Some(_) => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    // column = Name block
+    fn parse_column(&mut self) -> Result<ColumnNode, SyntaxError> {
+        let (name, position) = match self.next() {
+            Some((Token::Name(name), position)) => (name.clone(), position),
+            _ => return Err(SyntaxError::UnknownError),
+        };
+        let body = self.parse_block()?;
+        return Ok(ColumnNode { name, body, position });
+    }
+
+    // option = NameColon arg* block?
+    // arg is value or Name which is not head of column
+    fn parse_option(&mut self) -> Result<OptionNode, SyntaxError> {
+        let (name, position) = match self.next() {
+            Some((Token::NameColon(name), position)) => (name.clone(), position),
+            _ => return Err(SyntaxError::UnknownError),
+        };
+        let mut args = vec!();
+        while let Some(t) = self.look() {
+            let is_column_head = t.is_name()
+                && self.look_second().is_some_and(|t| t.is_l_mid_paren());
+            if t.is_l_mid_paren() || t.is_r_mid_paren() || t.is_name_colon() || is_column_head {
+                break;
+            }
+            let (value, position) = self.next().unwrap();
+            args.push(Arg { value: value.clone(), position });
         }
+        let body = match self.look() {
+            Some(Token::LMidParen) => Some(self.parse_block()?),
+            _ => None,
+        };
+        return Ok(OptionNode { name, args, body, position });
     }
 }
@@ -1,7 +1,9 @@
-use crate::app::converter::ConverterError::Syntax;
-use crate::app::converter::mig::Mig;
-use crate::app::converter::syntax::SyntaxError;
-use crate::app::helper::slice_helper::split_with_head_and_separator;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+use crate::app::converter::ConverterError;
+use crate::app::converter::mig::Schema;
+use crate::app::converter::semantic::semantic_analyzer;
+use crate::app::converter::syntax::syntax_analyzer;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
@@ -11,9 +13,10 @@ pub enum Token {
     // table, column
     NameColon(String),
     // command, opt
-    Ymd(u16, u8, u8),
-    Time(u8, u8, u8),
-    DateTime(u16, u8, u8, u8, u8, u8),
+    Ymd(NaiveDate),
+    Time(NaiveTime),
+    // offset is None when the literal has no timezone
+    DateTime(NaiveDateTime, Option<FixedOffset>),
     Integer(i16),
     Double(f32),
     String(String),  // "..."
@@ -21,130 +24,76 @@ pub enum Token {
 
 impl Token {
     pub fn is_l_mid_paren(&self) -> bool {
-        return match &self {
-            Token::LMidParen => true,
-            _ => false,
-        };
+        return matches!(self, Token::LMidParen);
     }
 
     pub fn is_r_mid_paren(&self) -> bool {
-        return match &self {
-            Token::RMidParen => true,
-            _ => false,
-        };
+        return matches!(self, Token::RMidParen);
     }
 
     pub fn is_name(&self) -> bool {
-        return match &self {
-            Token::Name(_) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Name(_));
     }
 
-    pub fn is_name_with(&self, name: String) -> bool {
-        return match &self {
-            Token::Name(name) => true,
-            _ => false,
-        };
+    pub fn is_name_with(&self, name: &str) -> bool {
+        return matches!(self, Token::Name(s) if s == name);
     }
 
     pub fn is_name_colon(&self) -> bool {
-        return match &self {
-            Token::NameColon(_) => true,
-            _ => false,
-        };
+        return matches!(self, Token::NameColon(_));
     }
 
-    pub fn is_name_colon_with(&self, name_colon: String) -> bool {
-        return match &self {
-            &Token::NameColon(name_colon) => true,
-            _ => false,
-        };
+    pub fn is_name_colon_with(&self, name_colon: &str) -> bool {
+        return matches!(self, Token::NameColon(s) if s == name_colon);
     }
 
     pub fn is_ymd(&self) -> bool {
-        return match &self {
-            Token::Ymd(_, _, _) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Ymd(_));
     }
 
-    pub fn is_ymd_with(&self, year: u16, month: u8, day: u8) -> bool {
-        return match &self {
-            &Token::Ymd(year, month, day) => true,
-            _ => false,
-        };
+    pub fn is_ymd_with(&self, date: NaiveDate) -> bool {
+        return matches!(self, Token::Ymd(d) if *d == date);
     }
 
     pub fn is_time(&self) -> bool {
-        return match &self {
-            Token::Time(_, _, _) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Time(_));
     }
 
-    pub fn is_time_with(&self, hour: u8, minute: u8, second: u8) -> bool {
-        return match &self {
-            &Token::Time(hour, minute, second) => true,
-            _ => false,
-        };
+    pub fn is_time_with(&self, time: NaiveTime) -> bool {
+        return matches!(self, Token::Time(t) if *t == time);
     }
 
     pub fn is_date_time(&self) -> bool {
-        return match &self {
-            Token::DateTime(_, _, _, _, _, _) => true,
-            _ => false,
-        };
+        return matches!(self, Token::DateTime(_, _));
     }
 
-    pub fn is_date_time_with(&self, year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8)
-                             -> bool {
-        return match &self {
-            &Token::DateTime(year, month, day, hour, minute, second) => true,
-            _ => false,
-        };
+    pub fn is_date_time_with(&self, date_time: NaiveDateTime, offset: Option<FixedOffset>) -> bool {
+        return matches!(self, Token::DateTime(dt, o) if *dt == date_time && *o == offset);
     }
 
     pub fn is_integer(&self) -> bool {
-        return match &self {
-            Token::Integer(_) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Integer(_));
     }
 
     pub fn is_integer_with(&self, int: i16) -> bool {
-        return match &self {
-            &Token::Integer(int) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Integer(i) if *i == int);
     }
 
     pub fn is_double(&self) -> bool {
-        return match &self {
-            Token::Double(_) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Double(_));
     }
 
+    #[allow(clippy::float_cmp)]
     pub fn is_double_with(&self, dbl: f32) -> bool {
-        return match &self {
-            &Token::Double(dbl) => true,
-            _ => false,
-        };
+        return matches!(self, Token::Double(d) if *d == dbl);
     }
 
     pub fn is_string(&self) -> bool {
-        return match &self {
-            Token::String(_) => true,
-            _ => false,
-        };
+        return matches!(self, Token::String(_));
     }
 
-    pub fn is_string_with(&self, s: String) -> bool {
-        return match &self {
-            &Token::String(s) => true,
-            _ => false,
-        };
+    pub fn is_string_with(&self, s: &str) -> bool {
+        return matches!(self, Token::String(string) if string == s);
     }
 
     pub fn is_column_option(&self) -> bool {
@@ -167,185 +116,103 @@ impl Token {
                 || self.is_ymd()
                 || self.is_name();
     }
+
+    /// date with the timezone offset when the literal has one.
+    pub fn to_date_time_with_offset(&self) -> Option<DateTime<FixedOffset>> {
+        return match self {
+            Token::DateTime(dt, Some(offset)) =>
+                offset.from_local_datetime(dt).single(),
+            _ => None,
+        };
+    }
+
+    /// text of the token in mig-file. lexical_analyzer parses it to the same token.
+    pub fn to_source(&self) -> String {
+        return match self {
+            Token::LMidParen => "{".to_string(),
+            Token::RMidParen => "}".to_string(),
+            Token::Name(name) => name.clone(),
+            Token::NameColon(name) => format!(":{}", name),
+            Token::Ymd(date) => date.format("%Y-%m-%d").to_string(),
+            Token::Time(time) => time.format("%H:%M:%S%.f").to_string(),
+            Token::DateTime(dt, offset) => {
+                let date_time = dt.format("%Y-%m-%d_%H:%M:%S%.f").to_string();
+                match offset {
+                    Some(offset) if offset.local_minus_utc() == 0 => format!("{}Z", date_time),
+                    Some(offset) => {
+                        let seconds = offset.local_minus_utc();
+                        let sign = if seconds < 0 { '-' } else { '+' };
+                        let minutes = seconds.abs() / 60;
+                        format!("{}{}{:02}:{:02}", date_time, sign, minutes / 60, minutes % 60)
+                    }
+                    None => date_time,
+                }
+            }
+            Token::Integer(int) => int.to_string(),
+            Token::Double(dbl) => {
+                // keep the point not to be Integer
+                let s = dbl.to_string();
+                if s.contains('.') { s } else { format!("{}.0", s) }
+            }
+            Token::String(s) => format!("\"{}\"", s),
+        };
+    }
+}
+
+/// `# text` until the end of the line. it is not a token, but kept for the formatter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// after `#` without the newline
+    pub text: String,
+    pub position: (u32, u32),
+    /// a token is before the comment in the same line
+    pub trailing: bool,
 }
 
 #[derive(Debug)]
 pub struct Sequence {
-    seq: Vec<Token>
+    seq: Vec<Token>,
+    // (row, col) of the head of each token
+    positions: Vec<(u32, u32)>,
+    comments: Vec<Comment>,
 }
 
 impl From<Vec<Token>> for Sequence {
     fn from(vec: Vec<Token>) -> Sequence {
-        return Sequence { seq: vec };
+        let positions = vec![(0, 0); vec.len()];
+        return Sequence { seq: vec, positions, comments: vec!() };
     }
 }
 
 impl Sequence {
-    pub fn get_token(&self, i: usize) -> Token {
-        return self.seq[i].clone();
+    pub fn new(seq: Vec<Token>, positions: Vec<(u32, u32)>) -> Self {
+        return Sequence { seq, positions, comments: vec!() };
     }
 
-    pub fn get_tokens(&self) -> Vec<Token> {
-        return self.seq.clone();
+    pub fn with_comments(self, comments: Vec<Comment>) -> Self {
+        return Sequence { comments, ..self };
     }
 
-    pub fn analyze_syntax(&self) -> Result<Mig, SyntaxError> {
-        //println!("\nparse data is:");
-        let mut tokens = self.get_tokens();
-        if tokens.len() < 5 { return Err(SyntaxError::TooShort); }
-        let mut mig = Mig::new();
-        // table name check
-        if tokens[0].is_name_colon_with("create".to_string())
-            & &tokens[1].is_name()
-            & &tokens[2].is_l_mid_paren()
-            & &tokens[tokens.len() - 1].is_r_mid_paren()
-        {
-            // set table params of Mig
-            mig.set_method(tokens[0].clone());
-            mig.set_table_name(tokens[1].clone());
-
-            let l = tokens.len();
-            let res = analyze_columns_or_table_options(&mut mig, &mut tokens[3..l - 1].to_vec());
-            return res.and_then(|mig| Ok(mig.clone()));
-        }
-        println!("  {:?}", mig);
-        return Err(SyntaxError::UnknownError);
+    pub fn get_comments(&self) -> &[Comment] {
+        return &self.comments;
     }
-}
-
-fn analyze_columns_or_table_options<'a>(mig: &'a mut Mig, tokens: &mut Vec<Token>) -> Result<&'a mut Mig, SyntaxError> {
-    let seq = tokens.clone();
-    match &seq[0] {
-        // columns is Name { many1 option }
-        t @ Token::Name(_) => {
-            if seq[1].is_l_mid_paren() {
-                let mut separated: Vec<Vec<Token>> = vec!();
-                // split at last of first option from first left mid -paren
-                for group in seq[2..].splitn(2, |t| t.is_r_mid_paren()) {
-                    separated.push(group.to_vec());
-                }
 
-                println!("  {:?}:  {:?}", t, separated[0]);
-
-                let column_options = &(separated[0]);
-                let others = &(separated[1]);
-
-                if column_options.len() == 0 {
-                    return Err(SyntaxError::NoOption(t.clone()));
-                }
-
-                if others.len() == 0 {
-                    return analyze_column(mig, t.clone(), &mut column_options.clone());
-                }
-                return
-                    analyze_column(mig, t.clone(), &mut column_options.clone())
-                        .and_then(|mig| analyze_columns_or_table_options(mig, &mut others.clone()));
-            }
-            return Err(SyntaxError::UnknownError);
-        }
-        // table_option is NameColon { many1 option and option has Name } or NameColon
-        t @ Token::NameColon(_) => {
-            if seq[1].is_l_mid_paren() {
-                let mut separated: Vec<Vec<Token>> = vec!();
-                for group in seq[2..].splitn(2, |t| t.is_r_mid_paren()) {
-                    separated.push(group.to_vec());
-                }
-
-                println!("  {:?}:  {:?}", t, separated[0]);
-
-                let table_options = &(separated[0]);
-                let others = &(separated[1]);
-
-                if table_options.len() == 0 {
-                    return Err(SyntaxError::NoOption(t.clone()));
-                }
-
-                if others.len() == 0 {
-                    return analyze_table(mig, t.clone(), &mut table_options.clone());
-                }
-                return
-                    analyze_table(mig, t.clone(), &mut table_options.clone())
-                        .and_then(|mig| analyze_columns_or_table_options(mig, &mut others.clone()));
-            }
-            println!("  {:?}:  No Options", t);
-            mig.add_table_options(t.clone(), &mut vec!());
-            let mut seq_dummy = seq[1..].to_vec().clone();
-            return analyze_columns_or_table_options(mig, &mut seq_dummy);
-        }
-        _ => { Err(SyntaxError::UnknownError) }
+    pub fn get_token(&self, i: usize) -> Token {
+        return self.seq[i].clone();
     }
-}
 
-fn analyze_column<'a>(mig: &'a mut Mig, token: Token, column_options: &mut Vec<Token>) -> Result<&'a mut Mig, SyntaxError> {
-    let r = split_with_head_and_separator(&column_options, |t| t.is_name_colon());
-    //println!("{:?}", r);
-    let mut options = r.1.clone();
-    let head = r.0;
-    if head.is_empty() {
-        return analyze_column_options(mig, token, &mut options);
+    pub fn get_tokens(&self) -> &[Token] {
+        return &self.seq;
     }
-    return Err(SyntaxError::UnknownOptionName(head[0].clone()));
-}
 
-fn analyze_table<'a>(mig: &'a mut Mig, token: Token, table_options: &mut Vec<Token>) -> Result<&'a mut Mig, SyntaxError> {
-    let r = split_with_head_and_separator(&table_options, |t| t.is_name_colon());
-    //println!("{:?}", r);
-    let mut options = r.1.clone();
-    let head = r.0;
-
-    if head.is_empty() {
-        return analyze_table_options(mig, token, &mut options);
+    pub fn get_position(&self, i: usize) -> (u32, u32) {
+        return self.positions[i];
     }
-    return Err(SyntaxError::UnknownOptionName(head[0].clone()));
-}
 
-fn analyze_table_options<'a>(mig: &'a mut Mig, token: Token, options: &mut Vec<(Token, Vec<Token>)>) -> Result<&'a mut Mig, SyntaxError> {
-    let mut token_s: Vec<(String, Vec<Token>)> = vec!();
-    for (name, option_params) in options.iter() {
-        //println!("{:?}", (name, option_params));
-        if name.is_name_colon() {
-            for param in option_params.iter() {
-                if param.is_table_option() {
-                    continue;
-                }
-                //println!("{:?}", param);
-                return Err(SyntaxError::UnknownOptionParam(param.clone()));
-            }
-
-            match name {
-                Token::NameColon(s) => token_s.push((s.to_string(), option_params.clone())),
-                _ => unreachable!(),
-            }
-            continue;
-        }
-        return Err(SyntaxError::UnknownOptionName(name.clone()));
+    /// syntax and semantic analysis
+    pub fn analyze_syntax(&self) -> Result<Schema, ConverterError> {
+        let file = syntax_analyzer(self)?;
+        let schema = semantic_analyzer(&file)?;
+        return Ok(schema);
     }
-
-    mig.add_table_options(token, &mut token_s);
-    return Ok(mig);
 }
-
-fn analyze_column_options<'a>(mig: &'a mut Mig, token: Token, options: &mut Vec<(Token, Vec<Token>)>) -> Result<&'a mut Mig, SyntaxError> {
-    let mut token_s: Vec<(String, Vec<Token>)> = vec!();
-    for (name, option_params) in options.iter() {
-        //println!("{:?}", (name, option_params));
-        if name.is_name_colon() {
-            for param in option_params.iter() {
-                if param.is_column_option() {
-                    continue;
-                }
-                //println!("{:?}", param);
-                return Err(SyntaxError::UnknownOptionParam(param.clone()));
-            }
-
-            match name {
-                Token::NameColon(s) => token_s.push((s.to_string(), option_params.clone())),
-                _ => unreachable!(),
-            }
-            continue;
-        }
-        return Err(SyntaxError::UnknownOptionName(name.clone()));
-    }
-    mig.add_column_options(token, &mut token_s);
-    return Ok(mig);
-}
\ No newline at end of file
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppError;
+use crate::app::converter::ConverterError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// one error for `--message-format json`. line and column start from 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn from_converter_error(file: Option<&str>, e: &ConverterError) -> Self {
+        let position = e.get_position();
+        return Diagnostic {
+            file: file.map(|f| f.to_string()),
+            line: position.map(|(row, _)| row),
+            column: position.map(|(_, col)| col),
+            severity: Severity::Error,
+            code: e.code().to_string(),
+            message: e.to_string(),
+        };
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).expect("diagnostic has only string keys");
+    }
+}
+
+/// errors of the files are separated with the paths
+pub fn to_diagnostics(e: &AppError) -> Vec<Diagnostic> {
+    return to_diagnostics_of_file(None, e);
+}
+
+fn to_diagnostics_of_file(file: Option<&str>, e: &AppError) -> Vec<Diagnostic> {
+    return match e {
+        AppError::InvalidFiles(errors) => errors.iter()
+            .flat_map(|(path, e)| to_diagnostics_of_file(Some(path), e))
+            .collect(),
+        AppError::Converter(c_e) => vec!(Diagnostic::from_converter_error(file, c_e)),
+        _ => vec!(Diagnostic {
+            file: file.map(|f| f.to_string()),
+            line: None,
+            column: None,
+            severity: Severity::Error,
+            code: e.code().to_string(),
+            message: e.to_string(),
+        }),
+    };
+}
+
+/// json lines to stdout for --message-format json, otherwise the message to stderr
+pub fn print_error(e: &AppError, json_message: bool) {
+    if json_message {
+        for diagnostic in to_diagnostics(e) {
+            println!("{}", diagnostic.to_json());
+        }
+        return;
+    }
+    eprintln!("\nConverterError:\n {}", e);
+}
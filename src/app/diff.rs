@@ -0,0 +1,454 @@
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Schema, Table};
+use crate::app::converter::parser::is_mig_name;
+
+// structural comparison of two schemas. the result is the steps from the old schema to the new one.
+
+/// changes of the tables in order to apply
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SchemaDiff {
+    pub tables: Vec<TableDiff>,
+    /// dropped and added names which look renamed
+    pub hints: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        return self.tables.is_empty();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableDiff {
+    Create(Table),
+    // the old definition
+    Drop(Table),
+    Alter(AlterTable),
+}
+
+impl TableDiff {
+    /// name of the table after the change
+    pub fn table_name(&self) -> &str {
+        return match self {
+            TableDiff::Create(table) | TableDiff::Drop(table) => &table.name,
+            TableDiff::Alter(alter) => &alter.name,
+        };
+    }
+
+    /// {method} of the migration name
+    pub fn method(&self) -> &'static str {
+        return match self {
+            TableDiff::Create(_) => "create",
+            TableDiff::Drop(_) => "drop",
+            TableDiff::Alter(_) => "alter",
+        };
+    }
+
+    /// the step of the rollback. Err has the reasons if the data cannot be restored by it.
+    pub fn reverse(&self) -> Result<TableDiff, Vec<String>> {
+        return match self {
+            TableDiff::Create(table) => Ok(TableDiff::Drop(table.clone())),
+            TableDiff::Drop(table) => Ok(TableDiff::Create(table.clone())),
+            TableDiff::Alter(alter) => alter.reverse().map(TableDiff::Alter),
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlterTable {
+    /// the new name
+    pub name: String,
+    /// in order to apply
+    pub changes: Vec<Change>,
+}
+
+impl AlterTable {
+    /// name of the table before the changes
+    pub fn get_old_name(&self) -> &str {
+        return self.changes.iter()
+            .find_map(|change| match change {
+                Change::RenameTable { from } => Some(from.as_str()),
+                _ => None,
+            })
+            .unwrap_or(&self.name);
+    }
+
+    // the inverse changes in reverse order. the table is renamed back at the last.
+    fn reverse(&self) -> Result<AlterTable, Vec<String>> {
+        let mut reasons = vec!();
+        let changes = self.changes.iter().rev()
+            .map(|change| match change {
+                Change::RenameTable { .. } => Change::RenameTable { from: self.name.clone() },
+                Change::DropForeignKey { column, references, .. } =>
+                    Change::AddForeignKey { column: column.clone(), references: references.clone() },
+                Change::DropIndex(index) => Change::AddIndex(index.clone()),
+                Change::RenameColumn { from, to } => Change::RenameColumn { from: to.clone(), to: from.clone() },
+                Change::DropColumn(column) => Change::AddColumn(column.clone()),
+                Change::DropTimestamps => Change::AddTimestamps,
+                Change::DropSoftDeletes => Change::AddSoftDeletes,
+                Change::AddColumn(column) => Change::DropColumn(column.clone()),
+                Change::ChangeColumn { old, new } => {
+                    if is_lossy(old, new) {
+                        reasons.push(format!(
+                            "{}.{} is changed from {} to {} and the data may be lost",
+                            self.name, new.name, to_type_name(old), to_type_name(new),
+                        ));
+                    }
+                    // the column is renamed back after this
+                    Change::ChangeColumn { old: new.clone(), new: Column { name: new.name.clone(), ..old.clone() } }
+                }
+                Change::AddTimestamps => Change::DropTimestamps,
+                Change::AddSoftDeletes => Change::DropSoftDeletes,
+                Change::AddIndex(index) => Change::DropIndex(index.clone()),
+                Change::AddForeignKey { column, references } =>
+                    Change::DropForeignKey { column: column.clone(), references: references.clone(), name: None },
+            })
+            .collect();
+        if !reasons.is_empty() {
+            return Err(reasons);
+        }
+        return Ok(AlterTable { name: self.get_old_name().to_string(), changes });
+    }
+}
+
+/// names of the foreign keys and the indexes to drop are filled when the table is renamed,
+/// because the default names are made from the old name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    RenameTable { from: String },
+    DropForeignKey { column: String, references: ForeignKey, name: Option<String> },
+    DropIndex(Index),
+    RenameColumn { from: String, to: String },
+    // the old definition
+    DropColumn(Column),
+    DropTimestamps,
+    DropSoftDeletes,
+    AddColumn(Column),
+    // the old definition has the old name
+    ChangeColumn { old: Column, new: Column },
+    AddTimestamps,
+    AddSoftDeletes,
+    AddIndex(Index),
+    AddForeignKey { column: String, references: ForeignKey },
+}
+
+/// names which are renamed from the old schema. they cannot be told from dropping and adding.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Renames {
+    // (from, to)
+    pub tables: Vec<(String, String)>,
+    // (table, from, to). the table is the new name
+    pub columns: Vec<(String, String, String)>,
+}
+
+impl Renames {
+    /// `old=new` of the table or `table.old=new` of the column
+    pub fn add(&mut self, hint: &str) -> Result<(), String> {
+        let (from, to) = hint.split_once('=').ok_or_else(|| hint.to_string())?;
+        let (from, to) = (from.trim(), to.trim());
+        match from.split_once('.') {
+            Some((table, column)) if is_mig_name(table) && is_mig_name(column) && is_mig_name(to) =>
+                self.columns.push((table.to_string(), column.to_string(), to.to_string())),
+            None if is_mig_name(from) && is_mig_name(to) => self.tables.push((from.to_string(), to.to_string())),
+            _ => return Err(hint.to_string()),
+        }
+        return Ok(());
+    }
+
+    // None if the name is renamed to the other table
+    fn old_table_name<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if let Some((from, _)) = self.tables.iter().find(|(_, to)| to == name) {
+            return Some(from);
+        }
+        if self.tables.iter().any(|(from, _)| from == name) {
+            return None;
+        }
+        return Some(name);
+    }
+
+    fn new_table_name<'a>(&'a self, name: &'a str) -> &'a str {
+        return self.tables.iter()
+            .find(|(from, _)| from == name)
+            .map(|(_, to)| to.as_str())
+            .unwrap_or(name);
+    }
+
+    // None if the column is renamed to the other column
+    fn old_column_name<'a>(&'a self, table: &str, name: &'a str) -> Option<&'a str> {
+        if let Some((_, from, _)) = self.columns.iter().find(|(t, _, to)| t == table && to == name) {
+            return Some(from);
+        }
+        if self.columns.iter().any(|(t, from, _)| t == table && from == name) {
+            return None;
+        }
+        return Some(name);
+    }
+
+    fn new_column_name<'a>(&'a self, table: &str, name: &'a str) -> &'a str {
+        return self.columns.iter()
+            .find(|(t, from, _)| t == table && from == name)
+            .map(|(_, _, to)| to.as_str())
+            .unwrap_or(name);
+    }
+}
+
+/// created and altered tables are in order of the references of the new schema.
+/// dropped tables are the last in reverse order of the references of the old schema.
+pub fn diff_schemas(old: &Schema, new: &Schema, renames: &Renames) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    let mut matched: Vec<&str> = vec!();
+    let mut created: Vec<&Table> = vec!();
+    for table in sorted_tables(new) {
+        let old_table = renames.old_table_name(&table.name).and_then(|name| old.get_table(name));
+        match old_table {
+            Some(old_table) => {
+                matched.push(&old_table.name);
+                let changes = diff_table(old_table, table, renames, &mut diff.hints);
+                if !changes.is_empty() {
+                    diff.tables.push(TableDiff::Alter(AlterTable { name: table.name.clone(), changes }));
+                }
+            }
+            None => {
+                created.push(table);
+                diff.tables.push(TableDiff::Create(table.clone()));
+            }
+        }
+    }
+
+    let dropped: Vec<&Table> = sorted_tables(old).into_iter().rev()
+        .filter(|table| !matched.contains(&table.name.as_str()))
+        .collect();
+    for table in dropped.iter() {
+        for new_table in created.iter().filter(|t| t.columns == table.columns) {
+            diff.hints.push(format!(
+                "table {} is dropped and {} is created. use --rename {}={} if it is renamed",
+                table.name, new_table.name, table.name, new_table.name,
+            ));
+        }
+        diff.tables.push(TableDiff::Drop((*table).clone()));
+    }
+    return diff;
+}
+
+// the order of the commands if the references are cyclic
+fn sorted_tables(schema: &Schema) -> Vec<&Table> {
+    return schema.sort_by_references().unwrap_or_else(|_| schema.tables.iter().collect());
+}
+
+fn diff_table(old: &Table, new: &Table, renames: &Renames, hints: &mut Vec<String>) -> Vec<Change> {
+    let renamed = old.name != new.name;
+
+    // (old, new) of the same column
+    let mut pairs: Vec<(&Column, &Column)> = vec!();
+    let mut added: Vec<&Column> = vec!();
+    for column in new.columns.iter() {
+        match renames.old_column_name(&new.name, &column.name).and_then(|name| old.get_column(name)) {
+            Some(old_column) => pairs.push((old_column, column)),
+            None => added.push(column),
+        }
+    }
+    let dropped: Vec<&Column> = old.columns.iter()
+        .filter(|column| !pairs.iter().any(|(old_column, _)| old_column.name == column.name))
+        .collect();
+    let to_new_column = |name: &str| -> String {
+        return pairs.iter()
+            .find(|(old_column, _)| old_column.name == name)
+            .map(|(_, new_column)| new_column.name.clone())
+            .unwrap_or_else(|| name.to_string());
+    };
+    // the referenced table and column may be renamed too
+    let to_new_foreign_key = |foreign: &ForeignKey| -> ForeignKey {
+        let table = renames.new_table_name(&foreign.table);
+        return ForeignKey {
+            table: table.to_string(),
+            column: renames.new_column_name(table, &foreign.column).to_string(),
+        };
+    };
+    let foreign_key_name = |column: &str| -> Option<String> {
+        return if renamed { Some(format!("{}_{}_foreign", old.name, column)) } else { None };
+    };
+
+    for column in dropped.iter() {
+        for new_column in added.iter().filter(|c| Column { name: column.name.clone(), ..(**c).clone() } == **column) {
+            hints.push(format!(
+                "column {}.{} is dropped and {} is added. use --rename {}.{}={} if it is renamed",
+                new.name, column.name, new_column.name, new.name, column.name, new_column.name,
+            ));
+        }
+    }
+
+    let mut changes = vec!();
+    if renamed {
+        changes.push(Change::RenameTable { from: old.name.clone() });
+    }
+    for (old_column, new_column) in pairs.iter() {
+        if let Some(foreign) = &old_column.references {
+            if new_column.references.as_ref() != Some(&to_new_foreign_key(foreign)) {
+                changes.push(Change::DropForeignKey {
+                    column: old_column.name.clone(),
+                    references: foreign.clone(),
+                    name: foreign_key_name(&old_column.name),
+                });
+            }
+        }
+    }
+    for column in dropped.iter() {
+        if let Some(foreign) = &column.references {
+            changes.push(Change::DropForeignKey {
+                column: column.name.clone(),
+                references: foreign.clone(),
+                name: foreign_key_name(&column.name),
+            });
+        }
+    }
+
+    // indexes are compared with the new names of the columns
+    let old_indexes: Vec<(&Index, Index)> = old.indexes.iter()
+        .map(|index| {
+            let columns = index.columns.iter().map(|c| to_new_column(c)).collect();
+            return (index, Index { columns, ..index.clone() });
+        })
+        .collect();
+    for (index, renamed_index) in old_indexes.iter() {
+        if !new.indexes.contains(renamed_index) {
+            let name = match &index.name {
+                Some(name) => Some(name.clone()),
+                None if renamed && index.kind != IndexKind::Primary => Some(default_index_name(&old.name, index)),
+                None => None,
+            };
+            changes.push(Change::DropIndex(Index { name, ..(*index).clone() }));
+        }
+    }
+
+    for (old_column, new_column) in pairs.iter() {
+        if old_column.name != new_column.name {
+            changes.push(Change::RenameColumn { from: old_column.name.clone(), to: new_column.name.clone() });
+        }
+    }
+    for column in dropped.iter() {
+        changes.push(Change::DropColumn((*column).clone()));
+    }
+    if old.timestamps && !new.timestamps {
+        changes.push(Change::DropTimestamps);
+    }
+    if old.soft_deletes && !new.soft_deletes {
+        changes.push(Change::DropSoftDeletes);
+    }
+    for column in added.iter() {
+        changes.push(Change::AddColumn((*column).clone()));
+    }
+    // foreign keys are compared separately
+    for (old_column, new_column) in pairs.iter() {
+        let old_definition = Column { name: new_column.name.clone(), references: None, ..(*old_column).clone() };
+        let new_definition = Column { references: None, ..(*new_column).clone() };
+        if old_definition != new_definition {
+            changes.push(Change::ChangeColumn { old: (*old_column).clone(), new: (*new_column).clone() });
+        }
+    }
+    if !old.timestamps && new.timestamps {
+        changes.push(Change::AddTimestamps);
+    }
+    if !old.soft_deletes && new.soft_deletes {
+        changes.push(Change::AddSoftDeletes);
+    }
+    for index in new.indexes.iter() {
+        if !old_indexes.iter().any(|(_, renamed_index)| renamed_index == index) {
+            changes.push(Change::AddIndex(index.clone()));
+        }
+    }
+
+    for (old_column, new_column) in pairs.iter() {
+        if let Some(foreign) = &new_column.references {
+            if old_column.references.as_ref().map(&to_new_foreign_key).as_ref() != Some(foreign) {
+                changes.push(Change::AddForeignKey { column: new_column.name.clone(), references: foreign.clone() });
+            }
+        }
+    }
+    for column in added.iter() {
+        if let Some(foreign) = &column.references {
+            changes.push(Change::AddForeignKey { column: column.name.clone(), references: foreign.clone() });
+        }
+    }
+    return changes;
+}
+
+/// e.g. members_name_old_unique
+pub fn default_index_name(table: &str, index: &Index) -> String {
+    let suffix = match index.kind {
+        IndexKind::Index => "index",
+        IndexKind::Unique => "unique",
+        IndexKind::Primary => "primary",
+    };
+    return format!("{}_{}_{}", table, index.columns.join("_"), suffix);
+}
+
+// the new type cannot have all values of the old type
+fn is_lossy(old: &Column, new: &Column) -> bool {
+    if old.unsigned && !new.unsigned && old.column_type.is_number() {
+        return true;
+    }
+    let integer_size = |t: &ColumnType| match t {
+        ColumnType::TinyInteger => 1,
+        ColumnType::SmallInteger => 2,
+        ColumnType::Increments | ColumnType::Integer => 4,
+        _ => 8,
+    };
+    return match (old.column_type, new.column_type) {
+        (o, n) if o == n => false,
+        (o, n) if o.is_integer() && n.is_integer() => integer_size(&n) < integer_size(&o),
+        (o, ColumnType::Decimal { precision, scale }) if o.is_integer() => precision.saturating_sub(scale) < 20,
+        (o, ColumnType::Double) if o.is_integer() => integer_size(&o) == 8,
+        (ColumnType::Float, ColumnType::Double) => false,
+        (ColumnType::Decimal { precision: op, scale: os }, ColumnType::Decimal { precision: np, scale: ns }) =>
+            ns < os || np.saturating_sub(ns) < op.saturating_sub(os),
+        // string has 255 characters by default
+        (o, n) if is_sized_string(&o) && is_sized_string(&n) => string_length(&n) < string_length(&o),
+        (o, ColumnType::Text) if o.is_string() => false,
+        (ColumnType::Date, ColumnType::DateTime) | (ColumnType::Date, ColumnType::Timestamp) => false,
+        (ColumnType::DateTime, ColumnType::Timestamp) | (ColumnType::Timestamp, ColumnType::DateTime) => false,
+        _ => true,
+    };
+}
+
+fn is_sized_string(column_type: &ColumnType) -> bool {
+    return matches!(column_type, ColumnType::Char { .. } | ColumnType::String { .. });
+}
+
+fn string_length(column_type: &ColumnType) -> u16 {
+    return match column_type {
+        ColumnType::Char { length } => *length,
+        ColumnType::String { length } => length.unwrap_or(255),
+        _ => 0,
+    };
+}
+
+// options of mig-file. e.g. :string 10
+fn to_type_name(column: &Column) -> String {
+    let unsigned = if column.unsigned && column.column_type.is_number() { " :unsigned" } else { "" };
+    return format!("{}{}", to_type_option(&column.column_type), unsigned);
+}
+
+pub(crate) fn to_type_option(column_type: &ColumnType) -> String {
+    let t = match *column_type {
+        ColumnType::Increments => "increments",
+        ColumnType::BigIncrements => "big-increments",
+        ColumnType::TinyInteger => "tiny-int",
+        ColumnType::SmallInteger => "small-int",
+        ColumnType::Integer => "int",
+        ColumnType::BigInteger => "big-int",
+        ColumnType::Float => "float",
+        ColumnType::Double => "double",
+        ColumnType::Decimal { precision, scale } => return format!(":decimal {} {}", precision, scale),
+        ColumnType::Boolean => "boolean",
+        ColumnType::Char { length } => return format!(":char {}", length),
+        ColumnType::String { length: Some(length) } => return format!(":string {}", length),
+        ColumnType::String { length: None } => "string",
+        ColumnType::Text => "text",
+        ColumnType::Date => "date",
+        ColumnType::Time => "time",
+        ColumnType::DateTime => "datetime",
+        ColumnType::Timestamp => "timestamp",
+        ColumnType::Json => "json",
+        ColumnType::Binary => "binary",
+    };
+    return format!(":{}", t);
+}
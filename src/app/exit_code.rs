@@ -0,0 +1,11 @@
+// exit codes of mig. success is 0. see README.
+
+// bug of mig
+pub const INTERNAL: i32 = 1;
+pub const ARGUMENT: i32 = 2;
+pub const IO: i32 = 3;
+// lexical and syntax analysis
+pub const PARSE: i32 = 4;
+// semantic analysis and the relations of the tables
+pub const VALIDATION: i32 = 5;
+pub const GENERATION: i32 = 6;
@@ -0,0 +1,135 @@
+use crate::app::converter::ast::{Block, ColumnNode, Command, File, Node, OptionNode};
+use crate::app::converter::token::Comment;
+
+// canonical style of mig-file
+const INDENT: &str = "    ";
+const MAX_WIDTH: usize = 80;
+// position after all tokens
+const END: (u32, u32) = (u32::MAX, u32::MAX);
+
+/// format mig-file to the canonical style. commands are separated by an empty line
+/// and each option has its own line. comments are kept in front of the next node or at the end of the line.
+pub fn format_file(file: &File) -> String {
+    let mut writer = Writer { out: String::new(), comments: &file.comments, next: 0 };
+    for (i, command) in file.commands.iter().enumerate() {
+        if i > 0 {
+            writer.out.push('\n');
+        }
+        let next = file.commands.get(i + 1).map(|c| c.position).unwrap_or(END);
+        write_command(&mut writer, command, next);
+    }
+    writer.write_comments_before(END, 0);
+    return writer.out;
+}
+
+// output and the comments which are not written yet
+struct Writer<'a> {
+    out: String,
+    comments: &'a [Comment],
+    next: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn has_comment_before(&self, position: (u32, u32)) -> bool {
+        return self.comments.get(self.next).is_some_and(|c| c.position < position);
+    }
+
+    // each comment has its own line
+    fn write_comments_before(&mut self, position: (u32, u32), depth: usize) {
+        while self.has_comment_before(position) {
+            let comment = &self.comments[self.next];
+            self.out.push_str(&format!("{}#{}\n", INDENT.repeat(depth), comment.text));
+            self.next += 1;
+        }
+    }
+
+    // the comment after the tokens of the line is kept at the end.
+    // next is the position of the token after the line.
+    fn write_line(&mut self, depth: usize, line: &str, next: (u32, u32)) {
+        self.out.push_str(&INDENT.repeat(depth));
+        self.out.push_str(line);
+        if let Some(comment) = self.comments.get(self.next) {
+            if comment.trailing && comment.position < next {
+                self.out.push_str(&format!(" #{}", comment.text));
+                self.next += 1;
+            }
+        }
+        self.out.push('\n');
+    }
+}
+
+fn write_command(writer: &mut Writer, command: &Command, next: (u32, u32)) {
+    let head = format!(":{} {}", command.name, command.table_name);
+    write_block_with_head(writer, &head, command.position, &command.body, 0, false, next);
+}
+
+// write `head { ... }` and newline
+fn write_block_with_head(
+    writer: &mut Writer,
+    head: &str,
+    position: (u32, u32),
+    block: &Block,
+    depth: usize,
+    allow_inline: bool,
+    next: (u32, u32),
+) {
+    writer.write_comments_before(position, depth);
+    let is_empty = block.nodes.is_empty() && !writer.has_comment_before(block.end);
+    if allow_inline && !writer.has_comment_before(block.end) {
+        if let Some(inline) = to_inline_block(block) {
+            let line = format!("{} {}", head, inline);
+            if INDENT.len() * depth + line.len() <= MAX_WIDTH {
+                writer.write_line(depth, &line, next);
+                return;
+            }
+        }
+    }
+    if is_empty {
+        writer.write_line(depth, &format!("{} {{}}", head), next);
+        return;
+    }
+    let first = block.nodes.first().map(|node| node.get_position()).unwrap_or(block.end);
+    writer.write_line(depth, &format!("{} {{", head), first);
+    for (i, node) in block.nodes.iter().enumerate() {
+        let next_in_block = block.nodes.get(i + 1).map(|node| node.get_position()).unwrap_or(block.end);
+        match node {
+            Node::Column(column) => write_column(writer, column, depth + 1, next_in_block),
+            Node::Option(option) => write_option(writer, option, depth + 1, next_in_block),
+        }
+    }
+    writer.write_comments_before(block.end, depth + 1);
+    writer.write_line(depth, "}", next);
+}
+
+fn write_column(writer: &mut Writer, column: &ColumnNode, depth: usize, next: (u32, u32)) {
+    write_block_with_head(writer, &column.name, column.position, &column.body, depth, true, next);
+}
+
+fn write_option(writer: &mut Writer, option: &OptionNode, depth: usize, next: (u32, u32)) {
+    let head = to_option_head(option);
+    match &option.body {
+        Some(body) => write_block_with_head(writer, &head, option.position, body, depth, false, next),
+        None => {
+            writer.write_comments_before(option.position, depth);
+            writer.write_line(depth, &head, next);
+        }
+    }
+}
+
+fn to_option_head(option: &OptionNode) -> String {
+    let mut head = format!(":{}", option.name);
+    for arg in option.args.iter() {
+        head.push(' ');
+        head.push_str(&arg.value.to_source());
+    }
+    return head;
+}
+
+// `{ :a 1 }` if the block has only one option without block
+fn to_inline_block(block: &Block) -> Option<String> {
+    return match block.nodes.as_slice() {
+        [] => Some("{}".to_string()),
+        [Node::Option(option)] if option.body.is_none() => Some(format!("{{ {} }}", to_option_head(option))),
+        _ => None,
+    };
+}
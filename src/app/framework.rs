@@ -1,6 +1,81 @@
-#[derive(Clone, Copy, Debug)]
+use crate::app::generator::sql::Dialect;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Framework {
     Laravel,
+    // dump of the analyzed schema
+    Json,
+    Yaml,
+    // one sql file of all tables
+    Sql(Dialect),
+    // up migration of golang-migrate/migrate (one file per table)
+    GolangMigrate(Dialect),
+}
+
+/// all supported targets in order of `mig targets`
+pub const FRAMEWORKS: [Framework; 7] = [
+    Framework::Laravel,
+    Framework::Json,
+    Framework::Yaml,
+    Framework::Sql(Dialect::Postgres),
+    Framework::Sql(Dialect::Mysql),
+    Framework::Sql(Dialect::Sqlite),
+    Framework::GolangMigrate(Dialect::Postgres),
+];
+
+impl Framework {
+    /// name for `--target`
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Framework::Laravel => "laravel",
+            Framework::Json => "json",
+            Framework::Yaml => "yaml",
+            Framework::Sql(Dialect::Postgres) => "sql-postgres",
+            Framework::Sql(Dialect::Mysql) => "sql-mysql",
+            Framework::Sql(Dialect::Sqlite) => "sql-sqlite",
+            Framework::GolangMigrate(_) => "golang-migrate",
+        };
+    }
+
+    /// directory of the migration files in the project of the framework
+    pub fn default_out_dir(&self) -> &'static str {
+        return match self {
+            Framework::Laravel => "database/migrations",
+            Framework::GolangMigrate(_) => "migrations",
+            // schema dump is not used by the framework
+            Framework::Json | Framework::Yaml | Framework::Sql(_) => ".",
+        };
+    }
+
+    pub fn description(&self) -> &'static str {
+        return match self {
+            Framework::Laravel => "migration class of Laravel (one file per table)",
+            Framework::Json => "analyzed schema as a versioned JSON document",
+            Framework::Yaml => "analyzed schema as a versioned YAML document",
+            Framework::Sql(Dialect::Postgres) => "CREATE TABLE statements of PostgreSQL",
+            Framework::Sql(Dialect::Mysql) => "CREATE TABLE statements of MySQL",
+            Framework::Sql(Dialect::Sqlite) => "CREATE TABLE statements of SQLite",
+            Framework::GolangMigrate(_) => "up migration of golang-migrate (dialect in mig.toml)",
+        };
+    }
+
+    /// the output is one document of all tables, not migration files
+    pub fn is_schema_dump(&self) -> bool {
+        return matches!(self, Framework::Json | Framework::Yaml | Framework::Sql(_));
+    }
+
+    /// json and yaml export the schema as it is, so they cannot alter it
+    pub fn has_alteration(&self) -> bool {
+        return !matches!(self, Framework::Json | Framework::Yaml);
+    }
+
+    /// dialect of golang-migrate is chosen in mig.toml. the others are kept.
+    pub fn with_dialect(self, dialect: Dialect) -> Framework {
+        return match self {
+            Framework::GolangMigrate(_) => Framework::GolangMigrate(dialect),
+            framework => framework,
+        };
+    }
 }
 
 pub fn to_framework_type(from: &str) -> Option<Framework> {
@@ -10,7 +85,25 @@ pub fn to_framework_type(from: &str) -> Option<Framework> {
 
     let fw_opt = match from.to_lowercase().as_str() {
         "laravel" => Some(Framework::Laravel),
+        "json" => Some(Framework::Json),
+        "yaml" | "yml" => Some(Framework::Yaml),
+        "sql-postgres" | "sql-postgresql" => Some(Framework::Sql(Dialect::Postgres)),
+        "sql-mysql" => Some(Framework::Sql(Dialect::Mysql)),
+        "sql-sqlite" => Some(Framework::Sql(Dialect::Sqlite)),
+        "golang-migrate" => Some(Framework::GolangMigrate(Dialect::Postgres)),
         _ => None,
     };
     return fw_opt;
 }
+
+/// comma separated targets. e.g. "laravel,sql-postgres". Err has the unknown name.
+pub fn to_framework_types(from: &str) -> Result<Vec<Framework>, String> {
+    let mut frameworks = vec!();
+    for name in from.split(',').map(|name| name.trim()) {
+        let framework = to_framework_type(name).ok_or_else(|| name.to_string())?;
+        if !frameworks.contains(&framework) {
+            frameworks.push(framework);
+        }
+    }
+    return Ok(frameworks);
+}
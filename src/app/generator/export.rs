@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::converter::mig::{Schema, Table};
+
+/// version of the structure of the exported document.
+/// it is incremented when the structure is changed incompatibly.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// document for `--target json` and `--target yaml`. see README for the structure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedSchema {
+    pub version: u32,
+    pub tables: Vec<Table>,
+}
+
+impl ExportedSchema {
+    pub fn new(schema: &Schema) -> Self {
+        return ExportedSchema {
+            version: EXPORT_VERSION,
+            tables: schema.tables.clone(),
+        };
+    }
+}
+
+pub fn to_json(schema: &Schema) -> String {
+    let mut json = serde_json::to_string_pretty(&ExportedSchema::new(schema))
+        .expect("schema has only string keys");
+    json.push('\n');
+    return json;
+}
+
+pub fn to_yaml(schema: &Schema) -> String {
+    return serde_yaml::to_string(&ExportedSchema::new(schema))
+        .expect("schema has only string keys");
+}
@@ -0,0 +1,267 @@
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Table, Value};
+use crate::app::diff::{AlterTable, Change, TableDiff};
+
+/// class name is made from migration_name. e.g. create_members_table -> CreateMembersTable
+pub fn generate(table: &Table, migration_name: &str) -> String {
+    return match table.method {
+        Method::Create => generate_step(&TableDiff::Create(table.clone()), migration_name),
+    };
+}
+
+/// migration of the step. down() is the reverse step, or throws if the data cannot be restored.
+pub fn generate_step(step: &TableDiff, migration_name: &str) -> String {
+    return to_step_migration(step, migration_name, Some(&to_studly_case(migration_name)));
+}
+
+/// migration of the diff. the same table is altered many times with the same migration name,
+/// so the class is anonymous (laravel 8.37+) not to declare the class twice.
+pub fn generate_diff_step(step: &TableDiff, migration_name: &str) -> String {
+    return to_step_migration(step, migration_name, None);
+}
+
+fn to_step_migration(step: &TableDiff, migration_name: &str, class_name: Option<&str>) -> String {
+    let down_body = match step.reverse() {
+        Ok(reverse) => to_step_body(&reverse),
+        Err(reasons) => format!(
+            "throw new \\RuntimeException({});",
+            to_php_string(&format!("{} cannot be rolled back. {}", migration_name, reasons.join(". "))),
+        ),
+    };
+    return to_migration(class_name, &to_step_body(step), &down_body);
+}
+
+fn to_step_body(step: &TableDiff) -> String {
+    return match step {
+        TableDiff::Create(table) => to_create_body(table),
+        TableDiff::Drop(table) => format!("Schema::dropIfExists({});", to_php_string(&table.name)),
+        TableDiff::Alter(alter) => to_alter_body(alter),
+    };
+}
+
+fn to_create_body(table: &Table) -> String {
+    let mut lines = vec!();
+    for column in table.columns.iter() {
+        lines.push(to_column_definition(column));
+    }
+    if table.timestamps {
+        lines.push("$table->timestamps();".to_string());
+    }
+    if table.soft_deletes {
+        lines.push("$table->softDeletes();".to_string());
+    }
+    for index in table.indexes.iter() {
+        lines.push(to_index_definition(index));
+    }
+    for column in table.columns.iter() {
+        if let Some(foreign) = &column.references {
+            lines.push(to_foreign_key_definition(&column.name, foreign));
+        }
+    }
+    return to_blueprint("create", &table.name, &lines);
+}
+
+// Schema::table is divided at Schema::rename
+fn to_alter_body(alter: &AlterTable) -> String {
+    let mut statements = vec!();
+    let mut table_name = alter.get_old_name().to_string();
+    let mut lines = vec!();
+    for change in alter.changes.iter() {
+        match change {
+            Change::RenameTable { from } => {
+                if !lines.is_empty() {
+                    statements.push(to_blueprint("table", &table_name, &lines));
+                    lines.clear();
+                }
+                statements.push(format!("Schema::rename({}, {});", to_php_string(from), to_php_string(&alter.name)));
+                table_name = alter.name.clone();
+            }
+            Change::DropForeignKey { column, name, .. } => lines.push(match name {
+                Some(name) => format!("$table->dropForeign({});", to_php_string(name)),
+                None => format!("$table->dropForeign([{}]);", to_php_string(column)),
+            }),
+            Change::DropIndex(index) => lines.push(to_drop_index_definition(index)),
+            Change::RenameColumn { from, to } =>
+                lines.push(format!("$table->renameColumn({}, {});", to_php_string(from), to_php_string(to))),
+            Change::DropColumn(column) =>
+                lines.push(format!("$table->dropColumn({});", to_php_string(&column.name))),
+            Change::DropTimestamps => lines.push("$table->dropTimestamps();".to_string()),
+            Change::DropSoftDeletes => lines.push("$table->dropSoftDeletes();".to_string()),
+            Change::AddColumn(column) => lines.push(to_column_definition(column)),
+            Change::ChangeColumn { new, .. } => {
+                let def = to_column_definition(new);
+                lines.push(format!("{}->change();", def.trim_end_matches(';')));
+            }
+            Change::AddTimestamps => lines.push("$table->timestamps();".to_string()),
+            Change::AddSoftDeletes => lines.push("$table->softDeletes();".to_string()),
+            Change::AddIndex(index) => lines.push(to_index_definition(index)),
+            Change::AddForeignKey { column, references } =>
+                lines.push(to_foreign_key_definition(column, references)),
+        }
+    }
+    if !lines.is_empty() {
+        statements.push(to_blueprint("table", &table_name, &lines));
+    }
+    return statements.join("\n        ");
+}
+
+// Schema::create or Schema::table with the lines of $table
+fn to_blueprint(method: &str, table_name: &str, lines: &[String]) -> String {
+    return format!(
+        "Schema::{}({}, function (Blueprint $table) {{\n{}        }});",
+        method,
+        to_php_string(table_name),
+        lines.iter().map(|line| format!("            {}\n", line)).collect::<String>(),
+    );
+}
+
+// the anonymous class is returned if class_name is None
+fn to_migration(class_name: Option<&str>, up_body: &str, down_body: &str) -> String {
+    let (declaration, end) = match class_name {
+        Some(class_name) => (format!("class {} extends Migration", class_name), ""),
+        None => ("return new class extends Migration".to_string(), ";"),
+    };
+    return format!(r#"<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+{}
+{{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {{
+        {}
+    }}
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {{
+        {}
+    }}
+}}{}
+"#, declaration, up_body, down_body, end);
+}
+
+// create_members_table -> CreateMembersTable
+fn to_studly_case(s: &str) -> String {
+    return s.split('_')
+        .map(|word| {
+            let mut cs = word.chars();
+            match cs.next() {
+                Some(c) => c.to_ascii_uppercase().to_string() + cs.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+}
+
+fn to_column_definition(column: &Column) -> String {
+    let name = to_php_string(&column.name);
+    let mut def = match column.column_type {
+        ColumnType::Increments => format!("$table->increments({})", name),
+        ColumnType::BigIncrements => format!("$table->bigIncrements({})", name),
+        ColumnType::TinyInteger => format!("$table->tinyInteger({})", name),
+        ColumnType::SmallInteger => format!("$table->smallInteger({})", name),
+        ColumnType::Integer => format!("$table->integer({})", name),
+        ColumnType::BigInteger => format!("$table->bigInteger({})", name),
+        ColumnType::Float => format!("$table->float({})", name),
+        ColumnType::Double => format!("$table->double({})", name),
+        ColumnType::Decimal { precision, scale } =>
+            format!("$table->decimal({}, {}, {})", name, precision, scale),
+        ColumnType::Boolean => format!("$table->boolean({})", name),
+        ColumnType::Char { length } => format!("$table->char({}, {})", name, length),
+        ColumnType::String { length: Some(length) } => format!("$table->string({}, {})", name, length),
+        ColumnType::String { length: None } => format!("$table->string({})", name),
+        ColumnType::Text => format!("$table->text({})", name),
+        ColumnType::Date => format!("$table->date({})", name),
+        ColumnType::Time => format!("$table->time({})", name),
+        ColumnType::DateTime => format!("$table->dateTime({})", name),
+        ColumnType::Timestamp => format!("$table->timestamp({})", name),
+        ColumnType::Json => format!("$table->json({})", name),
+        ColumnType::Binary => format!("$table->binary({})", name),
+    };
+    if column.unsigned && column.column_type.is_number() && !column.column_type.is_auto_increment() {
+        def.push_str("->unsigned()");
+    }
+    if column.nullable {
+        def.push_str("->nullable()");
+    }
+    if let Some(value) = &column.default {
+        def.push_str(&format!("->default({})", to_php_value(value, &column.column_type)));
+    }
+    if let Some(comment) = &column.comment {
+        def.push_str(&format!("->comment({})", to_php_string(comment)));
+    }
+    def.push(';');
+    return def;
+}
+
+fn to_foreign_key_definition(column: &str, foreign: &ForeignKey) -> String {
+    return format!(
+        "$table->foreign({})->references({})->on({});",
+        to_php_string(column),
+        to_php_string(&foreign.column),
+        to_php_string(&foreign.table),
+    );
+}
+
+// the name is made from the columns if it is not given
+fn to_drop_index_definition(index: &Index) -> String {
+    let method = match index.kind {
+        IndexKind::Index => "dropIndex",
+        IndexKind::Unique => "dropUnique",
+        IndexKind::Primary => "dropPrimary",
+    };
+    return match &index.name {
+        Some(name) => format!("$table->{}({});", method, to_php_string(name)),
+        None => format!(
+            "$table->{}([{}]);",
+            method,
+            index.columns.iter().map(|c| to_php_string(c)).collect::<Vec<String>>().join(", "),
+        ),
+    };
+}
+
+fn to_index_definition(index: &Index) -> String {
+    let method = match index.kind {
+        IndexKind::Index => "index",
+        IndexKind::Unique => "unique",
+        IndexKind::Primary => "primary",
+    };
+    let columns = index.columns.iter()
+        .map(|c| to_php_string(c))
+        .collect::<Vec<String>>()
+        .join(", ");
+    return match &index.name {
+        Some(name) => format!("$table->{}([{}], {});", method, columns, to_php_string(name)),
+        None => format!("$table->{}([{}]);", method, columns),
+    };
+}
+
+fn to_php_value(value: &Value, column_type: &ColumnType) -> String {
+    return match value {
+        Value::Integer(i) if *column_type == ColumnType::Boolean =>
+            (if *i == 0 { "false" } else { "true" }).to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Double(d) => format!("{:?}", d),
+        Value::String(s) => to_php_string(s),
+        Value::Date(d) => to_php_string(&d.format("%Y-%m-%d").to_string()),
+        Value::Time(t) => to_php_string(&t.format("%H:%M:%S%.f").to_string()),
+        Value::DateTime(dt) => to_php_string(&dt.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+        Value::DateTimeTz(dt) => to_php_string(&dt.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string()),
+    };
+}
+
+// single quoted string of php
+fn to_php_string(s: &str) -> String {
+    return format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"));
+}
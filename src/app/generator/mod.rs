@@ -0,0 +1,123 @@
+use crate::app::converter::mig::{Method, Schema, Table};
+use crate::app::diff::{SchemaDiff, TableDiff};
+use crate::app::framework::Framework;
+use crate::app::helper::file_helper::get_file_name_for_framework;
+
+pub mod export;
+pub mod laravel;
+pub mod sql;
+
+/// migration file for a table. file_name has no timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    /// empty for the schema dump which has all tables
+    pub table_name: String,
+    pub file_name: String,
+    pub content: String,
+    /// rollback in the other file. e.g. .down.sql of golang-migrate
+    pub down: Option<String>,
+}
+
+/// template of the migration name. {method} and {table} are replaced.
+pub const DEFAULT_MIGRATION_NAME: &str = "{method}_{table}_table";
+
+pub fn generate(framework: Framework, schema: &Schema) -> Vec<GeneratedFile> {
+    return generate_with_naming(framework, schema, DEFAULT_MIGRATION_NAME);
+}
+
+/// migration names are made from the template. e.g. "{method}_{table}"
+pub fn generate_with_naming(framework: Framework, schema: &Schema, migration_name: &str) -> Vec<GeneratedFile> {
+    if framework.is_schema_dump() {
+        return vec!(generate_schema_dump(framework, schema));
+    }
+    return schema.tables.iter()
+        .map(|table| generate_table(framework, table, &to_migration_name(table, migration_name)))
+        .collect();
+}
+
+fn generate_table(framework: Framework, table: &Table, migration_name: &str) -> GeneratedFile {
+    let step = match table.method {
+        Method::Create => TableDiff::Create(table.clone()),
+    };
+    return generate_step(framework, &step, migration_name, laravel::generate_step)
+        .expect("schema dump is not per table");
+}
+
+// None for the schema dump. the class of laravel is made by to_laravel
+fn generate_step(
+    framework: Framework,
+    step: &TableDiff,
+    migration_name: &str,
+    to_laravel: fn(&TableDiff, &str) -> String,
+) -> Option<GeneratedFile> {
+    let (content, down) = match framework {
+        Framework::Laravel => (to_laravel(step, migration_name), None),
+        Framework::GolangMigrate(dialect) =>
+            (sql::generate_step(step, dialect), Some(sql::generate_reverse(step, dialect))),
+        Framework::Json | Framework::Yaml | Framework::Sql(_) => return None,
+    };
+    return Some(GeneratedFile {
+        table_name: step.table_name().to_string(),
+        file_name: get_file_name_for_framework(migration_name, &framework),
+        content,
+        down,
+    });
+}
+
+fn generate_schema_dump(framework: Framework, schema: &Schema) -> GeneratedFile {
+    let content = match framework {
+        Framework::Json => export::to_json(schema),
+        Framework::Yaml => export::to_yaml(schema),
+        Framework::Sql(dialect) => sql::generate_schema(schema, dialect),
+        Framework::Laravel | Framework::GolangMigrate(_) => unreachable!("migration is not schema dump"),
+    };
+    return GeneratedFile {
+        table_name: String::new(),
+        file_name: get_file_name_for_framework("schema", &framework),
+        content,
+        down: None,
+    };
+}
+
+/// migrations from the old schema to the new one. sql-* has all changes in a diff file and its rollback.
+/// json and yaml have no alteration, so they have no file.
+pub fn generate_diff(framework: Framework, diff: &SchemaDiff, migration_name: &str) -> Vec<GeneratedFile> {
+    if let Framework::Sql(dialect) = framework {
+        let content = diff.tables.iter()
+            .map(|step| sql::generate_step(step, dialect))
+            .collect::<Vec<String>>()
+            .join("\n");
+        // the reverse steps in reverse order
+        let down = diff.tables.iter().rev()
+            .map(|step| sql::generate_reverse(step, dialect))
+            .collect::<Vec<String>>()
+            .join("\n");
+        return vec!(GeneratedFile {
+            table_name: String::new(),
+            file_name: get_file_name_for_framework("diff", &framework),
+            content,
+            down: Some(down),
+        });
+    }
+    return diff.tables.iter()
+        .filter_map(|step| {
+            let migration_name = fill_migration_name(migration_name, step.method(), step.table_name());
+            return generate_step(framework, step, &migration_name, laravel::generate_diff_step);
+        })
+        .collect();
+}
+
+/// e.g. create_members_table
+pub fn get_migration_name(table: &Table) -> String {
+    return to_migration_name(table, DEFAULT_MIGRATION_NAME);
+}
+
+pub fn to_migration_name(table: &Table, template: &str) -> String {
+    return fill_migration_name(template, table.method.as_str(), &table.name);
+}
+
+fn fill_migration_name(template: &str, method: &str, table_name: &str) -> String {
+    return template
+        .replace("{method}", method)
+        .replace("{table}", table_name);
+}
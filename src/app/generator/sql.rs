@@ -0,0 +1,378 @@
+use serde::Deserialize;
+
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Schema, Table, Value};
+use crate::app::diff::{AlterTable, Change, default_index_name, TableDiff};
+
+/// SQL dialect of `sql-*` and `golang-migrate` targets
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dialect {
+    Postgres,
+    Mysql,
+    Sqlite,
+}
+
+impl Dialect {
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Dialect::Postgres => "postgres",
+            Dialect::Mysql => "mysql",
+            Dialect::Sqlite => "sqlite",
+        };
+    }
+}
+
+pub fn to_dialect(from: &str) -> Option<Dialect> {
+    return match from.to_lowercase().as_str() {
+        "postgres" | "postgresql" => Some(Dialect::Postgres),
+        "mysql" => Some(Dialect::Mysql),
+        "sqlite" => Some(Dialect::Sqlite),
+        _ => None,
+    };
+}
+
+/// statements of all tables in the order of the schema
+pub fn generate_schema(schema: &Schema, dialect: Dialect) -> String {
+    return schema.tables.iter()
+        .map(|table| generate(table, dialect))
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+/// CREATE TABLE and CREATE INDEX statements of the table
+pub fn generate(table: &Table, dialect: Dialect) -> String {
+    let mut definitions = vec!();
+    for column in table.columns.iter() {
+        definitions.push(to_column_definition(column, dialect));
+    }
+    if table.timestamps {
+        definitions.push(format!("{} TIMESTAMP NULL", quote("created_at", dialect)));
+        definitions.push(format!("{} TIMESTAMP NULL", quote("updated_at", dialect)));
+    }
+    if table.soft_deletes {
+        definitions.push(format!("{} TIMESTAMP NULL", quote("deleted_at", dialect)));
+    }
+    for index in table.indexes.iter().filter(|index| index.kind == IndexKind::Primary) {
+        definitions.push(format!("PRIMARY KEY ({})", quote_all(&index.columns, dialect)));
+    }
+    for column in table.columns.iter() {
+        if let Some(foreign) = &column.references {
+            definitions.push(to_foreign_key_definition(&table.name, &column.name, foreign, dialect));
+        }
+    }
+
+    let mut statements = match table.method {
+        Method::Create => vec!(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            quote(&table.name, dialect),
+            definitions.iter().map(|def| format!("    {}", def)).collect::<Vec<String>>().join(",\n"),
+        )),
+    };
+    for index in table.indexes.iter().filter(|index| index.kind != IndexKind::Primary) {
+        statements.push(to_index_statement(&table.name, index, dialect));
+    }
+    // postgres has no inline comment
+    if dialect == Dialect::Postgres {
+        for column in table.columns.iter() {
+            if let Some(comment) = &column.comment {
+                statements.push(to_comment_statement(&table.name, &column.name, Some(comment), dialect));
+            }
+        }
+    }
+    return statements.iter().map(|s| format!("{}\n", s)).collect();
+}
+
+/// statements of the step
+pub fn generate_step(step: &TableDiff, dialect: Dialect) -> String {
+    return match step {
+        TableDiff::Create(table) => generate(table, dialect),
+        TableDiff::Drop(table) => format!("DROP TABLE {};\n", quote(&table.name, dialect)),
+        TableDiff::Alter(alter) => generate_alter(alter, dialect),
+    };
+}
+
+/// statements of the reverse step. the statement which always fails is put if the data cannot be restored.
+pub fn generate_reverse(step: &TableDiff, dialect: Dialect) -> String {
+    let reasons = match step.reverse() {
+        Ok(reverse) => return generate_step(&reverse, dialect),
+        Err(reasons) => reasons,
+    };
+    let comments: String = reasons.iter().map(|reason| format!("-- {}\n", reason)).collect();
+    let statement = to_failing_statement(&format!("{} cannot be rolled back", step.table_name()), dialect);
+    return format!("{}{}\n", comments, statement);
+}
+
+/// changes of the step which sqlite cannot alter. the table must be recreated for them.
+pub fn sqlite_unsupported(step: &TableDiff) -> Vec<String> {
+    return match step {
+        TableDiff::Alter(alter) => alter.changes.iter()
+            .filter_map(to_sqlite_unsupported)
+            .map(|what| format!("sqlite cannot {}. recreate the table {}", what, alter.get_old_name()))
+            .collect(),
+        TableDiff::Create(_) | TableDiff::Drop(_) => vec!(),
+    };
+}
+
+// sqlite cannot change nor constrain the existing column
+fn to_sqlite_unsupported(change: &Change) -> Option<String> {
+    return match change {
+        Change::DropForeignKey { column, .. } => Some(format!("drop the foreign key of {}", column)),
+        Change::DropIndex(index) if index.kind == IndexKind::Primary => Some("drop the primary key".to_string()),
+        Change::ChangeColumn { new, .. } => Some(format!("change the column {}", new.name)),
+        Change::AddIndex(index) if index.kind == IndexKind::Primary => Some("add the primary key".to_string()),
+        Change::AddForeignKey { column, .. } => Some(format!("add the foreign key of {}", column)),
+        _ => None,
+    };
+}
+
+/// ALTER TABLE statements of the changes.
+/// sqlite cannot run some of them, so the comment is put instead and the migration fails at first.
+fn generate_alter(alter: &AlterTable, dialect: Dialect) -> String {
+    let mut table = alter.get_old_name();
+    let mut statements = vec!();
+    for change in alter.changes.iter() {
+        let alter_table = |s: String| format!("ALTER TABLE {} {};", quote(table, dialect), s);
+        let unsupported = |change: &Change| {
+            let what = to_sqlite_unsupported(change).unwrap_or_default();
+            return format!("-- sqlite cannot {}. recreate the table {}", what, table);
+        };
+        match change {
+            Change::RenameTable { from } => {
+                let (from, to) = (quote(from, dialect), quote(&alter.name, dialect));
+                statements.push(format!("ALTER TABLE {} RENAME TO {};", from, to));
+                table = &alter.name;
+            }
+            Change::DropForeignKey { column, name, .. } => {
+                let name = name.clone().unwrap_or_else(|| default_foreign_key_name(table, column));
+                statements.push(match dialect {
+                    Dialect::Postgres => alter_table(format!("DROP CONSTRAINT {}", quote(&name, dialect))),
+                    Dialect::Mysql => alter_table(format!("DROP FOREIGN KEY {}", quote(&name, dialect))),
+                    Dialect::Sqlite => unsupported(change),
+                });
+            }
+            Change::DropIndex(index) if index.kind == IndexKind::Primary => statements.push(match dialect {
+                Dialect::Postgres =>
+                    alter_table(format!("DROP CONSTRAINT {}", quote(&format!("{}_pkey", table), dialect))),
+                Dialect::Mysql => alter_table("DROP PRIMARY KEY".to_string()),
+                Dialect::Sqlite => unsupported(change),
+            }),
+            Change::DropIndex(index) => {
+                let name = index.name.clone().unwrap_or_else(|| default_index_name(table, index));
+                statements.push(match dialect {
+                    Dialect::Mysql => format!("DROP INDEX {} ON {};", quote(&name, dialect), quote(table, dialect)),
+                    Dialect::Postgres | Dialect::Sqlite => format!("DROP INDEX {};", quote(&name, dialect)),
+                });
+            }
+            Change::RenameColumn { from, to } => {
+                let (from, to) = (quote(from, dialect), quote(to, dialect));
+                statements.push(alter_table(format!("RENAME COLUMN {} TO {}", from, to)));
+            }
+            Change::DropColumn(column) =>
+                statements.push(alter_table(format!("DROP COLUMN {}", quote(&column.name, dialect)))),
+            Change::DropTimestamps => {
+                statements.push(alter_table(format!("DROP COLUMN {}", quote("created_at", dialect))));
+                statements.push(alter_table(format!("DROP COLUMN {}", quote("updated_at", dialect))));
+            }
+            Change::DropSoftDeletes =>
+                statements.push(alter_table(format!("DROP COLUMN {}", quote("deleted_at", dialect)))),
+            Change::AddColumn(column) => {
+                statements.push(alter_table(format!("ADD COLUMN {}", to_column_definition(column, dialect))));
+                if let (Dialect::Postgres, Some(comment)) = (dialect, &column.comment) {
+                    statements.push(to_comment_statement(table, &column.name, Some(comment), dialect));
+                }
+            }
+            Change::ChangeColumn { old, new } => match dialect {
+                Dialect::Postgres => statements.extend(to_postgres_changes(table, old, new)),
+                Dialect::Mysql =>
+                    statements.push(alter_table(format!("MODIFY COLUMN {}", to_column_definition(new, dialect)))),
+                Dialect::Sqlite => statements.push(unsupported(change)),
+            },
+            Change::AddTimestamps => {
+                statements.push(alter_table(format!("ADD COLUMN {} TIMESTAMP NULL", quote("created_at", dialect))));
+                statements.push(alter_table(format!("ADD COLUMN {} TIMESTAMP NULL", quote("updated_at", dialect))));
+            }
+            Change::AddSoftDeletes =>
+                statements.push(alter_table(format!("ADD COLUMN {} TIMESTAMP NULL", quote("deleted_at", dialect)))),
+            Change::AddIndex(index) if index.kind == IndexKind::Primary => statements.push(match dialect {
+                Dialect::Sqlite => unsupported(change),
+                Dialect::Postgres | Dialect::Mysql =>
+                    alter_table(format!("ADD PRIMARY KEY ({})", quote_all(&index.columns, dialect))),
+            }),
+            Change::AddIndex(index) => statements.push(to_index_statement(table, index, dialect)),
+            Change::AddForeignKey { column, references } => statements.push(match dialect {
+                Dialect::Sqlite => unsupported(change),
+                Dialect::Postgres | Dialect::Mysql =>
+                    alter_table(format!("ADD {}", to_foreign_key_definition(table, column, references, dialect))),
+            }),
+        }
+    }
+    // nothing is altered rather than a part of the changes
+    if dialect == Dialect::Sqlite && alter.changes.iter().any(|change| to_sqlite_unsupported(change).is_some()) {
+        let message = format!("{} must be recreated", alter.get_old_name());
+        statements.insert(0, to_failing_statement(&message, dialect));
+    }
+    return statements.iter().map(|s| format!("{}\n", s)).collect();
+}
+
+// sql cannot throw, so the statement fails instead
+fn to_failing_statement(message: &str, dialect: Dialect) -> String {
+    let message = to_sql_string(message, dialect);
+    return match dialect {
+        Dialect::Postgres => format!("DO $$ BEGIN RAISE EXCEPTION {}; END $$;", message),
+        Dialect::Mysql => format!("SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = {};", message),
+        // raise() outside of the trigger is the error too
+        Dialect::Sqlite => format!("SELECT RAISE(ABORT, {});", message),
+    };
+}
+
+// postgres changes the type, the null, the default and the comment separately
+fn to_postgres_changes(table: &str, old: &Column, new: &Column) -> Vec<String> {
+    let dialect = Dialect::Postgres;
+    let alter_column = |s: String| {
+        format!("ALTER TABLE {} ALTER COLUMN {} {};", quote(table, dialect), quote(&new.name, dialect), s)
+    };
+    let mut statements = vec!();
+    if old.column_type != new.column_type {
+        statements.push(alter_column(format!("TYPE {}", to_sql_type(&new.column_type, dialect))));
+    }
+    if old.nullable != new.nullable {
+        statements.push(alter_column((if new.nullable { "DROP NOT NULL" } else { "SET NOT NULL" }).to_string()));
+    }
+    if old.default != new.default {
+        statements.push(alter_column(match &new.default {
+            Some(value) => format!("SET DEFAULT {}", to_sql_value(value, &new.column_type, dialect)),
+            None => "DROP DEFAULT".to_string(),
+        }));
+    }
+    if old.comment != new.comment {
+        statements.push(to_comment_statement(table, &new.name, new.comment.as_deref(), dialect));
+    }
+    return statements;
+}
+
+fn to_comment_statement(table: &str, column: &str, comment: Option<&str>, dialect: Dialect) -> String {
+    let comment = comment.map(|comment| to_sql_string(comment, dialect)).unwrap_or_else(|| "NULL".to_string());
+    return format!("COMMENT ON COLUMN {}.{} IS {};", quote(table, dialect), quote(column, dialect), comment);
+}
+
+// the name is the same as laravel. e.g. posts_member_id_foreign
+fn default_foreign_key_name(table: &str, column: &str) -> String {
+    return format!("{}_{}_foreign", table, column);
+}
+
+fn to_foreign_key_definition(table: &str, column: &str, foreign: &ForeignKey, dialect: Dialect) -> String {
+    return format!(
+        "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        quote(&default_foreign_key_name(table, column), dialect),
+        quote(column, dialect),
+        quote(&foreign.table, dialect),
+        quote(&foreign.column, dialect),
+    );
+}
+
+fn to_column_definition(column: &Column, dialect: Dialect) -> String {
+    let mut def = format!("{} {}", quote(&column.name, dialect), to_sql_type(&column.column_type, dialect));
+    if column.column_type.is_auto_increment() {
+        // primary key is not null
+        return def;
+    }
+    // only mysql has unsigned
+    if dialect == Dialect::Mysql && column.unsigned && column.column_type.is_number() {
+        def.push_str(" UNSIGNED");
+    }
+    def.push_str(if column.nullable { " NULL" } else { " NOT NULL" });
+    if let Some(value) = &column.default {
+        def.push_str(&format!(" DEFAULT {}", to_sql_value(value, &column.column_type, dialect)));
+    }
+    if dialect == Dialect::Mysql {
+        if let Some(comment) = &column.comment {
+            def.push_str(&format!(" COMMENT {}", to_sql_string(comment, dialect)));
+        }
+    }
+    return def;
+}
+
+fn to_sql_type(column_type: &ColumnType, dialect: Dialect) -> String {
+    let t = match (column_type, dialect) {
+        (ColumnType::Increments, Dialect::Postgres) => "SERIAL PRIMARY KEY",
+        (ColumnType::BigIncrements, Dialect::Postgres) => "BIGSERIAL PRIMARY KEY",
+        (ColumnType::Increments, Dialect::Mysql) => "INT UNSIGNED AUTO_INCREMENT PRIMARY KEY",
+        (ColumnType::BigIncrements, Dialect::Mysql) => "BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY",
+        (ColumnType::Increments, Dialect::Sqlite) | (ColumnType::BigIncrements, Dialect::Sqlite) =>
+            "INTEGER PRIMARY KEY AUTOINCREMENT",
+        // sqlite has only INTEGER for integers
+        (t, Dialect::Sqlite) if t.is_integer() => "INTEGER",
+        (ColumnType::TinyInteger, Dialect::Mysql) => "TINYINT",
+        (ColumnType::TinyInteger, _) | (ColumnType::SmallInteger, _) => "SMALLINT",
+        (ColumnType::Integer, Dialect::Mysql) => "INT",
+        (ColumnType::Integer, _) => "INTEGER",
+        (ColumnType::BigInteger, _) => "BIGINT",
+        (ColumnType::Float, Dialect::Mysql) => "FLOAT",
+        (ColumnType::Float, _) => "REAL",
+        (ColumnType::Double, Dialect::Postgres) => "DOUBLE PRECISION",
+        (ColumnType::Double, Dialect::Mysql) => "DOUBLE",
+        (ColumnType::Double, Dialect::Sqlite) => "REAL",
+        (ColumnType::Decimal { precision, scale }, _) => return format!("DECIMAL({}, {})", precision, scale),
+        (ColumnType::Boolean, _) => "BOOLEAN",
+        (ColumnType::Char { length }, _) => return format!("CHAR({})", length),
+        (ColumnType::String { length }, _) => return format!("VARCHAR({})", length.unwrap_or(255)),
+        (ColumnType::Text, _) => "TEXT",
+        (ColumnType::Date, _) => "DATE",
+        (ColumnType::Time, _) => "TIME",
+        (ColumnType::DateTime, Dialect::Postgres) => "TIMESTAMP",
+        (ColumnType::DateTime, _) => "DATETIME",
+        (ColumnType::Timestamp, _) => "TIMESTAMP",
+        (ColumnType::Json, Dialect::Sqlite) => "TEXT",
+        (ColumnType::Json, _) => "JSON",
+        (ColumnType::Binary, Dialect::Postgres) => "BYTEA",
+        (ColumnType::Binary, _) => "BLOB",
+    };
+    return t.to_string();
+}
+
+fn to_index_statement(table: &str, index: &Index, dialect: Dialect) -> String {
+    let keyword = match index.kind {
+        IndexKind::Unique => "UNIQUE INDEX",
+        _ => "INDEX",
+    };
+    let name = index.name.clone().unwrap_or_else(|| default_index_name(table, index));
+    return format!(
+        "CREATE {} {} ON {} ({});",
+        keyword, quote(&name, dialect), quote(table, dialect), quote_all(&index.columns, dialect),
+    );
+}
+
+fn to_sql_value(value: &Value, column_type: &ColumnType, dialect: Dialect) -> String {
+    return match value {
+        Value::Integer(i) if *column_type == ColumnType::Boolean && dialect == Dialect::Postgres =>
+            (if *i == 0 { "FALSE" } else { "TRUE" }).to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Double(d) => format!("{:?}", d),
+        Value::String(s) => to_sql_string(s, dialect),
+        Value::Date(d) => to_sql_string(&d.format("%Y-%m-%d").to_string(), dialect),
+        Value::Time(t) => to_sql_string(&t.format("%H:%M:%S%.f").to_string(), dialect),
+        Value::DateTime(dt) => to_sql_string(&dt.format("%Y-%m-%d %H:%M:%S%.f").to_string(), dialect),
+        Value::DateTimeTz(dt) => to_sql_string(&dt.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string(), dialect),
+    };
+}
+
+// the names are quoted, so the keywords can be the names. e.g. order
+fn quote(name: &str, dialect: Dialect) -> String {
+    return match dialect {
+        Dialect::Mysql => format!("`{}`", name.replace('`', "``")),
+        Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+    };
+}
+
+fn quote_all(names: &[String], dialect: Dialect) -> String {
+    return names.iter().map(|name| quote(name, dialect)).collect::<Vec<String>>().join(", ");
+}
+
+// quote is doubled. mysql also escapes backslash.
+fn to_sql_string(s: &str, dialect: Dialect) -> String {
+    let s = match dialect {
+        Dialect::Mysql => s.replace('\\', "\\\\"),
+        Dialect::Postgres | Dialect::Sqlite => s.to_string(),
+    };
+    return format!("'{}'", s.replace('\'', "''"));
+}
@@ -1,37 +1,144 @@
 extern crate chrono;
 
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use chrono::Local;
+use chrono::NaiveDateTime;
 
 use crate::app::framework::Framework;
 
-// TODO 出力先を相対パスなどで指定するようにするのならtimestampを最下ファイルの頭だけにつけるように変更
-pub fn with_timestamp(target_file: &str, framework_type: &Framework) -> String {
-    let target = get_file_name_for_framework(target_file, framework_type);
-    return format!("{}{}", generate_timestamp(framework_type), target);
+/// timestamp is put on the head of the file name, not the directory
+pub fn with_timestamp(target_file: &Path, framework_type: &Framework, time: &NaiveDateTime) -> PathBuf {
+    let file_name = target_file.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = get_file_name_for_framework(&file_name, framework_type);
+    return target_file.with_file_name(format!("{}{}", generate_timestamp(framework_type, time), target));
 }
 
-fn generate_timestamp(framework_type: &Framework) -> String {
+/// prefix of the file name. e.g. for the files which are converted at once
+pub fn generate_timestamp(framework_type: &Framework, time: &NaiveDateTime) -> String {
     let fmt = match framework_type {
         Framework::Laravel => "%Y_%m_%d_%H%M%S_",
+        Framework::GolangMigrate(_) => "%Y%m%d%H%M%S_",
+        // schema dump is not migration
+        Framework::Json | Framework::Yaml | Framework::Sql(_) => "",
     };
-    return Local::now().format(fmt).to_string();
+    return time.format(fmt).to_string();
 }
 
+/// prefix of the new migration. the diff of sql-* is the migration unlike the schema dump.
+pub fn generate_migration_timestamp(framework_type: &Framework, time: &NaiveDateTime) -> String {
+    return match framework_type {
+        Framework::Sql(_) => time.format("%Y%m%d%H%M%S_").to_string(),
+        _ => generate_timestamp(framework_type, time),
+    };
+}
+
+/// file name without the timestamp prefix of the framework
+pub fn without_timestamp<'a>(file_name: &'a str, framework_type: &Framework) -> &'a str {
+    // e.g. 2019_07_12_215755_ and 20190712215755_
+    let (fmt, len) = match framework_type {
+        Framework::Laravel => ("%Y_%m_%d_%H%M%S", "2019_07_12_215755".len()),
+        Framework::GolangMigrate(_) => ("%Y%m%d%H%M%S", "20190712215755".len()),
+        Framework::Json | Framework::Yaml | Framework::Sql(_) => return file_name,
+    };
+    if file_name.len() > len + 1 && file_name.is_char_boundary(len) && &file_name[len..len + 1] == "_"
+        && NaiveDateTime::parse_from_str(&file_name[..len], fmt).is_ok() {
+        return &file_name[len + 1..];
+    }
+    return file_name;
+}
+
+/// the latest timestamp of the migrations in the directory
+pub fn latest_timestamp(dir: &Path, framework_type: &Framework) -> Option<NaiveDateTime> {
+    let (fmt, len) = match framework_type {
+        Framework::Laravel => ("%Y_%m_%d_%H%M%S", "2019_07_12_215755".len()),
+        // the diff of sql-*
+        Framework::GolangMigrate(_) | Framework::Sql(_) => ("%Y%m%d%H%M%S", "20190712215755".len()),
+        Framework::Json | Framework::Yaml => return None,
+    };
+    return fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.is_char_boundary(len) {
+                return None;
+            }
+            return NaiveDateTime::parse_from_str(&name[..len], fmt).ok();
+        })
+        .max();
+}
+
+/// files in the same directory which are the same migration ignoring the timestamp
+pub fn find_same_migrations(target_file: &Path, framework_type: &Framework) -> Vec<PathBuf> {
+    let target_name = match target_file.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return vec!(),
+    };
+    let target_name = without_timestamp(&target_name, framework_type);
+    let dir = match target_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // no directory has no file
+        Err(_) => return vec!(),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| without_timestamp(&name.to_string_lossy(), framework_type) == target_name)
+                .unwrap_or(false)
+        })
+        .map(|path| dir.join(path.file_name().unwrap()))
+        .collect();
+    paths.sort();
+    return paths;
+}
+
+/// the extension is not doubled if target_file already has it
 pub fn get_file_name_for_framework(target_file: &str, framework_type: &Framework) -> String {
-    let ext = get_extension_for_framework(framework_type);
-    return format!("{}.{}", target_file, ext);
+    if is_file_for_framework(Path::new(target_file), framework_type) {
+        return target_file.to_string();
+    }
+    return format!("{}.{}", target_file, get_extension_for_framework(framework_type));
+}
+
+/// the extension may have a dot. e.g. up.sql
+pub fn is_file_for_framework(file_path: &Path, framework_type: &Framework) -> bool {
+    let ext = format!(".{}", get_extension_for_framework(framework_type));
+    return file_path.file_name()
+        .map(|name| name.to_string_lossy().ends_with(&ext))
+        .unwrap_or(false);
+}
+
+/// e.g. 20190712215755_create_members_table.down.sql for golang-migrate and 20190712215755_diff.down.sql for sql-*
+pub fn to_down_file(up_file: &Path) -> PathBuf {
+    let file_name = up_file.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = file_name.strip_suffix(".up.sql")
+        .or_else(|| file_name.strip_suffix(".sql"))
+        .unwrap_or(&file_name);
+    return up_file.with_file_name(format!("{}.down.sql", stem));
 }
 
 pub fn get_extension_for_framework(framework_type: &Framework) -> String {
     let ext = match framework_type {
         Framework::Laravel => "php",
+        Framework::Json => "json",
+        Framework::Yaml => "yaml",
+        Framework::Sql(_) => "sql",
+        Framework::GolangMigrate(_) => "up.sql",
     };
     return ext.to_string();
 }
 
-pub fn is_extension(file_path: &PathBuf, extension: &str) -> bool {
+pub fn is_extension(file_path: &Path, extension: &str) -> bool {
     let file_extension = file_path.extension();
     if file_extension.is_none() {
         return false;
@@ -1,13 +1,31 @@
+use std::path::Path;
+use std::process::Command;
+
+/// content of the file in the revision of git. Err has the message of git.
+pub fn git_show(revision: &str, path: &Path) -> Result<String, String> {
+    // ./ is relative to the current directory, not the root of the repository
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{}:./{}", revision, path.display()))
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    return String::from_utf8(output.stdout).map_err(|e| e.to_string());
+}
+
+// for confirming before overwriting the output file
 pub fn confirm(msg: &str) -> bool {
     let mut ans = String::new();
-    println!("{} (Y/n):  ", msg);
+    // stdout may be the generated code
+    eprintln!("{} (y/N):  ", msg);
     if std::io::stdin().read_line(&mut ans).is_err() {
-        println!("cannot read the line");
+        eprintln!("cannot read the line");
         return false;
     }
-    println!("{}", ans);
     // ans has newline character
-    if ans.trim_end().to_lowercase() == "y".to_string() {
+    if ans.trim_end().to_lowercase() == "y" {
         return true;
     }
     return false;
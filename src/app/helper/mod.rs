@@ -1,3 +1,2 @@
 pub mod file_helper;
 pub mod io_helper;
-pub mod slice_helper;
\ No newline at end of file
@@ -0,0 +1,741 @@
+use std::convert::TryFrom;
+
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Table, Value};
+use crate::app::diff::default_index_name;
+use crate::app::import::{Imported, to_default_value};
+
+// best-effort reader of the migrations of Laravel.
+// only up() is read and the calls of Schema:: and the blueprint are applied to the schema in order of the files.
+
+/// sources are (file name, content) in order of the migrations
+pub fn import_migrations(sources: &[(String, String)]) -> Imported {
+    let mut imported = Imported::default();
+    for (file_name, content) in sources.iter() {
+        let mut reader = Reader { imported: &mut imported, file_name };
+        reader.read_file(content);
+    }
+    return imported;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Php {
+    // without $
+    Variable(String),
+    Ident(String),
+    Str(String),
+    Number(String),
+    Symbol(String),
+}
+
+// token and the line
+type Spanned = (Php, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+    Array(Vec<Expr>),
+    // constants, function calls and so on
+    Other,
+}
+
+impl Expr {
+    fn as_str(&self) -> Option<&str> {
+        return match self {
+            Expr::Str(s) => Some(s),
+            _ => None,
+        };
+    }
+
+    fn as_u16(&self) -> Option<u16> {
+        return match self {
+            Expr::Int(i) => u16::try_from(*i).ok(),
+            _ => None,
+        };
+    }
+
+    // 'a' or ['a', 'b']
+    fn as_names(&self) -> Option<Vec<String>> {
+        return match self {
+            Expr::Str(s) => Some(vec!(s.clone())),
+            Expr::Array(items) => items.iter().map(|item| item.as_str().map(|s| s.to_string())).collect(),
+            _ => None,
+        };
+    }
+}
+
+// `->name(args)`
+#[derive(Debug, Clone)]
+struct Call {
+    name: String,
+    args: Vec<Expr>,
+}
+
+impl Call {
+    fn arg(&self, i: usize) -> Option<&Expr> {
+        return self.args.get(i);
+    }
+}
+
+struct Reader<'a> {
+    imported: &'a mut Imported,
+    file_name: &'a str,
+}
+
+impl<'a> Reader<'a> {
+    fn warn(&mut self, line: usize, message: String) {
+        self.imported.warnings.push(format!("{}:{}: {}", self.file_name, line, message));
+    }
+
+    fn read_file(&mut self, content: &str) {
+        let tokens = tokenize(content);
+        let body = match find_up_body(&tokens) {
+            Some(body) => body,
+            None => {
+                self.warn(1, "up() is not found".to_string());
+                return;
+            }
+        };
+        let mut i = 0;
+        while i < body.len() {
+            match (&body[i].0, body.get(i + 1).map(|t| &t.0), body.get(i + 2).map(|t| &t.0)) {
+                (Php::Ident(class), Some(Php::Symbol(colons)), Some(Php::Ident(method)))
+                if colons == "::" && is_class(class, "Schema") => {
+                    let line = body[i].1;
+                    // e.g. Schema::connection('other')->create(...) is not a call of Schema
+                    match find_close(body, i + 3).filter(|_| body[i + 3].0 == symbol("(")) {
+                        Some(end) => {
+                            self.read_schema_call(method, &body[i + 4..end], line);
+                            i = end + 1;
+                        }
+                        None => i += 3,
+                    }
+                }
+                (Php::Ident(class), Some(Php::Symbol(colons)), Some(Php::Ident(method)))
+                if colons == "::" && is_class(class, "DB") => {
+                    self.warn(body[i].1, format!("DB::{} is not imported", method));
+                    i += 3;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    // args are the tokens in the parentheses of Schema::method(...)
+    fn read_schema_call(&mut self, method: &str, args: &[Spanned], line: usize) {
+        match method {
+            "create" | "table" => {
+                let (name, rest) = split_arg(args);
+                let name = match parse_expr(name).as_str() {
+                    Some(name) => name.to_string(),
+                    None => {
+                        self.warn(line, format!("Schema::{} with the dynamic table name is not imported", method));
+                        return;
+                    }
+                };
+                let (variable, body) = match find_closure(rest) {
+                    Some(closure) => closure,
+                    None => {
+                        self.warn(line, format!("blueprint of {} is not found", name));
+                        return;
+                    }
+                };
+                // the altered table keeps its place
+                let position = self.imported.schema.tables.iter().position(|t| t.name == name);
+                let (mut table, position) = match (method, position) {
+                    ("create", Some(i)) => {
+                        self.warn(line, format!("table {} is created again", name));
+                        let _ = self.imported.schema.tables.remove(i);
+                        (Table::new(Method::Create, &name), i)
+                    }
+                    ("create", None) => (Table::new(Method::Create, &name), self.imported.schema.tables.len()),
+                    (_, Some(i)) => (self.imported.schema.tables.remove(i), i),
+                    (_, None) => {
+                        self.warn(line, format!("table {} is not created before Schema::table", name));
+                        return;
+                    }
+                };
+                for statement in split_statements(body) {
+                    let line = statement.first().map(|t| t.1).unwrap_or(line);
+                    match parse_chain(statement, &variable) {
+                        Some(chain) => self.apply_chain(&mut table, chain, line),
+                        None => self.warn(line, format!("statement in the blueprint of {} is not imported", name)),
+                    }
+                }
+                self.imported.schema.tables.insert(position, table);
+            }
+            "drop" | "dropIfExists" => {
+                if let Some(name) = parse_expr(args).as_str() {
+                    self.imported.schema.tables.retain(|t| t.name != name);
+                }
+            }
+            "rename" => {
+                let (from, to) = split_arg(args);
+                match (parse_expr(from), parse_expr(to)) {
+                    (Expr::Str(from), Expr::Str(to)) => match self.imported.schema.tables.iter_mut().find(|t| t.name == from) {
+                        Some(table) => table.name = to,
+                        None => self.warn(line, format!("table {} is not created before Schema::rename", from)),
+                    },
+                    _ => self.warn(line, "Schema::rename with the dynamic table name is not imported".to_string()),
+                }
+            }
+            // conditions and settings
+            "hasTable" | "hasColumn" | "hasColumns" | "defaultStringLength" => {}
+            _ => self.warn(line, format!("Schema::{} is not imported", method)),
+        }
+    }
+
+    // $table->first(...)->modifier(...)
+    fn apply_chain(&mut self, table: &mut Table, chain: Vec<Call>, line: usize) {
+        let (first, modifiers) = match chain.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        if let Some((column_type, unsigned)) = to_column_type(first) {
+            let name = match first.arg(0).and_then(|arg| arg.as_str()) {
+                Some(name) => name,
+                None if first.name == "id" => "id",
+                None => {
+                    self.warn(line, format!("{} without the column name is not imported", first.name));
+                    return;
+                }
+            };
+            if first.name == "enum" || first.name == "set" {
+                self.warn(line, format!("{} of {}.{} is imported as :string", first.name, table.name, name));
+            }
+            let mut column = Column::new(name, column_type);
+            column.unsigned = unsigned;
+            self.apply_column_modifiers(table, &mut column, modifiers, line);
+            return;
+        }
+        let names = first.arg(0).and_then(|arg| arg.as_names());
+        match (first.name.as_str(), names) {
+            ("timestamps", _) | ("timestampsTz", _) | ("nullableTimestamps", _) => table.timestamps = true,
+            ("softDeletes", _) | ("softDeletesTz", _) => table.soft_deletes = true,
+            ("dropTimestamps", _) | ("dropTimestampsTz", _) => table.timestamps = false,
+            ("dropSoftDeletes", _) | ("dropSoftDeletesTz", _) => table.soft_deletes = false,
+            ("rememberToken", _) => {
+                let mut column = Column::new("remember_token", ColumnType::String { length: Some(100) });
+                column.nullable = true;
+                replace_column(table, column);
+            }
+            ("dropRememberToken", _) => drop_column(table, "remember_token"),
+            ("index", Some(columns)) => add_index(table, IndexKind::Index, columns, first.arg(1)),
+            ("unique", Some(columns)) => add_index(table, IndexKind::Unique, columns, first.arg(1)),
+            ("primary", Some(columns)) => add_index(table, IndexKind::Primary, columns, first.arg(1)),
+            ("foreign", Some(columns)) => self.add_foreign_key(table, &columns, modifiers, line),
+            ("dropColumn", _) | ("dropColumns", _) => {
+                // dropColumn(['a', 'b']) or dropColumn('a', 'b')
+                let names: Option<Vec<Vec<String>>> = first.args.iter().map(|arg| arg.as_names()).collect();
+                match names {
+                    Some(names) => names.iter().flatten().for_each(|name| drop_column(table, name)),
+                    None => self.warn(line, format!("dropColumn of {} is not imported", table.name)),
+                }
+            }
+            ("renameColumn", _) => match (first.arg(0).and_then(|a| a.as_str()), first.arg(1).and_then(|a| a.as_str())) {
+                (Some(from), Some(to)) => rename_column(table, from, to),
+                _ => self.warn(line, format!("renameColumn of {} is not imported", table.name)),
+            },
+            ("dropIndex", _) | ("dropUnique", _) | ("dropPrimary", _) => {
+                let kind = match first.name.as_str() {
+                    "dropIndex" => IndexKind::Index,
+                    "dropUnique" => IndexKind::Unique,
+                    _ => IndexKind::Primary,
+                };
+                let name = table.name.clone();
+                let before = table.indexes.len();
+                table.indexes.retain(|index| index.kind != kind || !is_dropped_index(&name, index, first.arg(0)));
+                if table.indexes.len() == before {
+                    self.warn(line, format!("index of {} is not found for {}", table.name, first.name));
+                }
+            }
+            ("dropForeign", _) => {
+                let name = table.name.clone();
+                let dropped = table.columns.iter_mut()
+                    .find(|column| column.references.is_some() && is_dropped_foreign_key(&name, &column.name, first.arg(0)));
+                match dropped {
+                    Some(column) => column.references = None,
+                    None => self.warn(line, format!("foreign key of {} is not found for dropForeign", table.name)),
+                }
+            }
+            _ => self.warn(line, format!("{} of {} is not imported", first.name, table.name)),
+        }
+    }
+
+    fn apply_column_modifiers(&mut self, table: &mut Table, column: &mut Column, modifiers: &[Call], line: usize) {
+        let mut is_change = false;
+        let mut references: Option<(Option<String>, Option<String>)> = None;
+        for modifier in modifiers.iter() {
+            match modifier.name.as_str() {
+                "nullable" => column.nullable = modifier.arg(0) != Some(&Expr::Bool(false)),
+                "unsigned" => column.unsigned = true,
+                "default" => match modifier.arg(0).map(|arg| to_value(arg, &column.column_type)) {
+                    // NULL is the default of the nullable column
+                    Some(None) if modifier.arg(0) == Some(&Expr::Null) => {}
+                    Some(Some(value)) => column.default = Some(value),
+                    _ => self.warn(line, format!("default of {}.{} is not imported", table.name, column.name)),
+                },
+                "comment" => match modifier.arg(0).and_then(|arg| arg.as_str()) {
+                    Some(comment) => column.comment = Some(comment.to_string()),
+                    None => self.warn(line, format!("comment of {}.{} is not imported", table.name, column.name)),
+                },
+                "unique" | "index" | "primary" => {
+                    let kind = match modifier.name.as_str() {
+                        "unique" => IndexKind::Unique,
+                        "index" => IndexKind::Index,
+                        _ => IndexKind::Primary,
+                    };
+                    add_index(table, kind, vec!(column.name.clone()), modifier.arg(0));
+                }
+                "change" => is_change = true,
+                // foreignId('user_id')->constrained() references users
+                "constrained" => {
+                    let on = modifier.arg(0).and_then(|arg| arg.as_str()).map(|s| s.to_string())
+                        .or_else(|| Some(guess_table(&column.name)));
+                    let reference = modifier.arg(1).and_then(|arg| arg.as_str()).map(|s| s.to_string());
+                    references = Some((on, reference));
+                }
+                "references" => {
+                    let (on, _) = references.take().unwrap_or((None, None));
+                    references = Some((on, modifier.arg(0).and_then(|arg| arg.as_str()).map(|s| s.to_string())));
+                }
+                "on" => {
+                    let (_, reference) = references.take().unwrap_or((None, None));
+                    references = Some((modifier.arg(0).and_then(|arg| arg.as_str()).map(|s| s.to_string()), reference));
+                }
+                name => self.warn(line, format!("{} of {}.{} is not imported", name, table.name, column.name)),
+            }
+        }
+        match references {
+            Some((Some(on), reference)) => column.references = Some(ForeignKey {
+                table: on,
+                column: reference.unwrap_or_else(|| "id".to_string()),
+            }),
+            Some((None, _)) => self.warn(line, format!("foreign key of {}.{} has no table", table.name, column.name)),
+            None => {}
+        }
+        // the existing column keeps the foreign key of foreign()
+        if is_change {
+            if let Some(old) = table.get_column(&column.name) {
+                if column.references.is_none() {
+                    column.references = old.references.clone();
+                }
+            }
+        } else if table.get_column(&column.name).is_some() {
+            self.warn(line, format!("column {}.{} is added again", table.name, column.name));
+        }
+        replace_column(table, column.clone());
+    }
+
+    // $table->foreign('user_id')->references('id')->on('users')
+    fn add_foreign_key(&mut self, table: &mut Table, columns: &[String], modifiers: &[Call], line: usize) {
+        let mut foreign = ForeignKey { table: String::new(), column: "id".to_string() };
+        for modifier in modifiers.iter() {
+            let arg = modifier.arg(0).and_then(|arg| arg.as_str()).map(|s| s.to_string());
+            match (modifier.name.as_str(), arg) {
+                ("references", Some(column)) => foreign.column = column,
+                ("on", Some(on)) => foreign.table = on,
+                (name, _) => self.warn(line, format!("{} of the foreign key of {} is not imported", name, table.name)),
+            }
+        }
+        let column = match columns {
+            [column] => column,
+            _ => {
+                self.warn(line, format!("foreign key of {}({}) is not imported. mig has the foreign key of one column",
+                                        table.name, columns.join(", ")));
+                return;
+            }
+        };
+        if foreign.table.is_empty() {
+            self.warn(line, format!("foreign key of {}.{} has no table", table.name, column));
+            return;
+        }
+        match table.columns.iter_mut().find(|c| c.name == *column) {
+            Some(c) => c.references = Some(foreign),
+            None => self.warn(line, format!("column {}.{} of the foreign key is not found", table.name, column)),
+        }
+    }
+}
+
+// name is the default of Laravel if it is not given
+fn add_index(table: &mut Table, kind: IndexKind, columns: Vec<String>, name: Option<&Expr>) {
+    let mut index = Index { kind, columns, name: None };
+    if let Some(name) = name.and_then(|name| name.as_str()) {
+        if name != default_index_name(&table.name, &index) {
+            index.name = Some(name.to_string());
+        }
+    }
+    table.indexes.push(index);
+}
+
+// the class may have the namespace. e.g. \Illuminate\Support\Facades\Schema
+fn is_class(ident: &str, class: &str) -> bool {
+    return ident == class || ident.ends_with(&format!("\\{}", class));
+}
+
+// (column type, unsigned) of the method of the blueprint
+fn to_column_type(call: &Call) -> Option<(ColumnType, bool)> {
+    let length = |i: usize, default: u16| call.arg(i).and_then(|arg| arg.as_u16()).unwrap_or(default);
+    let column_type = match call.name.as_str() {
+        "id" | "bigIncrements" => ColumnType::BigIncrements,
+        "increments" | "mediumIncrements" | "smallIncrements" | "tinyIncrements" => ColumnType::Increments,
+        "tinyInteger" => ColumnType::TinyInteger,
+        "smallInteger" => ColumnType::SmallInteger,
+        "integer" | "mediumInteger" => ColumnType::Integer,
+        "bigInteger" => ColumnType::BigInteger,
+        "unsignedTinyInteger" => return Some((ColumnType::TinyInteger, true)),
+        "unsignedSmallInteger" => return Some((ColumnType::SmallInteger, true)),
+        "unsignedInteger" | "unsignedMediumInteger" => return Some((ColumnType::Integer, true)),
+        "unsignedBigInteger" | "foreignId" => return Some((ColumnType::BigInteger, true)),
+        "float" => ColumnType::Float,
+        "double" => ColumnType::Double,
+        "decimal" | "unsignedDecimal" => {
+            let column_type = ColumnType::Decimal { precision: length(1, 8), scale: length(2, 2) };
+            return Some((column_type, call.name == "unsignedDecimal"));
+        }
+        "boolean" => ColumnType::Boolean,
+        "char" => ColumnType::Char { length: length(1, 255) },
+        "string" => ColumnType::String { length: call.arg(1).and_then(|arg| arg.as_u16()) },
+        "uuid" => ColumnType::Char { length: 36 },
+        "ulid" => ColumnType::Char { length: 26 },
+        "ipAddress" => ColumnType::String { length: Some(45) },
+        "macAddress" => ColumnType::String { length: Some(17) },
+        "enum" | "set" => ColumnType::String { length: None },
+        "text" | "tinyText" | "mediumText" | "longText" => ColumnType::Text,
+        "date" => ColumnType::Date,
+        "time" | "timeTz" => ColumnType::Time,
+        "dateTime" | "dateTimeTz" => ColumnType::DateTime,
+        "timestamp" | "timestampTz" => ColumnType::Timestamp,
+        "json" | "jsonb" => ColumnType::Json,
+        "binary" => ColumnType::Binary,
+        _ => return None,
+    };
+    return Some((column_type, false));
+}
+
+// the value of ->default() for the column type
+fn to_value(expr: &Expr, column_type: &ColumnType) -> Option<Value> {
+    let value = match expr {
+        Expr::Int(i) => Value::Integer(*i),
+        Expr::Float(f) => Value::Double(*f),
+        Expr::Bool(b) => Value::Integer(i64::from(*b)),
+        Expr::Str(s) => to_default_value(s, column_type)?,
+        _ => return None,
+    };
+    return if column_type.accepts(&value) { Some(value) } else { None };
+}
+
+// users of user_id
+fn guess_table(column: &str) -> String {
+    return format!("{}s", column.trim_end_matches("_id"));
+}
+
+// the column is replaced in place by ->change()
+fn replace_column(table: &mut Table, column: Column) {
+    match table.columns.iter_mut().find(|c| c.name == column.name) {
+        Some(c) => *c = column,
+        None => table.columns.push(column),
+    }
+}
+
+// the indexes of the column are dropped with it
+fn drop_column(table: &mut Table, name: &str) {
+    table.columns.retain(|c| c.name != name);
+    table.indexes.retain(|index| !index.columns.iter().any(|c| c == name));
+}
+
+fn rename_column(table: &mut Table, from: &str, to: &str) {
+    if let Some(column) = table.columns.iter_mut().find(|c| c.name == from) {
+        column.name = to.to_string();
+    }
+    for column in table.indexes.iter_mut().flat_map(|index| index.columns.iter_mut()) {
+        if column == from {
+            *column = to.to_string();
+        }
+    }
+}
+
+// dropIndex('name') or dropIndex(['column'])
+fn is_dropped_index(table: &str, index: &Index, arg: Option<&Expr>) -> bool {
+    return match arg {
+        Some(Expr::Str(name)) => index.name.as_deref().unwrap_or(&default_index_name(table, index)) == name,
+        Some(Expr::Array(_)) => arg.and_then(|arg| arg.as_names()).is_some_and(|columns| columns == index.columns),
+        _ => false,
+    };
+}
+
+// dropForeign('members_user_id_foreign') or dropForeign(['user_id'])
+fn is_dropped_foreign_key(table: &str, column: &str, arg: Option<&Expr>) -> bool {
+    return match arg {
+        Some(Expr::Str(name)) => *name == format!("{}_{}_foreign", table, column),
+        Some(Expr::Array(columns)) => columns.as_slice() == [Expr::Str(column.to_string())],
+        _ => false,
+    };
+}
+
+// tokens in the braces of `function up()`
+fn find_up_body(tokens: &[Spanned]) -> Option<&[Spanned]> {
+    let start = tokens.windows(2).position(|w| {
+        w[0].0 == Php::Ident("function".to_string()) && w[1].0 == Php::Ident("up".to_string())
+    })?;
+    let open = start + tokens[start..].iter().position(|t| t.0 == symbol("{"))?;
+    let close = find_close(tokens, open)?;
+    return Some(&tokens[open + 1..close]);
+}
+
+// index of the bracket which closes the bracket at open
+fn find_close(tokens: &[Spanned], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, (token, _)) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Php::Symbol(s) if s == "(" || s == "[" || s == "{" => depth += 1,
+            Php::Symbol(s) if s == ")" || s == "]" || s == "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    return None;
+}
+
+// the first argument and the rest after `,`
+fn split_arg(tokens: &[Spanned]) -> (&[Spanned], &[Spanned]) {
+    let mut depth = 0;
+    for (i, (token, _)) in tokens.iter().enumerate() {
+        match token {
+            Php::Symbol(s) if s == "(" || s == "[" || s == "{" => depth += 1,
+            Php::Symbol(s) if s == ")" || s == "]" || s == "}" => depth -= 1,
+            Php::Symbol(s) if s == "," && depth == 0 => return (&tokens[..i], &tokens[i + 1..]),
+            _ => {}
+        }
+    }
+    return (tokens, &[]);
+}
+
+// the variable of the blueprint and the body of `function (Blueprint $table) { ... }`
+fn find_closure(tokens: &[Spanned]) -> Option<(String, &[Spanned])> {
+    let params = tokens.iter().position(|t| t.0 == symbol("("))?;
+    let params_end = find_close(tokens, params)?;
+    let variable = tokens[params..params_end].iter().find_map(|t| match &t.0 {
+        Php::Variable(name) => Some(name.clone()),
+        _ => None,
+    })?;
+    // `use ($x)` may be before the body
+    let open = params_end + tokens[params_end..].iter().position(|t| t.0 == symbol("{"))?;
+    let close = find_close(tokens, open)?;
+    return Some((variable, &tokens[open + 1..close]));
+}
+
+// statements separated by `;` out of the brackets
+fn split_statements(tokens: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut statements = vec!();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, (token, _)) in tokens.iter().enumerate() {
+        match token {
+            Php::Symbol(s) if s == "(" || s == "[" || s == "{" => depth += 1,
+            Php::Symbol(s) if s == ")" || s == "]" || s == "}" => depth -= 1,
+            Php::Symbol(s) if s == ";" && depth == 0 => {
+                statements.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        statements.push(&tokens[start..]);
+    }
+    return statements;
+}
+
+// $table->a(...)->b(...). None if the statement is not the chain of the variable
+fn parse_chain(tokens: &[Spanned], variable: &str) -> Option<Vec<Call>> {
+    match tokens.first() {
+        Some((Php::Variable(name), _)) if name == variable => {}
+        _ => return None,
+    }
+    let mut chain = vec!();
+    let mut i = 1;
+    while i < tokens.len() {
+        let name = match (&tokens[i].0, tokens.get(i + 1).map(|t| &t.0), tokens.get(i + 2).map(|t| &t.0)) {
+            (Php::Symbol(arrow), Some(Php::Ident(name)), Some(Php::Symbol(paren))) if arrow == "->" && paren == "(" =>
+                name.clone(),
+            _ => return None,
+        };
+        let close = find_close(tokens, i + 2)?;
+        let mut args = vec!();
+        let mut rest = &tokens[i + 3..close];
+        while !rest.is_empty() {
+            let (arg, next) = split_arg(rest);
+            args.push(parse_expr(arg));
+            rest = next;
+        }
+        chain.push(Call { name, args });
+        i = close + 1;
+    }
+    return if chain.is_empty() { None } else { Some(chain) };
+}
+
+fn parse_expr(tokens: &[Spanned]) -> Expr {
+    let tokens: Vec<&Php> = tokens.iter().map(|t| &t.0).collect();
+    // named argument. e.g. length: 10
+    let tokens = match tokens.as_slice() {
+        [Php::Ident(_), Php::Symbol(colon), rest @ ..] if colon == ":" => rest.to_vec(),
+        _ => tokens,
+    };
+    return match tokens.as_slice() {
+        [Php::Str(s)] => Expr::Str(s.clone()),
+        [Php::Number(n)] => to_number(n, false),
+        [Php::Symbol(minus), Php::Number(n)] if minus == "-" => to_number(n, true),
+        [Php::Ident(ident)] => match ident.to_ascii_lowercase().as_str() {
+            "true" => Expr::Bool(true),
+            "false" => Expr::Bool(false),
+            "null" => Expr::Null,
+            _ => Expr::Other,
+        },
+        [Php::Symbol(open), items @ .., Php::Symbol(close)] if open == "[" && close == "]" => to_array(items),
+        [Php::Ident(array), Php::Symbol(open), items @ .., Php::Symbol(close)]
+        if array.eq_ignore_ascii_case("array") && open == "(" && close == ")" => to_array(items),
+        _ => Expr::Other,
+    };
+}
+
+fn to_array(items: &[&Php]) -> Expr {
+    // line numbers are not used in the items
+    let items: Vec<Spanned> = items.iter().map(|t| ((*t).clone(), 0)).collect();
+    let mut rest = items.as_slice();
+    let mut array = vec!();
+    while !rest.is_empty() {
+        let (item, next) = split_arg(rest);
+        // the keys of the associative array are not supported
+        if item.iter().any(|t| t.0 == symbol("=>")) {
+            return Expr::Other;
+        }
+        array.push(parse_expr(item));
+        rest = next;
+    }
+    return Expr::Array(array);
+}
+
+fn to_number(n: &str, negative: bool) -> Expr {
+    let n = if negative { format!("-{}", n) } else { n.to_string() };
+    if let Ok(i) = n.parse::<i64>() {
+        return Expr::Int(i);
+    }
+    return n.parse::<f64>().map(Expr::Float).unwrap_or(Expr::Other);
+}
+
+fn symbol(s: &str) -> Php {
+    return Php::Symbol(s.to_string());
+}
+
+// tokens of php without comments. the html out of `<?php` is not expected in migrations.
+fn tokenize(src: &str) -> Vec<Spanned> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec!();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let next = chars.get(i + 1).copied();
+        match ch {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            _ if ch.is_whitespace() => i += 1,
+            '#' => i = skip_line(&chars, i),
+            '/' if next == Some('/') => i = skip_line(&chars, i),
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' => {
+                let start_line = line;
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != ch {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        let escaped = chars[i + 1];
+                        match (ch, escaped) {
+                            (_, '\\') => s.push('\\'),
+                            ('\'', '\'') | ('"', '"') => s.push(escaped),
+                            ('"', 'n') => s.push('\n'),
+                            ('"', 't') => s.push('\t'),
+                            ('"', '$') => s.push('$'),
+                            _ => {
+                                s.push('\\');
+                                s.push(escaped);
+                            }
+                        }
+                        i += 2;
+                        continue;
+                    }
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                tokens.push((Php::Str(s), start_line));
+                i += 1;
+            }
+            '$' if next.is_some_and(is_ident_char) => {
+                let end = skip_while(&chars, i + 1, is_ident_char);
+                tokens.push((Php::Variable(chars[i + 1..end].iter().collect()), line));
+                i = end;
+            }
+            _ if ch.is_ascii_digit() => {
+                let end = skip_while(&chars, i, |c| c.is_ascii_digit() || c == '.' || c == '_');
+                let n: String = chars[i..end].iter().filter(|c| **c != '_').collect();
+                tokens.push((Php::Number(n), line));
+                i = end;
+            }
+            _ if is_ident_char(ch) || ch == '\\' => {
+                let end = skip_while(&chars, i, |c| is_ident_char(c) || c == '\\');
+                tokens.push((Php::Ident(chars[i..end].iter().collect()), line));
+                i = end;
+            }
+            _ => {
+                let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                if ["->", "::", "=>"].contains(&two.as_str()) {
+                    tokens.push((Php::Symbol(two), line));
+                    i += 2;
+                } else {
+                    tokens.push((Php::Symbol(ch.to_string()), line));
+                    i += 1;
+                }
+            }
+        }
+    }
+    return tokens;
+}
+
+fn is_ident_char(c: char) -> bool {
+    return c.is_alphanumeric() || c == '_';
+}
+
+fn skip_while(chars: &[char], start: usize, f: impl Fn(char) -> bool) -> usize {
+    let mut i = start;
+    while i < chars.len() && f(chars[i]) {
+        i += 1;
+    }
+    return i;
+}
+
+// the position of the newline
+fn skip_line(chars: &[char], start: usize) -> usize {
+    return skip_while(chars, start, |c| c != '\n');
+}
@@ -0,0 +1,206 @@
+use std::convert::TryFrom;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::app::converter::mig::{Column, ColumnType, Index, IndexKind, Schema, Table, Value};
+use crate::app::converter::parser::is_mig_name;
+use crate::app::converter::token::Token;
+use crate::app::diff::to_type_option;
+use crate::app::formatter::format_file;
+use crate::parse_str;
+
+pub mod laravel;
+pub mod sql;
+pub mod sqlite;
+
+// mig-files are made from the schema of the existing migrations.
+// the constructs which mig cannot express are dropped with the warnings.
+
+/// tables read from the other format and the warnings of the constructs which are not imported
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Imported {
+    pub schema: Schema,
+    pub warnings: Vec<String>,
+}
+
+/// mig-file of the table in the canonical style. the values which mig cannot write are warned and dropped.
+pub fn to_mig_file(table: &Table, warnings: &mut Vec<String>) -> String {
+    let mut lines = vec!();
+    for column in table.columns.iter() {
+        let mut options = vec!(to_type_option(&column.column_type));
+        if column.unsigned && column.column_type.is_number() && !column.column_type.is_auto_increment() {
+            options.push(":unsigned".to_string());
+        }
+        if column.nullable {
+            options.push(":nullable".to_string());
+        }
+        if let Some(value) = &column.default {
+            match to_token(value) {
+                Some(token) => options.push(format!(":default {}", token.to_source())),
+                None => warnings.push(format!("default of {}.{} cannot be written in mig-file", table.name, column.name)),
+            }
+        }
+        if let Some(comment) = &column.comment {
+            match to_string_token(comment) {
+                Some(token) => options.push(format!(":comment {}", token.to_source())),
+                None => warnings.push(format!("comment of {}.{} cannot be written in mig-file", table.name, column.name)),
+            }
+        }
+        if let Some(foreign) = &column.references {
+            options.push(format!(":references {} {}", foreign.table, foreign.column));
+        }
+        // the index of only this column is the option of the column
+        for index in table.indexes.iter().filter(|index| is_column_index(index, column)) {
+            options.push(to_index_option(index.kind).to_string());
+        }
+        lines.push(format!("{} {{ {} }}", column.name, options.join(" ")));
+    }
+    if table.timestamps {
+        lines.push(":timestamps".to_string());
+    }
+    if table.soft_deletes {
+        lines.push(":soft-deletes".to_string());
+    }
+    for index in table.indexes.iter() {
+        if table.columns.iter().any(|column| is_column_index(index, column)) {
+            continue;
+        }
+        let kind = match index.kind {
+            IndexKind::Index => ":index",
+            IndexKind::Unique => ":unique-index",
+            IndexKind::Primary => ":primary-key",
+        };
+        let mut body = format!(":target {}", index.columns.join(" "));
+        match index.name.as_deref().map(|name| (name, to_string_token(name))) {
+            Some((_, Some(token))) => body.push_str(&format!(" :name {}", token.to_source())),
+            Some((name, None)) => warnings.push(format!("index name {} cannot be written in mig-file", name)),
+            None => {}
+        }
+        lines.push(format!("{} {{ {} }}", kind, body));
+    }
+    if table.columns.is_empty() {
+        warnings.push(format!("table {} has no column. add a column to check it", table.name));
+    }
+
+    let text = format!(":create {} {{\n{}\n}}\n", table.name, lines.join("\n"));
+    // the text is made from the valid names and tokens
+    return match parse_str(&text) {
+        Ok(file) => format_file(&file),
+        Err(_) => text,
+    };
+}
+
+/// the columns and the indexes which mig cannot read are removed with the warnings
+pub fn retain_valid_names(imported: &mut Imported) {
+    let warnings = &mut imported.warnings;
+    imported.schema.tables.retain(|table| {
+        if !is_mig_name(&table.name) {
+            warnings.push(format!("table {} is not imported. mig name has only ascii alphanumerics and _", table.name));
+        }
+        return is_mig_name(&table.name);
+    });
+    for table in imported.schema.tables.iter_mut() {
+        let table_name = table.name.clone();
+        table.columns.retain(|column| {
+            if !is_mig_name(&column.name) {
+                warnings.push(format!(
+                    "column {}.{} is not imported. mig name has only ascii alphanumerics and _",
+                    table_name, column.name,
+                ));
+            }
+            return is_mig_name(&column.name);
+        });
+        for column in table.columns.iter_mut() {
+            let valid = column.references.as_ref()
+                .is_none_or(|foreign| is_mig_name(&foreign.table) && is_mig_name(&foreign.column));
+            if !valid {
+                warnings.push(format!("foreign key of {}.{} is not imported", table_name, column.name));
+                column.references = None;
+            }
+        }
+        let columns: Vec<String> = table.columns.iter().map(|column| column.name.clone()).collect();
+        let (timestamps, soft_deletes) = (table.timestamps, table.soft_deletes);
+        table.indexes.retain(|index| {
+            let valid = index.columns.iter().all(|name| {
+                columns.contains(name)
+                    || (timestamps && (name == "created_at" || name == "updated_at"))
+                    || (soft_deletes && name == "deleted_at")
+            });
+            if !valid {
+                warnings.push(format!("index of {}({}) is not imported", table_name, index.columns.join(", ")));
+            }
+            return valid;
+        });
+    }
+}
+
+/// the columns of :timestamps and :soft-deletes which are made by the generators are the table options
+pub(crate) fn to_table_options(table: &mut Table) {
+    let is_option_column = |table: &Table, name: &str| table.get_column(name).is_some_and(|column| {
+        column.column_type == ColumnType::Timestamp && column.nullable && column.default.is_none()
+            && column.comment.is_none() && column.references.is_none()
+    });
+    if !table.timestamps && is_option_column(table, "created_at") && is_option_column(table, "updated_at") {
+        table.columns.retain(|column| column.name != "created_at" && column.name != "updated_at");
+        table.timestamps = true;
+    }
+    if !table.soft_deletes && is_option_column(table, "deleted_at") {
+        table.columns.retain(|column| column.name != "deleted_at");
+        table.soft_deletes = true;
+    }
+}
+
+/// value of the default which is written as a string literal. e.g. '0' of the integer column
+pub(crate) fn to_default_value(s: &str, column_type: &ColumnType) -> Option<Value> {
+    let value = match column_type {
+        _ if column_type.is_integer() || *column_type == ColumnType::Boolean => Value::Integer(s.parse().ok()?),
+        _ if column_type.is_number() => match s.parse::<i64>() {
+            Ok(i) => Value::Integer(i),
+            Err(_) => Value::Double(s.parse().ok()?),
+        },
+        ColumnType::Date => Value::Date(NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?),
+        ColumnType::Time => Value::Time(NaiveTime::parse_from_str(s, "%H:%M:%S%.f").ok()?),
+        ColumnType::DateTime | ColumnType::Timestamp =>
+            Value::DateTime(NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").ok()?),
+        _ => Value::String(s.to_string()),
+    };
+    return if column_type.accepts(&value) { Some(value) } else { None };
+}
+
+fn is_column_index(index: &Index, column: &Column) -> bool {
+    return index.name.is_none() && index.columns.len() == 1 && index.columns[0] == column.name;
+}
+
+fn to_index_option(kind: IndexKind) -> &'static str {
+    return match kind {
+        IndexKind::Index => ":index",
+        IndexKind::Unique => ":unique",
+        IndexKind::Primary => ":primary",
+    };
+}
+
+// None if the lexer cannot read the value. e.g. integer out of i16
+fn to_token(value: &Value) -> Option<Token> {
+    return match value {
+        Value::Integer(i) => i16::try_from(*i).ok().map(Token::Integer),
+        Value::Double(d) => {
+            let f = *d as f32;
+            if f.is_finite() { Some(Token::Double(f)) } else { None }
+        }
+        Value::String(s) => to_string_token(s),
+        Value::Date(d) if (1000..=9999).contains(&d.year()) => Some(Token::Ymd(*d)),
+        Value::Time(t) => Some(Token::Time(*t)),
+        Value::DateTime(dt) if (1000..=9999).contains(&dt.year()) => Some(Token::DateTime(*dt, None)),
+        Value::DateTimeTz(dt) if (1000..=9999).contains(&dt.year()) =>
+            Some(Token::DateTime(dt.naive_local(), Some(*dt.offset()))),
+        _ => None,
+    };
+}
+
+// string of mig-file has no escape
+fn to_string_token(s: &str) -> Option<Token> {
+    if s.contains('"') || s.contains('\n') {
+        return None;
+    }
+    return Some(Token::String(s.to_string()));
+}
@@ -0,0 +1,847 @@
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Table, Value};
+use crate::app::diff::default_index_name;
+use crate::app::import::{Imported, to_default_value, to_table_options};
+
+// best-effort reader of the schema dump. e.g. pg_dump --schema-only, mysqldump --no-data and .schema of sqlite.
+// CREATE TABLE, CREATE INDEX, ALTER TABLE and COMMENT ON COLUMN are read. the other statements are skipped.
+
+/// tables of the statements in the sql files. sources are (file name, content) in order.
+pub fn import_schema(sources: &[(String, String)]) -> Imported {
+    let mut imported = Imported::default();
+    for (file_name, src) in sources.iter() {
+        let tokens = tokenize(src);
+        let mut reader = Reader { imported: &mut imported, file_name };
+        for statement in tokens.split(|t| t.0 == symbol(";")).filter(|s| !s.is_empty()) {
+            reader.read_statement(statement);
+        }
+    }
+    for table in imported.schema.tables.iter_mut() {
+        to_table_options(table);
+    }
+    return imported;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Sql {
+    Word(String),
+    // "name", `name` or [name]
+    Quoted(String),
+    Str(String),
+    Number(String),
+    Symbol(String),
+}
+
+// token and the line
+type Spanned = (Sql, usize);
+
+struct Reader<'a> {
+    imported: &'a mut Imported,
+    file_name: &'a str,
+}
+
+impl<'a> Reader<'a> {
+    fn warn(&mut self, line: usize, message: String) {
+        self.imported.warnings.push(format!("{}:{}: {}", self.file_name, line, message));
+    }
+
+    fn read_statement(&mut self, tokens: &[Spanned]) {
+        let line = tokens[0].1;
+        let mut cursor = Cursor { tokens, i: 0 };
+        if cursor.eat_keyword("CREATE") {
+            let _ = cursor.eat_keyword("TEMPORARY") || cursor.eat_keyword("TEMP");
+            if cursor.eat_keyword("TABLE") {
+                self.read_create_table(&mut cursor, line);
+                return;
+            }
+            let unique = cursor.eat_keyword("UNIQUE");
+            if cursor.eat_keyword("INDEX") {
+                self.read_create_index(&mut cursor, unique, line);
+                return;
+            }
+            // sequences are read as the default of the serial column
+            if cursor.eat_keyword("SEQUENCE") || cursor.eat_keyword("SCHEMA") || cursor.eat_keyword("DATABASE") {
+                return;
+            }
+            let kind = cursor.peek_word().unwrap_or_default().to_uppercase();
+            self.warn(line, format!("CREATE {} is not imported", kind));
+        } else if cursor.eat_keyword("ALTER") && cursor.eat_keyword("TABLE") {
+            self.read_alter_table(&mut cursor, line);
+        } else if cursor.eat_keyword("COMMENT") && cursor.eat_keyword("ON") && cursor.eat_keyword("COLUMN") {
+            self.read_comment(&mut cursor, line);
+        }
+        // SET, DROP, INSERT and so on of the dump
+    }
+
+    fn read_create_table(&mut self, cursor: &mut Cursor, line: usize) {
+        let _ = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = match cursor.name() {
+            Some(name) => name,
+            None => return self.warn(line, "CREATE TABLE without the name is not imported".to_string()),
+        };
+        // internal tables of sqlite
+        if name.starts_with("sqlite_") {
+            return;
+        }
+        let items = match cursor.parens() {
+            Some(items) => items,
+            None => return self.warn(line, format!("table {} without the columns is not imported", name)),
+        };
+        if self.imported.schema.get_table(&name).is_some() {
+            self.warn(line, format!("table {} is created again", name));
+            self.imported.schema.tables.retain(|t| t.name != name);
+        }
+        let mut table = Table::new(Method::Create, &name);
+        for item in split_commas(items) {
+            let line = item.first().map(|t| t.1).unwrap_or(line);
+            let mut item_cursor = Cursor { tokens: item, i: 0 };
+            if is_constraint(&item_cursor) {
+                self.read_constraint(&mut table, &mut item_cursor, line);
+            } else {
+                self.read_column(&mut table, &mut item_cursor, line);
+            }
+        }
+        self.imported.schema.tables.push(table);
+    }
+
+    // name type constraints...
+    fn read_column(&mut self, table: &mut Table, cursor: &mut Cursor, line: usize) {
+        let name = match cursor.name() {
+            Some(name) => name,
+            None => return self.warn(line, format!("column definition of {} is not imported", table.name)),
+        };
+        let type_name = cursor.type_name();
+        if type_name.is_empty() {
+            return self.warn(line, format!("column {}.{} without the type is not imported", table.name, name));
+        }
+        let (mut column_type, mut unsigned) = match to_column_type(&type_name) {
+            Some(column_type) => column_type,
+            None => {
+                let message = format!("type {} of {}.{} is not imported", type_name.to_lowercase(), table.name, name);
+                return self.warn(line, message);
+            }
+        };
+        if type_name.to_uppercase().starts_with("ENUM") || type_name.to_uppercase().starts_with("SET") {
+            self.warn(line, format!("{} of {}.{} is imported as :string", type_name.to_lowercase(), table.name, name));
+        }
+        let mut column = Column::new(&name, column_type);
+        // columns are NULL by default in sql
+        column.nullable = true;
+        let mut primary = false;
+        let mut auto_increment = column_type.is_auto_increment();
+        while !cursor.is_end() {
+            if cursor.eat_keyword("UNSIGNED") {
+                unsigned = true;
+            } else if cursor.eat_keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if cursor.eat_keyword("NULL") {
+                column.nullable = true;
+            } else if cursor.eat_keyword("DEFAULT") {
+                let default = cursor.expression();
+                if is_sequence(&default) {
+                    auto_increment = true;
+                    continue;
+                }
+                match to_value(&default, &column.column_type) {
+                    Some(Some(value)) => column.default = Some(value),
+                    Some(None) => {}
+                    None => self.warn(line, format!("default of {}.{} is not imported", table.name, name)),
+                }
+            } else if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+                primary = true;
+            } else if cursor.eat_keyword("UNIQUE") {
+                let _ = cursor.eat_keyword("KEY");
+                add_index(table, IndexKind::Unique, vec!(name.clone()), None);
+            } else if cursor.eat_keyword("AUTO_INCREMENT") || cursor.eat_keyword("AUTOINCREMENT") {
+                auto_increment = true;
+            } else if cursor.eat_keyword("COMMENT") {
+                match cursor.next() {
+                    Some(Sql::Str(comment)) => column.comment = Some(comment),
+                    _ => self.warn(line, format!("comment of {}.{} is not imported", table.name, name)),
+                }
+            } else if cursor.eat_keyword("REFERENCES") {
+                match self.read_references(cursor, line) {
+                    Some(foreign) => column.references = Some(foreign),
+                    None => self.warn(line, format!("foreign key of {}.{} is not imported", table.name, name)),
+                }
+            } else if cursor.eat_keywords(&["ON", "UPDATE"]) {
+                let _ = cursor.next();
+                self.warn(line, format!("ON UPDATE of {}.{} is not imported", table.name, name));
+            } else if cursor.eat_keyword("CONSTRAINT") || cursor.eat_keyword("COLLATE")
+                || cursor.eat_keywords(&["CHARACTER", "SET"]) || cursor.eat_keyword("CHARSET") {
+                let _ = cursor.name();
+            } else {
+                let word = cursor.peek_word().unwrap_or_default().to_uppercase();
+                self.warn(line, format!("{} of {}.{} is not imported", word, table.name, name));
+                cursor.skip_to_end();
+            }
+        }
+        // serial, AUTO_INCREMENT and INTEGER PRIMARY KEY AUTOINCREMENT are the increments
+        if auto_increment {
+            column_type = match column_type {
+                ColumnType::BigInteger | ColumnType::BigIncrements => ColumnType::BigIncrements,
+                _ => ColumnType::Increments,
+            };
+            column.column_type = column_type;
+            column.nullable = false;
+            column.default = None;
+            unsigned = false;
+        } else if primary {
+            add_index(table, IndexKind::Primary, vec!(name.clone()), None);
+            column.nullable = false;
+        }
+        column.unsigned = unsigned && column_type.is_number() && !column_type.is_auto_increment();
+        table.columns.push(column);
+    }
+
+    // PRIMARY KEY, UNIQUE, KEY, INDEX and FOREIGN KEY with or without CONSTRAINT name
+    fn read_constraint(&mut self, table: &mut Table, cursor: &mut Cursor, line: usize) {
+        let mut name = if cursor.eat_keyword("CONSTRAINT") { cursor.name() } else { None };
+        let kind = if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+            IndexKind::Primary
+        } else if cursor.eat_keyword("UNIQUE") {
+            let _ = cursor.eat_keyword("KEY") || cursor.eat_keyword("INDEX");
+            IndexKind::Unique
+        } else if cursor.eat_keyword("KEY") || cursor.eat_keyword("INDEX") {
+            IndexKind::Index
+        } else if cursor.eat_keywords(&["FOREIGN", "KEY"]) {
+            let _ = cursor.name_before_parens();
+            let columns = cursor.name_list().unwrap_or_default();
+            let foreign = if cursor.eat_keyword("REFERENCES") { self.read_references(cursor, line) } else { None };
+            return match (columns.as_slice(), foreign) {
+                ([column], Some(foreign)) => match table.columns.iter_mut().find(|c| c.name == *column) {
+                    Some(c) => c.references = Some(foreign),
+                    None => self.warn(line, format!("column {}.{} of the foreign key is not found", table.name, column)),
+                },
+                _ => self.warn(line, format!(
+                    "foreign key of {}({}) is not imported. mig has the foreign key of one column",
+                    table.name, columns.join(", "),
+                )),
+            };
+        } else {
+            let word = cursor.peek_word().unwrap_or_default().to_uppercase();
+            return self.warn(line, format!("{} constraint of {} is not imported", word, table.name));
+        };
+        // mysql has the name after KEY. e.g. UNIQUE KEY `users_email_unique` (`email`)
+        if let Some(key_name) = cursor.name_before_parens() {
+            name = Some(key_name);
+        }
+        let _ = cursor.eat_keywords(&["USING", "BTREE"]);
+        match cursor.name_list() {
+            Some(columns) => {
+                // the primary key of the auto increment column is implicit
+                let implicit = kind == IndexKind::Primary && columns.len() == 1
+                    && table.get_column(&columns[0]).is_some_and(|c| c.column_type.is_auto_increment());
+                if !implicit {
+                    add_index(table, kind, columns, name);
+                }
+            }
+            None => self.warn(line, format!("index of {} with the expressions is not imported", table.name)),
+        }
+    }
+
+    // table (column) and the actions which are not imported
+    fn read_references(&mut self, cursor: &mut Cursor, line: usize) -> Option<ForeignKey> {
+        let table = cursor.name_before_parens().or_else(|| cursor.name())?;
+        let column = match cursor.name_list() {
+            Some(columns) if columns.len() == 1 => columns[0].clone(),
+            Some(_) => return None,
+            None => "id".to_string(),
+        };
+        while cursor.eat_keyword("ON") {
+            let action = cursor.peek_word().unwrap_or_default().to_uppercase();
+            self.warn(line, format!("ON {} of the foreign key to {} is not imported", action, table));
+            let _ = cursor.next();
+            // CASCADE, RESTRICT, SET NULL, SET DEFAULT and NO ACTION
+            let _ = cursor.eat_keyword("SET") || cursor.eat_keyword("NO");
+            let _ = cursor.next();
+        }
+        return Some(ForeignKey { table, column });
+    }
+
+    // CREATE [UNIQUE] INDEX name ON table (columns)
+    fn read_create_index(&mut self, cursor: &mut Cursor, unique: bool, line: usize) {
+        let _ = cursor.eat_keyword("CONCURRENTLY");
+        let _ = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = if cursor.peek_keyword("ON") { None } else { cursor.name() };
+        if !cursor.eat_keyword("ON") {
+            return self.warn(line, "CREATE INDEX without the table is not imported".to_string());
+        }
+        let _ = cursor.eat_keyword("ONLY");
+        let table_name = cursor.name().unwrap_or_default();
+        if cursor.eat_keyword("USING") {
+            let _ = cursor.next();
+        }
+        let columns = cursor.name_list();
+        if cursor.eat_keyword("WHERE") {
+            return self.warn(line, format!("partial index of {} is not imported", table_name));
+        }
+        let kind = if unique { IndexKind::Unique } else { IndexKind::Index };
+        match (self.imported.schema.tables.iter_mut().find(|t| t.name == table_name), columns) {
+            (Some(table), Some(columns)) => add_index(table, kind, columns, name),
+            (None, _) => self.warn(line, format!("table {} of the index is not found", table_name)),
+            (_, None) => self.warn(line, format!("index of {} with the expressions is not imported", table_name)),
+        }
+    }
+
+    // ALTER TABLE [ONLY] table ADD ..., ALTER COLUMN ... SET DEFAULT ...
+    fn read_alter_table(&mut self, cursor: &mut Cursor, line: usize) {
+        let _ = cursor.eat_keywords(&["IF", "EXISTS"]);
+        let _ = cursor.eat_keyword("ONLY");
+        let name = cursor.name().unwrap_or_default();
+        let position = match self.imported.schema.tables.iter().position(|t| t.name == name) {
+            Some(position) => position,
+            None => return self.warn(line, format!("table {} is not created before ALTER TABLE", name)),
+        };
+        let mut table = self.imported.schema.tables.remove(position);
+        let actions = cursor.rest();
+        for action in split_commas(actions) {
+            let mut cursor = Cursor { tokens: action, i: 0 };
+            if cursor.eat_keyword("ADD") {
+                if cursor.eat_keyword("COLUMN") || !is_constraint(&cursor) {
+                    self.read_column(&mut table, &mut cursor, line);
+                } else {
+                    self.read_constraint(&mut table, &mut cursor, line);
+                }
+            } else if cursor.eat_keyword("ALTER") {
+                let _ = cursor.eat_keyword("COLUMN");
+                let column_name = cursor.name().unwrap_or_default();
+                // the serial column of pg_dump. e.g. ALTER COLUMN id SET DEFAULT nextval('users_id_seq'::regclass)
+                if cursor.eat_keywords(&["SET", "DEFAULT"]) && is_sequence(&cursor.expression()) {
+                    if let Some(column) = table.columns.iter_mut().find(|c| c.name == column_name) {
+                        column.column_type = match column.column_type {
+                            ColumnType::BigInteger => ColumnType::BigIncrements,
+                            _ => ColumnType::Increments,
+                        };
+                        column.unsigned = false;
+                    }
+                    continue;
+                }
+                self.warn(line, format!("ALTER COLUMN {}.{} is not imported", table.name, column_name));
+            } else if cursor.eat_keyword("OWNER") || cursor.eat_keyword("ENABLE") || cursor.eat_keyword("DISABLE") {
+                // settings of the dump
+            } else {
+                let word = cursor.peek_word().unwrap_or_default().to_uppercase();
+                self.warn(line, format!("ALTER TABLE {} {} is not imported", table.name, word));
+            }
+        }
+        // the primary key which is added after the serial column is implicit
+        let increments: Vec<String> = table.columns.iter()
+            .filter(|c| c.column_type.is_auto_increment())
+            .map(|c| c.name.clone())
+            .collect();
+        table.indexes.retain(|index| !(index.kind == IndexKind::Primary && index.columns.len() == 1
+            && increments.contains(&index.columns[0])));
+        self.imported.schema.tables.insert(position, table);
+    }
+
+    // COMMENT ON COLUMN table.column IS 'comment'
+    fn read_comment(&mut self, cursor: &mut Cursor, line: usize) {
+        let names = cursor.qualified_name();
+        let comment = if cursor.eat_keyword("IS") { cursor.next() } else { None };
+        let (table_name, column_name) = match names.as_slice() {
+            [.., table, column] => (table, column),
+            _ => return,
+        };
+        let column = self.imported.schema.tables.iter_mut()
+            .find(|t| t.name == *table_name)
+            .and_then(|t| t.columns.iter_mut().find(|c| c.name == *column_name));
+        match (column, comment) {
+            (Some(column), Some(Sql::Str(comment))) => column.comment = Some(comment),
+            (Some(column), _) => column.comment = None,
+            (None, _) => self.warn(line, format!("column {}.{} of the comment is not found", table_name, column_name)),
+        }
+    }
+}
+
+struct Cursor<'a> {
+    tokens: &'a [Spanned],
+    i: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn is_end(&self) -> bool {
+        return self.i >= self.tokens.len();
+    }
+
+    fn peek(&self) -> Option<&Sql> {
+        return self.tokens.get(self.i).map(|t| &t.0);
+    }
+
+    fn next(&mut self) -> Option<Sql> {
+        let token = self.peek().cloned();
+        self.i += 1;
+        return token;
+    }
+
+    fn peek_word(&self) -> Option<String> {
+        return match self.peek() {
+            Some(Sql::Word(word)) => Some(word.clone()),
+            _ => None,
+        };
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        return matches!(self.peek(), Some(Sql::Word(word)) if word.eq_ignore_ascii_case(keyword));
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.i += 1;
+            return true;
+        }
+        return false;
+    }
+
+    // all keywords in order, or nothing is eaten
+    fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        let start = self.i;
+        if keywords.iter().all(|keyword| self.eat_keyword(keyword)) {
+            return true;
+        }
+        self.i = start;
+        return false;
+    }
+
+    fn eat_symbol(&mut self, s: &str) -> bool {
+        if self.peek() == Some(&symbol(s)) {
+            self.i += 1;
+            return true;
+        }
+        return false;
+    }
+
+    // schema.table.column
+    fn qualified_name(&mut self) -> Vec<String> {
+        let mut names = vec!();
+        loop {
+            match self.peek() {
+                Some(Sql::Word(name)) | Some(Sql::Quoted(name)) => names.push(name.clone()),
+                _ => return names,
+            }
+            self.i += 1;
+            if !self.eat_symbol(".") {
+                return names;
+            }
+        }
+    }
+
+    // the last of the qualified name. e.g. users of public.users
+    fn name(&mut self) -> Option<String> {
+        return self.qualified_name().pop();
+    }
+
+    // the name if the parentheses follow it
+    fn name_before_parens(&mut self) -> Option<String> {
+        let start = self.i;
+        if let Some(name) = self.name() {
+            if self.peek() == Some(&symbol("(")) {
+                return Some(name);
+            }
+        }
+        self.i = start;
+        return None;
+    }
+
+    // tokens in the parentheses
+    fn parens(&mut self) -> Option<&'a [Spanned]> {
+        if self.peek() != Some(&symbol("(")) {
+            return None;
+        }
+        let close = find_close(self.tokens, self.i)?;
+        let inner = &self.tokens[self.i + 1..close];
+        self.i = close + 1;
+        return Some(inner);
+    }
+
+    // (a, b DESC). None if the list has the expressions
+    fn name_list(&mut self) -> Option<Vec<String>> {
+        let items = self.parens()?;
+        let mut names = vec!();
+        for item in split_commas(items) {
+            let mut cursor = Cursor { tokens: item, i: 0 };
+            names.push(cursor.name()?);
+            // prefix length of mysql. e.g. `name`(10)
+            if let Some(length) = cursor.parens() {
+                if !matches!(length, [(Sql::Number(_), _)]) {
+                    return None;
+                }
+            }
+            let _ = cursor.eat_keyword("ASC") || cursor.eat_keyword("DESC");
+            if !cursor.is_end() {
+                return None;
+            }
+        }
+        return Some(names);
+    }
+
+    // e.g. varchar(10), double precision, timestamp(0) without time zone, int(11)
+    fn type_name(&mut self) -> String {
+        let mut type_name = String::new();
+        while let Some(Sql::Word(word)) = self.peek() {
+            if !type_name.is_empty() && !is_type_word(word) {
+                break;
+            }
+            if !type_name.is_empty() {
+                type_name.push(' ');
+            }
+            type_name.push_str(word);
+            self.i += 1;
+            if let Some(args) = self.parens() {
+                let args: Vec<String> = args.iter().map(|t| to_source(&t.0)).collect();
+                type_name.push_str(&format!("({})", args.join("")));
+            }
+            // the array of postgres. e.g. integer[]
+            if self.peek() == Some(&symbol("[")) {
+                type_name.push_str("[]");
+                self.skip_to_end();
+            }
+        }
+        return type_name;
+    }
+
+    // the tokens of the default value until the next constraint
+    fn expression(&mut self) -> Vec<Sql> {
+        let start = self.i;
+        if self.parens().is_some() {
+            return self.tokens[start..self.i].iter().map(|t| t.0.clone()).collect();
+        }
+        let mut tokens = vec!();
+        while let Some(token) = self.peek().cloned() {
+            let is_keyword = matches!(&token, Sql::Word(word) if is_constraint_word(word));
+            if is_keyword && !tokens.is_empty() {
+                break;
+            }
+            tokens.push(token);
+            self.i += 1;
+            if self.parens().is_some() {
+                tokens.push(Sql::Symbol("()".to_string()));
+            }
+            // cast of postgres. e.g. 'a'::character varying
+            if self.eat_symbol("::") {
+                let _ = self.type_name();
+            }
+            // operators continue the expression. e.g. - 1
+            let continues = matches!(self.peek(), Some(Sql::Symbol(s)) if s != "(")
+                || (tokens.len() == 1 && matches!(tokens[0], Sql::Symbol(_)));
+            if !continues {
+                break;
+            }
+        }
+        return tokens;
+    }
+
+    fn rest(&mut self) -> &'a [Spanned] {
+        let rest = &self.tokens[self.i.min(self.tokens.len())..];
+        self.i = self.tokens.len();
+        return rest;
+    }
+
+    fn skip_to_end(&mut self) {
+        self.i = self.tokens.len();
+    }
+}
+
+fn symbol(s: &str) -> Sql {
+    return Sql::Symbol(s.to_string());
+}
+
+fn to_source(token: &Sql) -> String {
+    return match token {
+        Sql::Word(s) | Sql::Number(s) | Sql::Symbol(s) => s.clone(),
+        Sql::Quoted(s) => format!("\"{}\"", s),
+        Sql::Str(s) => format!("'{}'", s),
+    };
+}
+
+fn is_constraint(cursor: &Cursor) -> bool {
+    return ["CONSTRAINT", "PRIMARY", "UNIQUE", "KEY", "INDEX", "FOREIGN", "CHECK", "FULLTEXT", "SPATIAL", "EXCLUDE"]
+        .iter()
+        .any(|keyword| cursor.peek_keyword(keyword));
+}
+
+// words after the default value
+fn is_constraint_word(word: &str) -> bool {
+    return ["NOT", "NULL", "PRIMARY", "UNIQUE", "REFERENCES", "CONSTRAINT", "COMMENT", "CHECK", "COLLATE",
+        "AUTO_INCREMENT", "AUTOINCREMENT", "ON", "GENERATED", "CHARACTER", "CHARSET"]
+        .iter()
+        .any(|keyword| word.eq_ignore_ascii_case(keyword));
+}
+
+// words which make the type with the previous word
+fn is_type_word(word: &str) -> bool {
+    return ["PRECISION", "VARYING", "WITHOUT", "WITH", "TIME", "ZONE", "UNSIGNED", "ZEROFILL"]
+        .iter()
+        .any(|keyword| word.eq_ignore_ascii_case(keyword));
+}
+
+// nextval('users_id_seq'::regclass)
+fn is_sequence(expression: &[Sql]) -> bool {
+    return matches!(expression.first(), Some(Sql::Word(word)) if word.eq_ignore_ascii_case("nextval"));
+}
+
+// (column type, unsigned) of the sql type
+pub(crate) fn to_column_type(type_name: &str) -> Option<(ColumnType, bool)> {
+    let upper = type_name.to_uppercase();
+    let unsigned = upper.contains("UNSIGNED");
+    // the first word is the type. e.g. timestamp(0) with time zone
+    let base = upper.split(['(', ' ']).next().unwrap_or("");
+    let args = upper.split_once('(').and_then(|(_, rest)| rest.split(')').next()).unwrap_or("");
+    let params: Vec<u16> = args.split(',').filter_map(|p| p.trim().parse().ok()).collect();
+    let column_type = match base {
+        "SERIAL" | "SMALLSERIAL" | "SERIAL4" | "SERIAL2" => ColumnType::Increments,
+        "BIGSERIAL" | "SERIAL8" => ColumnType::BigIncrements,
+        "TINYINT" if params == [1] => ColumnType::Boolean,
+        "TINYINT" => ColumnType::TinyInteger,
+        "SMALLINT" | "INT2" => ColumnType::SmallInteger,
+        "INT" | "INTEGER" | "INT4" | "MEDIUMINT" => ColumnType::Integer,
+        "BIGINT" | "INT8" => ColumnType::BigInteger,
+        "REAL" | "FLOAT" | "FLOAT4" => ColumnType::Float,
+        "DOUBLE" | "FLOAT8" => ColumnType::Double,
+        "DECIMAL" | "NUMERIC" => match params.as_slice() {
+            [precision, scale] => ColumnType::Decimal { precision: *precision, scale: *scale },
+            [precision] => ColumnType::Decimal { precision: *precision, scale: 0 },
+            _ => ColumnType::Decimal { precision: 8, scale: 2 },
+        },
+        "BOOLEAN" | "BOOL" => ColumnType::Boolean,
+        "CHAR" | "CHARACTER" | "BPCHAR" | "NCHAR" if upper.contains("VARYING") =>
+            ColumnType::String { length: params.first().cloned().filter(|length| *length != 255) },
+        "CHAR" | "CHARACTER" | "BPCHAR" | "NCHAR" => ColumnType::Char { length: params.first().cloned().unwrap_or(1) },
+        // VARCHAR(255) is :string
+        "VARCHAR" | "NVARCHAR" | "VARCHAR2" =>
+            ColumnType::String { length: params.first().cloned().filter(|length| *length != 255) },
+        "ENUM" | "SET" => ColumnType::String { length: None },
+        "UUID" => ColumnType::Char { length: 36 },
+        "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "CLOB" | "CITEXT" => ColumnType::Text,
+        "DATE" => ColumnType::Date,
+        "TIME" | "TIMETZ" => ColumnType::Time,
+        "DATETIME" => ColumnType::DateTime,
+        "TIMESTAMP" | "TIMESTAMPTZ" => ColumnType::Timestamp,
+        "JSON" | "JSONB" => ColumnType::Json,
+        "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BYTEA" | "BINARY" | "VARBINARY" => ColumnType::Binary,
+        _ => return None,
+    };
+    return Some((column_type, unsigned));
+}
+
+/// default of the column which is written in sql. e.g. 'a', 0, NULL. None if it is not a literal.
+pub(crate) fn to_default(expression: &str, column_type: &ColumnType) -> Option<Option<Value>> {
+    let tokens: Vec<Sql> = tokenize(expression).into_iter().map(|t| t.0).collect();
+    return to_value(&tokens, column_type);
+}
+
+// Some(None) is NULL. None if the value is not a literal. e.g. CURRENT_TIMESTAMP
+fn to_value(expression: &[Sql], column_type: &ColumnType) -> Option<Option<Value>> {
+    // ((0)) of sqlite and postgres
+    let mut tokens = expression;
+    while let [Sql::Symbol(open), inner @ .., Sql::Symbol(close)] = tokens {
+        if open != "(" || close != ")" {
+            break;
+        }
+        tokens = inner;
+    }
+    let value = match tokens {
+        [Sql::Word(word)] if word.eq_ignore_ascii_case("NULL") => return Some(None),
+        [Sql::Word(word)] if word.eq_ignore_ascii_case("TRUE") => Value::Integer(1),
+        [Sql::Word(word)] if word.eq_ignore_ascii_case("FALSE") => Value::Integer(0),
+        [Sql::Number(n)] => to_number(n)?,
+        [Sql::Symbol(minus), Sql::Number(n)] if minus == "-" => to_number(&format!("-{}", n))?,
+        [Sql::Str(s)] => to_default_value(s, column_type)?,
+        _ => return None,
+    };
+    return if column_type.accepts(&value) { Some(Some(value)) } else { None };
+}
+
+fn to_number(n: &str) -> Option<Value> {
+    if let Ok(i) = n.parse::<i64>() {
+        return Some(Value::Integer(i));
+    }
+    return n.parse().ok().map(Value::Double);
+}
+
+/// the column of AUTOINCREMENT in CREATE TABLE of sqlite. e.g. id of `id INTEGER PRIMARY KEY AUTOINCREMENT`
+pub(crate) fn to_auto_increment_column(create_table: &str) -> Option<String> {
+    let tokens = tokenize(create_table);
+    let open = tokens.iter().position(|t| t.0 == symbol("("))?;
+    let close = find_close(&tokens, open)?;
+    let is_auto_increment = |t: &Spanned| matches!(&t.0, Sql::Word(word) if word.eq_ignore_ascii_case("AUTOINCREMENT"));
+    for item in split_commas(&tokens[open + 1..close]) {
+        if !item.iter().any(is_auto_increment) {
+            continue;
+        }
+        let mut cursor = Cursor { tokens: item, i: 0 };
+        if !is_constraint(&cursor) {
+            return cursor.name();
+        }
+        // the table constraint. e.g. PRIMARY KEY (id AUTOINCREMENT)
+        if cursor.eat_keyword("CONSTRAINT") {
+            let _ = cursor.name();
+        }
+        if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+            return Cursor { tokens: cursor.parens()?, i: 0 }.name();
+        }
+    }
+    return None;
+}
+
+// name is dropped if it is the default of the generators
+pub(crate) fn add_index(table: &mut Table, kind: IndexKind, columns: Vec<String>, name: Option<String>) {
+    let mut index = Index { kind, columns, name: None };
+    let defaults = [default_index_name(&table.name, &index), format!("{}_pkey", table.name), "PRIMARY".to_string()];
+    index.name = name.filter(|name| !defaults.contains(name));
+    if !table.indexes.contains(&index) {
+        table.indexes.push(index);
+    }
+}
+
+// items separated by `,` out of the parentheses
+fn split_commas(tokens: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut items = vec!();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, (token, _)) in tokens.iter().enumerate() {
+        match token {
+            Sql::Symbol(s) if s == "(" => depth += 1,
+            Sql::Symbol(s) if s == ")" => depth -= 1,
+            Sql::Symbol(s) if s == "," && depth == 0 => {
+                items.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        items.push(&tokens[start..]);
+    }
+    return items.into_iter().filter(|item| !item.is_empty()).collect();
+}
+
+fn find_close(tokens: &[Spanned], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, (token, _)) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Sql::Symbol(s) if s == "(" => depth += 1,
+            Sql::Symbol(s) if s == ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    return None;
+}
+
+// tokens without comments. `/*! ... */` of mysqldump is also a comment.
+fn tokenize(src: &str) -> Vec<Spanned> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec!();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let next = chars.get(i + 1).copied();
+        match ch {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            _ if ch.is_whitespace() => i += 1,
+            '-' if next == Some('-') => i = skip_while(&chars, i, |c| c != '\n'),
+            '#' => i = skip_while(&chars, i, |c| c != '\n'),
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if ch == '[' { ']' } else { ch };
+                let start_line = line;
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == close {
+                        // doubled quote
+                        if chars.get(i + 1) == Some(&close) && close != ']' {
+                            s.push(close);
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    // backslash escape of mysql
+                    if ch == '\'' && chars[i] == '\\' && i + 1 < chars.len() {
+                        s.push(match chars[i + 1] {
+                            'n' => '\n',
+                            't' => '\t',
+                            c => c,
+                        });
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                let token = if ch == '\'' { Sql::Str(s) } else { Sql::Quoted(s) };
+                tokens.push((token, start_line));
+                i += 1;
+            }
+            // dollar quoted string of postgres. e.g. $$ ... $$ of the functions
+            '$' => {
+                let tag_end = skip_while(&chars, i + 1, |c| c.is_alphanumeric() || c == '_');
+                if chars.get(tag_end) != Some(&'$') {
+                    tokens.push((symbol("$"), line));
+                    i += 1;
+                    continue;
+                }
+                let tag: String = chars[i..=tag_end].iter().collect();
+                let start_line = line;
+                let mut j = tag_end + 1;
+                while j < chars.len() && chars[j..].iter().take(tag.len()).collect::<String>() != tag {
+                    if chars[j] == '\n' {
+                        line += 1;
+                    }
+                    j += 1;
+                }
+                tokens.push((Sql::Str(chars[(tag_end + 1).min(j)..j].iter().collect()), start_line));
+                i = j + tag.len();
+            }
+            _ if ch.is_ascii_digit() => {
+                let end = skip_while(&chars, i, |c| c.is_ascii_digit() || c == '.');
+                tokens.push((Sql::Number(chars[i..end].iter().collect()), line));
+                i = end;
+            }
+            _ if ch.is_alphanumeric() || ch == '_' => {
+                let end = skip_while(&chars, i, |c| c.is_alphanumeric() || c == '_' || c == '$');
+                tokens.push((Sql::Word(chars[i..end].iter().collect()), line));
+                i = end;
+            }
+            ':' if next == Some(':') => {
+                tokens.push((symbol("::"), line));
+                i += 2;
+            }
+            _ => {
+                tokens.push((Sql::Symbol(ch.to_string()), line));
+                i += 1;
+            }
+        }
+    }
+    return tokens;
+}
+
+fn skip_while(chars: &[char], start: usize, f: impl Fn(char) -> bool) -> usize {
+    let mut i = start;
+    while i < chars.len() && f(chars[i]) {
+        i += 1;
+    }
+    return i;
+}
+
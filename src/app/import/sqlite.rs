@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use rusqlite::{Connection, params};
+
+use crate::app::converter::mig::{Column, ColumnType, ForeignKey, IndexKind, Method, Table};
+use crate::app::import::{Imported, to_table_options};
+use crate::app::import::sql::{add_index, to_auto_increment_column, to_column_type, to_default};
+
+// the schema of the sqlite database is read from sqlite_master and the pragmas of each table.
+
+/// tables of the database in order of the creation
+pub fn introspect(connection: &Connection) -> rusqlite::Result<Imported> {
+    let mut imported = Imported::default();
+    let mut statement = connection.prepare(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY rowid"
+    )?;
+    let tables = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+    for (name, sql) in tables.iter() {
+        let mut table = Table::new(Method::Create, name);
+        // AUTOINCREMENT is allowed only for INTEGER PRIMARY KEY
+        let auto_increment = to_auto_increment_column(sql);
+        read_columns(connection, &mut table, auto_increment.as_deref(), &mut imported.warnings)?;
+        read_indexes(connection, &mut table, &mut imported.warnings)?;
+        read_foreign_keys(connection, &mut table, &mut imported.warnings)?;
+        to_table_options(&mut table);
+        imported.schema.tables.push(table);
+    }
+    return Ok(imported);
+}
+
+// PRAGMA table_info is (cid, name, type, notnull, dflt_value, pk)
+fn read_columns(
+    connection: &Connection,
+    table: &mut Table,
+    auto_increment: Option<&str>,
+    warnings: &mut Vec<String>,
+) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1)")?;
+    let rows = statement
+        .query_map(params![table.name], |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, bool>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, usize>(4)?,
+        )))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    // pk is the position in the primary key
+    let mut primary: Vec<(usize, String)> = rows.iter()
+        .filter(|row| row.4 > 0)
+        .map(|row| (row.4, row.0.clone()))
+        .collect();
+    primary.sort();
+    for (name, type_name, not_null, default, pk) in rows.into_iter() {
+        let (mut column_type, unsigned) = match to_column_type(&type_name) {
+            Some(column_type) => column_type,
+            None if type_name.is_empty() => {
+                warnings.push(format!("column {}.{} without the type is not imported", table.name, name));
+                continue;
+            }
+            None => {
+                warnings.push(format!("type {} of {}.{} is not imported", type_name.to_lowercase(), table.name, name));
+                continue;
+            }
+        };
+        if type_name.to_uppercase().starts_with("ENUM") {
+            warnings.push(format!("{} of {}.{} is imported as :string", type_name.to_lowercase(), table.name, name));
+        }
+        let is_auto_increment = auto_increment.is_some_and(|column| column.eq_ignore_ascii_case(&name));
+        if is_auto_increment && pk > 0 && primary.len() == 1 && type_name.eq_ignore_ascii_case("INTEGER") {
+            column_type = ColumnType::Increments;
+        }
+        let mut column = Column::new(&name, column_type);
+        column.nullable = !not_null && !column_type.is_auto_increment();
+        column.unsigned = unsigned && column_type.is_number() && !column_type.is_auto_increment();
+        if let Some(default) = default.filter(|_| !column_type.is_auto_increment()) {
+            match to_default(&default, &column_type) {
+                Some(value) => column.default = value,
+                None => warnings.push(format!("default of {}.{} is not imported", table.name, name)),
+            }
+        }
+        table.columns.push(column);
+    }
+    if !table.columns.iter().any(|column| column.column_type.is_auto_increment()) && !primary.is_empty() {
+        let columns = primary.into_iter().map(|(_, name)| name).collect();
+        add_index(table, IndexKind::Primary, columns, None);
+    }
+    return Ok(());
+}
+
+// PRAGMA index_list is (seq, name, unique, origin, partial). origin is c of CREATE INDEX, u of UNIQUE or pk.
+fn read_indexes(connection: &Connection, table: &mut Table, warnings: &mut Vec<String>) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare("SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1) ORDER BY seq DESC")?;
+    let indexes = statement
+        .query_map(params![table.name], |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, bool>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, bool>(3)?,
+        )))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (name, unique, origin, partial) in indexes.into_iter() {
+        if origin == "pk" {
+            continue;
+        }
+        if partial {
+            warnings.push(format!("partial index {} of {} is not imported", name, table.name));
+            continue;
+        }
+        // PRAGMA index_info is (seqno, cid, name). name is NULL for the expression.
+        let mut statement = connection.prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?;
+        let columns = statement
+            .query_map(params![name], |row| row.get::<_, Option<String>>(0))?
+            .collect::<rusqlite::Result<Option<Vec<String>>>>()?;
+        let columns = match columns {
+            Some(columns) => columns,
+            None => {
+                warnings.push(format!("index {} of {} with the expressions is not imported", name, table.name));
+                continue;
+            }
+        };
+        let kind = if unique { IndexKind::Unique } else { IndexKind::Index };
+        // sqlite_autoindex_<table>_<n> is made for the UNIQUE constraint
+        let name = if name.starts_with("sqlite_autoindex_") { None } else { Some(name) };
+        add_index(table, kind, columns, name);
+    }
+    return Ok(());
+}
+
+// PRAGMA foreign_key_list is (id, seq, table, from, to, on_update, on_delete, match). id groups the columns.
+fn read_foreign_keys(connection: &Connection, table: &mut Table, warnings: &mut Vec<String>) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?1) ORDER BY id, seq"
+    )?;
+    let rows = statement
+        .query_map(params![table.name], |row| Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+        )))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut foreign_keys = BTreeMap::new();
+    for row in rows.into_iter() {
+        foreign_keys.entry(row.0).or_insert_with(Vec::new).push(row);
+    }
+    for (_, rows) in foreign_keys.into_iter() {
+        let (_, to_table, from, to, on_update, on_delete) = &rows[0];
+        if rows.len() > 1 {
+            let columns: Vec<&str> = rows.iter().map(|row| row.2.as_str()).collect();
+            warnings.push(format!(
+                "foreign key of {}({}) is not imported. mig has the foreign key of one column",
+                table.name, columns.join(", "),
+            ));
+            continue;
+        }
+        for (action, rule) in [("UPDATE", on_update), ("DELETE", on_delete)].iter() {
+            if rule.as_str() != "NO ACTION" {
+                warnings.push(format!("ON {} of the foreign key to {} is not imported", action, to_table));
+            }
+        }
+        // the primary key is referenced if the column is omitted
+        let foreign = ForeignKey { table: to_table.clone(), column: to.clone().unwrap_or_else(|| "id".to_string()) };
+        match table.columns.iter_mut().find(|column| column.name == *from) {
+            Some(column) => column.references = Some(foreign),
+            None => warnings.push(format!("column {}.{} of the foreign key is not found", table.name, from)),
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,57 @@
+use log::warn;
+
+use crate::app::config::{LintConfig, LintLevel};
+use crate::app::converter::mig::{IndexKind, Schema};
+
+// warnings which the mig-files or the migrations can have. the level of each lint is in [lint] of mig.toml.
+
+/// kind of the warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    // the table has no primary key
+    NoPrimaryKey,
+    // a dropped one and an added one have the same definition. --rename may be missing
+    RenameHint,
+    // down() of the migration cannot restore the data
+    Irreversible,
+    // sqlite cannot alter the table, so the migration fails
+    SqliteRecreate,
+}
+
+impl Lint {
+    /// key in [lint] of mig.toml
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Lint::NoPrimaryKey => "no-primary-key",
+            Lint::RenameHint => "rename-hint",
+            Lint::Irreversible => "irreversible",
+            Lint::SqliteRecreate => "sqlite-recreate",
+        };
+    }
+}
+
+/// warnings of the tables which the semantic analysis accepts
+pub fn lint_schema(schema: &Schema) -> Vec<(Lint, String)> {
+    let mut warnings = vec!();
+    for table in schema.tables.iter() {
+        let has_primary = table.columns.iter().any(|column| column.column_type.is_auto_increment())
+            || table.indexes.iter().any(|index| index.kind == IndexKind::Primary);
+        if !has_primary {
+            warnings.push((Lint::NoPrimaryKey, format!("table {} has no primary key", table.name)));
+        }
+    }
+    return warnings;
+}
+
+/// the allowed lints are dropped and the others are warned. returns the messages of the denied lints.
+pub fn apply_lints(warnings: Vec<(Lint, String)>, config: &LintConfig) -> Vec<String> {
+    let mut denied = vec!();
+    for (lint, message) in warnings.into_iter() {
+        match config.level(lint) {
+            LintLevel::Allow => {}
+            LintLevel::Warn => warn!("{} [{}]", message, lint.name()),
+            LintLevel::Deny => denied.push(format!("{} [{}]", message, lint.name())),
+        }
+    }
+    return denied;
+}
@@ -0,0 +1,51 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+// all messages go to stderr. stdout is kept for the generated code.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() <= log::max_level();
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Info => eprintln!("{}", record.args()),
+            level => eprintln!("[{}] {}", level.to_string().to_lowercase(), record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// the summary of the command is shown without -q. stdout is kept for the generated code.
+pub fn print_summary(summary: &str) {
+    if log::max_level() > LevelFilter::Error {
+        eprintln!("{}", summary);
+    }
+}
+
+/// default is warning. -v is info, -vv is debug and -vvv is trace. -q shows only errors.
+pub fn to_level_filter(verbose: u64, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::Error;
+    }
+    return match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+}
+
+pub fn init_logger(level: LevelFilter) {
+    // the logger is set only once
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
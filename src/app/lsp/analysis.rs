@@ -0,0 +1,323 @@
+use crate::{analyze, parse_str};
+use crate::app::converter::ast::{Command, Node};
+use crate::app::converter::parser::lexical_analyzer;
+use crate::app::converter::semantic::{COLUMN_MODIFIERS, COLUMN_TYPES, INDEX_OPTIONS, OptionDoc, TABLE_OPTIONS};
+use crate::app::converter::token::{Sequence, Token};
+use crate::app::diagnostic::Diagnostic;
+use crate::app::formatter::format_file;
+
+// features of the language server. positions are (row, col) from 1 like the errors of the converter.
+
+const COMMANDS: &[OptionDoc] = &[
+    OptionDoc { name: "create", params: "<table> { ... }", doc: "create the table" },
+];
+
+// table options which have the block of INDEX_OPTIONS
+const INDEX_KINDS: [&str; 3] = ["index", "unique-index", "primary-key"];
+
+/// candidate of the completion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub detail: Option<String>,
+    pub is_option: bool,
+}
+
+/// errors of the lexer, the syntax and the semantic analysis
+pub fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    let result = parse_str(text).and_then(|file| analyze(&file));
+    return match result {
+        Ok(_) => vec!(),
+        Err(e) => vec!(Diagnostic::from_converter_error(None, &e)),
+    };
+}
+
+/// option names after `:` and names of the columns or the tables in the parameters
+pub fn complete(text: &str, position: (u32, u32)) -> Vec<Completion> {
+    let (word_start, is_option) = match word_at(text, position) {
+        Some((start, _, is_option)) => (start, is_option),
+        None => return vec!(),
+    };
+    // `:` of the option is not a token
+    let context_end = if is_option { word_start - 1 } else { word_start };
+    let before = match lexical_analyzer(&text[..context_end]) {
+        Ok(seq) => seq,
+        // the text is broken before the cursor
+        Err(_) => return vec!(),
+    };
+    let context = Context::new(&before);
+    if is_option {
+        return context.get_docs().iter()
+            .map(|doc| Completion {
+                label: doc.name.to_string(),
+                detail: Some(to_signature(doc)),
+                is_option: true,
+            })
+            .collect();
+    }
+
+    // names are taken from the whole text if it can be read
+    let all = lexical_analyzer(text).unwrap_or(before);
+    let names = match (context.scope, context.last_option.as_deref()) {
+        (Scope::Index, Some("target")) => column_names(&all, context.command),
+        (Scope::Column, Some("references")) => table_names(&all),
+        _ => vec!(),
+    };
+    return names.into_iter()
+        .map(|name| Completion { label: name, detail: None, is_option: false })
+        .collect();
+}
+
+/// markdown document of the option at the position
+pub fn hover(text: &str, position: (u32, u32)) -> Option<String> {
+    let (start, end, is_option) = word_at(text, position)?;
+    if !is_option {
+        return None;
+    }
+    let name = &text[start..end];
+    let context = lexical_analyzer(&text[..start - 1])
+        .map(|seq| Context::new(&seq))
+        .ok();
+    // options of other scope are also shown when the text is broken
+    let doc = context.and_then(|c| c.get_docs().into_iter().find(|doc| doc.name == name))
+        .or_else(|| all_docs().find(|doc| doc.name == name))?;
+    return Some(format!("```mig\n{}\n```\n{}", to_signature(doc), doc.doc));
+}
+
+/// (row, col) and length of the column or the table which is referenced at the position.
+/// `:target` of the index and `:references` of the column have the references.
+pub fn definition(text: &str, position: (u32, u32)) -> Option<((u32, u32), usize)> {
+    let file = parse_str(text).ok()?;
+    for command in file.commands.iter() {
+        for node in command.body.nodes.iter() {
+            match node {
+                Node::Option(option) if INDEX_KINDS.contains(&option.name.as_str()) => {
+                    let targets = option.body.iter()
+                        .flat_map(|body| body.nodes.iter())
+                        .filter_map(|node| match node {
+                            Node::Option(o) if o.name == "target" => Some(o),
+                            _ => None,
+                        });
+                    for target in targets {
+                        for arg in target.args.iter() {
+                            if let Token::Name(name) = &arg.value {
+                                if contains(arg.position, name.len(), position) {
+                                    return find_column(command, name);
+                                }
+                            }
+                        }
+                    }
+                }
+                Node::Column(column) => {
+                    let references = column.body.nodes.iter()
+                        .filter_map(|node| match node {
+                            Node::Option(o) if o.name == "references" => Some(o),
+                            _ => None,
+                        });
+                    for reference in references {
+                        let table = match reference.args.first().map(|arg| &arg.value) {
+                            Some(Token::Name(table)) => table,
+                            _ => continue,
+                        };
+                        let referenced = file.commands.iter().find(|c| c.table_name == *table)?;
+                        for (i, arg) in reference.args.iter().enumerate() {
+                            if let Token::Name(name) = &arg.value {
+                                if !contains(arg.position, name.len(), position) {
+                                    continue;
+                                }
+                                if i == 0 {
+                                    let head_len = referenced.name.len() + 1;
+                                    return Some((referenced.position, head_len));
+                                }
+                                return find_column(referenced, name);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    return None;
+}
+
+/// whole text in the canonical style. None if it cannot be parsed.
+pub fn format(text: &str) -> Option<String> {
+    return parse_str(text).ok().map(|file| format_file(&file));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    Top,
+    Table,
+    Column,
+    Index,
+    // block of the other option
+    Other,
+}
+
+// where the end of the tokens is
+struct Context {
+    scope: Scope,
+    // the last option in the current block
+    last_option: Option<String>,
+    // index of the token of the current command
+    command: usize,
+}
+
+impl Context {
+    fn new(seq: &Sequence) -> Self {
+        let tokens = seq.get_tokens();
+        let mut stack: Vec<Scope> = vec!();
+        let mut last_option: Option<String> = None;
+        let mut command = 0;
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                Token::NameColon(name) => {
+                    if stack.is_empty() {
+                        command = i;
+                    }
+                    last_option = Some(name.clone());
+                }
+                Token::LMidParen => {
+                    let scope = match stack.last() {
+                        None => Scope::Table,
+                        Some(Scope::Table) if i > 0 && tokens[i - 1].is_name() && last_option.is_none() =>
+                            Scope::Column,
+                        Some(Scope::Table) if last_option.as_deref().is_some_and(|o| INDEX_KINDS.contains(&o)) =>
+                            Scope::Index,
+                        Some(_) => Scope::Other,
+                    };
+                    stack.push(scope);
+                    last_option = None;
+                }
+                Token::RMidParen => {
+                    let _ = stack.pop();
+                    last_option = None;
+                }
+                // a column starts after the options in the table block
+                Token::Name(_) if stack.last() == Some(&Scope::Table)
+                    && tokens.get(i + 1).is_none_or(|t| t.is_l_mid_paren()) => {
+                    last_option = None;
+                }
+                _ => {}
+            }
+        }
+        return Context { scope: *stack.last().unwrap_or(&Scope::Top), last_option, command };
+    }
+
+    fn get_docs(&self) -> Vec<&'static OptionDoc> {
+        let docs: Vec<&[OptionDoc]> = match self.scope {
+            Scope::Top => vec!(COMMANDS),
+            Scope::Table => vec!(TABLE_OPTIONS),
+            Scope::Column => vec!(COLUMN_TYPES, COLUMN_MODIFIERS),
+            Scope::Index => vec!(INDEX_OPTIONS),
+            Scope::Other => vec!(),
+        };
+        return docs.into_iter().flat_map(|docs| docs.iter()).collect();
+    }
+}
+
+fn all_docs() -> impl Iterator<Item = &'static OptionDoc> {
+    return [COMMANDS, TABLE_OPTIONS, COLUMN_TYPES, COLUMN_MODIFIERS, INDEX_OPTIONS].iter()
+        .flat_map(|docs| docs.iter());
+}
+
+// e.g. :string [<length>]
+fn to_signature(doc: &OptionDoc) -> String {
+    if doc.params.is_empty() {
+        return format!(":{}", doc.name);
+    }
+    return format!(":{} {}", doc.name, doc.params);
+}
+
+// byte range of the name or the option name at the position, and whether it is an option.
+// the position may be just after the word.
+fn word_at(text: &str, position: (u32, u32)) -> Option<(usize, usize, bool)> {
+    let offset = to_offset(text, position)?;
+    let is_word_char = |c: u8| c.is_ascii_alphanumeric() || c == b'-' || c == b'_';
+    let bytes = text.as_bytes();
+    let mut start = offset;
+    while start > 0 && is_word_char(bytes[start - 1]) {
+        start -= 1;
+    }
+    let mut end = offset;
+    while end < bytes.len() && is_word_char(bytes[end]) {
+        end += 1;
+    }
+    let is_option = start > 0 && bytes[start - 1] == b':';
+    return Some((start, end, is_option));
+}
+
+// byte offset of (row, col). col is in utf-16 code units of lsp and can be the end of the line.
+fn to_offset(text: &str, (row, col): (u32, u32)) -> Option<usize> {
+    let mut offset = 0;
+    for (i, line) in text.split('\n').enumerate() {
+        if i + 1 == row as usize {
+            return to_byte_index(line, (col as usize).checked_sub(1)?).map(|index| offset + index);
+        }
+        offset += line.len() + 1;
+    }
+    return None;
+}
+
+// None if the units are over the line or in the middle of the character
+fn to_byte_index(line: &str, units: usize) -> Option<usize> {
+    let mut counted = 0;
+    for (index, c) in line.char_indices() {
+        if counted >= units {
+            return if counted == units { Some(index) } else { None };
+        }
+        counted += c.len_utf16();
+    }
+    return if counted == units { Some(line.len()) } else { None };
+}
+
+fn contains(start: (u32, u32), len: usize, position: (u32, u32)) -> bool {
+    return start.0 == position.0 && start.1 <= position.1 && (position.1 as usize) <= start.1 as usize + len;
+}
+
+fn find_column(command: &Command, name: &str) -> Option<((u32, u32), usize)> {
+    return command.body.nodes.iter().find_map(|node| match node {
+        Node::Column(column) if column.name == name => Some((column.position, name.len())),
+        _ => None,
+    });
+}
+
+// Name before `{` in the table block of the command
+fn column_names(seq: &Sequence, command: usize) -> Vec<String> {
+    let tokens = seq.get_tokens();
+    let mut names = vec!();
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(command) {
+        match token {
+            Token::LMidParen => depth += 1,
+            Token::RMidParen => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Token::Name(name) if depth == 1 && tokens.get(i + 1).is_some_and(|t| t.is_l_mid_paren()) =>
+                names.push(name.clone()),
+            _ => {}
+        }
+    }
+    return names;
+}
+
+// Name after the command
+fn table_names(seq: &Sequence) -> Vec<String> {
+    let tokens = seq.get_tokens();
+    let mut names = vec!();
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LMidParen => depth += 1,
+            Token::RMidParen => depth -= 1,
+            Token::Name(name) if depth == 0 && i > 0 && tokens[i - 1].is_name_colon() => names.push(name.clone()),
+            _ => {}
+        }
+    }
+    return names;
+}
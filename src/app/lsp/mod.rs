@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use log::debug;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentFormattingParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, NumberOrString, OneOf,
+    Position, PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url,
+};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, Formatting, GotoDefinition, HoverRequest, Request as _};
+
+use crate::app::AppError;
+
+pub mod analysis;
+
+// `mig lsp` serves on stdio. the documents are full text synchronized.
+
+/// run until the client sends exit
+pub fn run_server() -> Result<(), AppError> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!(":".to_string(), " ".to_string())),
+            ..CompletionOptions::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    };
+    let capabilities = serde_json::to_value(capabilities).map_err(|e| to_app_error(e.to_string()))?;
+    connection.initialize(capabilities).map_err(|e| to_app_error(e.to_string()))?;
+    main_loop(&connection)?;
+    drop(connection);
+    io_threads.join().map_err(|e| to_app_error(e.to_string()))?;
+    return Ok(());
+}
+
+fn to_app_error(message: String) -> AppError {
+    return AppError::FailedLanguageServer(message);
+}
+
+fn main_loop(connection: &Connection) -> Result<(), AppError> {
+    // text of the opened documents
+    let mut documents: HashMap<Url, String> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request).map_err(|e| to_app_error(e.to_string()))? {
+                    return Ok(());
+                }
+                let response = handle_request(&documents, request);
+                send(connection, Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = handle_notification(&mut documents, notification) {
+                    let text = documents.get(&uri).map(|text| text.as_str()).unwrap_or("");
+                    publish_diagnostics(connection, uri.clone(), text)?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    return Ok(());
+}
+
+fn send(connection: &Connection, message: Message) -> Result<(), AppError> {
+    return connection.sender.send(message).map_err(|e| to_app_error(e.to_string()));
+}
+
+// Some has the document which is changed
+fn handle_notification(documents: &mut HashMap<Url, String>, notification: Notification) -> Option<Url> {
+    debug!("notification: {}", notification.method);
+    return match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = notification.extract(DidOpenTextDocument::METHOD).ok()?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), params.text_document.text);
+            Some(uri)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = notification.extract(DidChangeTextDocument::METHOD).ok()?;
+            let uri = params.text_document.uri;
+            // the last change has the whole text
+            let text = params.content_changes.into_iter().last()?.text;
+            documents.insert(uri.clone(), text);
+            Some(uri)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = notification.extract(DidCloseTextDocument::METHOD).ok()?;
+            let uri = params.text_document.uri;
+            documents.remove(&uri);
+            // diagnostics of the closed document are cleared
+            Some(uri)
+        }
+        _ => None,
+    };
+}
+
+fn handle_request(documents: &HashMap<Url, String>, request: Request) -> Response {
+    debug!("request: {}", request.method);
+    let id = request.id.clone();
+    let method = request.method.clone();
+    let result = match method.as_str() {
+        Completion::METHOD => request.extract::<CompletionParams>(Completion::METHOD).ok()
+            .map(|(_, params)| {
+                let position = params.text_document_position;
+                to_value(complete(documents, &position.text_document.uri, position.position))
+            }),
+        HoverRequest::METHOD => request.extract::<HoverParams>(HoverRequest::METHOD).ok()
+            .map(|(_, params)| {
+                let position = params.text_document_position_params;
+                to_value(hover(documents, &position.text_document.uri, position.position))
+            }),
+        GotoDefinition::METHOD => request.extract::<GotoDefinitionParams>(GotoDefinition::METHOD).ok()
+            .map(|(_, params)| {
+                let position = params.text_document_position_params;
+                to_value(definition(documents, &position.text_document.uri, position.position))
+            }),
+        Formatting::METHOD => request.extract::<DocumentFormattingParams>(Formatting::METHOD).ok()
+            .map(|(_, params)| to_value(format(documents, &params.text_document.uri))),
+        _ => return Response::new_err(id, ErrorCode::MethodNotFound as i32, method),
+    };
+    return match result {
+        Some(value) => Response { id, result: Some(value), error: None },
+        None => Response::new_err(id, ErrorCode::InvalidParams as i32, "invalid params".to_string()),
+    };
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> serde_json::Value {
+    return serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+}
+
+fn publish_diagnostics(connection: &Connection, uri: Url, text: &str) -> Result<(), AppError> {
+    let diagnostics = analysis::diagnostics(text).into_iter()
+        .map(|d| {
+            let position = match (d.line, d.column) {
+                (Some(line), Some(column)) => to_lsp_position((line, column)),
+                _ => Position::new(0, 0),
+            };
+            Diagnostic {
+                range: Range::new(position, position),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(d.code)),
+                source: Some("mig".to_string()),
+                message: d.message,
+                ..Diagnostic::default()
+            }
+        })
+        .collect();
+    let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+    return send(connection, Message::Notification(Notification::new(PublishDiagnostics::METHOD.to_string(), params)));
+}
+
+fn complete(documents: &HashMap<Url, String>, uri: &Url, position: Position) -> Vec<CompletionItem> {
+    let text = match documents.get(uri) {
+        Some(text) => text,
+        None => return vec!(),
+    };
+    let position = match to_mig_position(position) {
+        Some(position) => position,
+        None => return vec!(),
+    };
+    return analysis::complete(text, position).into_iter()
+        .map(|c| CompletionItem {
+            label: c.label,
+            detail: c.detail,
+            kind: Some(if c.is_option { CompletionItemKind::KEYWORD } else { CompletionItemKind::FIELD }),
+            ..CompletionItem::default()
+        })
+        .collect();
+}
+
+fn hover(documents: &HashMap<Url, String>, uri: &Url, position: Position) -> Option<Hover> {
+    let doc = analysis::hover(documents.get(uri)?, to_mig_position(position)?)?;
+    return Some(Hover {
+        contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: doc }),
+        range: None,
+    });
+}
+
+fn definition(documents: &HashMap<Url, String>, uri: &Url, position: Position) -> Option<GotoDefinitionResponse> {
+    let (start, len) = analysis::definition(documents.get(uri)?, to_mig_position(position)?)?;
+    let start = to_lsp_position(start);
+    let end = Position::new(start.line, start.character + len as u32);
+    return Some(GotoDefinitionResponse::Scalar(Location::new(uri.clone(), Range::new(start, end))));
+}
+
+// the whole document is replaced
+fn format(documents: &HashMap<Url, String>, uri: &Url) -> Option<Vec<TextEdit>> {
+    let text = documents.get(uri)?;
+    let formatted = analysis::format(text)?;
+    let lines = text.split('\n').collect::<Vec<&str>>();
+    // the character is in utf-16 code units
+    let last = lines.last().map(|l| l.encode_utf16().count()).unwrap_or(0);
+    let end = Position::new(u32::try_from(lines.len() - 1).ok()?, u32::try_from(last).ok()?);
+    return Some(vec!(TextEdit::new(Range::new(Position::new(0, 0), end), formatted)));
+}
+
+// lsp is from 0 and mig is from 1. None if it is over the range.
+fn to_mig_position(position: Position) -> Option<(u32, u32)> {
+    return Some((position.line.checked_add(1)?, position.character.checked_add(1)?));
+}
+
+fn to_lsp_position((row, col): (u32, u32)) -> Position {
+    return Position::new(row.saturating_sub(1), col.saturating_sub(1));
+}
@@ -1,38 +1,193 @@
 extern crate clap;
 
 use std::fmt::{Display, Formatter};
-use std::fmt;
-use std::path::PathBuf;
+use std::{fmt, fs};
+use std::path::{Path, PathBuf};
 
 // macros
 use clap::{crate_authors, crate_description, crate_name, crate_version};
+use log::{debug, info, warn};
 // no macros
-use clap::{App, Arg, ArgMatches, Error};
+use chrono::NaiveDateTime;
+use clap::{App, AppSettings, Arg, ArgMatches, Error, SubCommand};
+use log::LevelFilter;
 
-use converter::convert_to_migration_file;
-use framework::to_framework_type;
+use batch::{collect_mig_files, convert_mig_files, ConvertOptions, is_batch_input, load_mig_files, to_new_migration_paths};
+use config::{Config, ConfigError, find_config_file, load_config};
+use converter::{check_mig_file, convert_mig_file, parse_mig_file};
+use diff::{diff_schemas, Renames, SchemaDiff};
+use formatter::format_file;
+use framework::{Framework, FRAMEWORKS, to_framework_types};
 use helper::file_helper::{
+    find_same_migrations,
     get_extension_for_framework,
-    get_file_name_for_framework,
     is_extension,
+    is_file_for_framework,
     with_timestamp,
 };
-use helper::io_helper::confirm;
+use helper::io_helper::{confirm, git_show};
+use diagnostic::print_error;
+use lint::{Lint, apply_lints, lint_schema};
+use logger::{print_summary, to_level_filter};
+use import::{Imported, retain_valid_names, to_mig_file};
+use output::{OutputMode, write_generated, write_output};
+use state::{load_state, save_state};
+use verify::{verify_sqlite, VerifyError};
+use watch::watch_mig_files;
 
-use crate::app::AppError::UnreachedError;
 use crate::app::converter::ConverterError;
+use crate::app::converter::mig::Schema;
+use crate::app::converter::parser::is_mig_name;
+use crate::app::generator::{generate_diff, sql};
+use crate::app::generator::sql::Dialect;
+use crate::{analyze, parse_str};
 
+mod batch;
+pub mod config;
 pub mod converter;
+pub mod diagnostic;
+pub mod diff;
+pub mod exit_code;
+pub mod formatter;
 pub mod framework;
+pub mod generator;
 mod helper;
+pub mod import;
+pub mod lint;
+pub mod logger;
+pub mod lsp;
+mod output;
+mod state;
+pub mod verify;
+mod watch;
 
 pub enum AppError {
     UnreachedError,
-    NotSupportedFrameWork,
+    NotSupportedFrameWork(String),
     NotMigFile,
     NotIsFile,
     InputFileIsNotExist,
+    OutputFileIsExist,
+    InvalidTableName(String),
+    FailedWriteFile(std::io::Error),
+    FailedReadDirectory(String, std::io::Error),
+    InvalidGlobPattern(String, &'static str),
+    NoMigFile(String),
+    OutputFileForManyFiles,
+    DuplicatedTable(String),
+    CyclicReference(String),
+    MigrationIsExist(PathBuf),
+    NewMigrationIsExist(PathBuf),
+    Config(ConfigError),
+    NoInput,
+    NoTarget,
+    OutputFileForManyTargets,
+    FailedWatch(String, notify::Error),
+    NotFormatted,
+    FailedLanguageServer(String),
+    FailedGitShow(String, String),
+    NoAlteration(String),
+    InvalidRename(String),
+    InvalidState(PathBuf, String),
+    NoImportFile(String),
+    FailedReadDatabase(String, rusqlite::Error),
+    Verify(VerifyError),
+    JsonMessageToStdout,
+    DeniedLints(Vec<String>),
     Converter(ConverterError),
+    // errors of some input files with the path
+    InvalidFiles(Vec<(String, AppError)>),
+}
+
+impl AppError {
+    /// stable code for the diagnostics
+    pub fn code(&self) -> &'static str {
+        return match self {
+            AppError::UnreachedError => "E0900",
+            AppError::NotSupportedFrameWork(_) => "E0901",
+            AppError::NotMigFile => "E0902",
+            AppError::NotIsFile => "E0903",
+            AppError::InputFileIsNotExist => "E0904",
+            AppError::OutputFileIsExist => "E0905",
+            AppError::InvalidTableName(_) => "E0906",
+            AppError::FailedWriteFile(_) => "E0907",
+            AppError::FailedReadDirectory(_, _) => "E0908",
+            AppError::InvalidGlobPattern(_, _) => "E0909",
+            AppError::NoMigFile(_) => "E0910",
+            AppError::OutputFileForManyFiles => "E0911",
+            AppError::DuplicatedTable(_) => "E0912",
+            AppError::CyclicReference(_) => "E0913",
+            AppError::MigrationIsExist(_) => "E0914",
+            AppError::Config(_) => "E0915",
+            AppError::NoInput => "E0916",
+            AppError::NoTarget => "E0917",
+            AppError::OutputFileForManyTargets => "E0918",
+            AppError::FailedWatch(_, _) => "E0919",
+            AppError::NotFormatted => "E0920",
+            AppError::FailedLanguageServer(_) => "E0921",
+            AppError::FailedGitShow(_, _) => "E0922",
+            AppError::NoAlteration(_) => "E0923",
+            AppError::InvalidRename(_) => "E0924",
+            AppError::InvalidState(_, _) => "E0925",
+            AppError::NoImportFile(_) => "E0926",
+            AppError::FailedReadDatabase(_, _) => "E0927",
+            AppError::Verify(_) => "E0928",
+            AppError::JsonMessageToStdout => "E0929",
+            AppError::DeniedLints(_) => "E0930",
+            AppError::NewMigrationIsExist(_) => "E0931",
+            AppError::Converter(c_e) => c_e.code(),
+            // each file has the code
+            AppError::InvalidFiles(_) => "E0999",
+        };
+    }
+
+    /// exit code of the process. the largest code is used for errors of many files.
+    pub fn exit_code(&self) -> i32 {
+        return match self {
+            AppError::UnreachedError => exit_code::INTERNAL,
+            AppError::NotSupportedFrameWork(_)
+            | AppError::OutputFileForManyTargets
+            | AppError::InvalidTableName(_)
+            | AppError::InvalidGlobPattern(_, _)
+            | AppError::OutputFileForManyFiles
+            | AppError::NotMigFile
+            | AppError::Config(_)
+            | AppError::NoInput
+            | AppError::NoTarget
+            | AppError::NoAlteration(_)
+            | AppError::InvalidRename(_)
+            | AppError::JsonMessageToStdout => exit_code::ARGUMENT,
+            AppError::NotIsFile
+            | AppError::InputFileIsNotExist
+            | AppError::OutputFileIsExist
+            | AppError::FailedWriteFile(_)
+            | AppError::FailedReadDirectory(_, _)
+            | AppError::NoMigFile(_)
+            | AppError::MigrationIsExist(_)
+            | AppError::NewMigrationIsExist(_)
+            | AppError::FailedWatch(_, _)
+            | AppError::FailedLanguageServer(_)
+            | AppError::FailedGitShow(_, _)
+            | AppError::InvalidState(_, _)
+            | AppError::NoImportFile(_)
+            | AppError::FailedReadDatabase(_, _) => exit_code::IO,
+            AppError::DuplicatedTable(_)
+            | AppError::CyclicReference(_)
+            | AppError::NotFormatted
+            | AppError::DeniedLints(_) => exit_code::VALIDATION,
+            AppError::Verify(_) => exit_code::GENERATION,
+            AppError::Converter(c_e) => match c_e {
+                ConverterError::FailedReadInputFile(_) => exit_code::IO,
+                ConverterError::Parse(_) | ConverterError::Syntax(_) => exit_code::PARSE,
+                ConverterError::Semantic(_) => exit_code::VALIDATION,
+                ConverterError::TooManyTables(_) => exit_code::GENERATION,
+            },
+            AppError::InvalidFiles(errors) => errors.iter()
+                .map(|(_, e)| e.exit_code())
+                .max()
+                .unwrap_or(exit_code::INTERNAL),
+        };
+    }
 }
 
 impl From<ConverterError> for AppError {
@@ -45,12 +200,81 @@ impl Display for AppError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             AppError::UnreachedError => write!(f, "unreached error! Why you reach?"),
-            AppError::NotSupportedFrameWork => write!(f, "not support the framework"),
+            AppError::NotSupportedFrameWork(name) =>
+                write!(f, "not support the framework {}. see `mig targets`", name),
             AppError::NotMigFile => write!(f, "input file is not mig file"),
             AppError::NotIsFile => write!(f, "type of file is not file"),
             AppError::InputFileIsNotExist => write!(f, "input file is not exists"),
-            AppError::Converter(e) => write!(f, "{}", e.to_string()),
+            AppError::OutputFileIsExist => write!(f, "output file already exists"),
+            AppError::InvalidTableName(name) =>
+                write!(f, "{} is not a table name. it starts with an ascii alphabet and can have only ascii alphanumerics and _", name),
+            AppError::FailedWriteFile(io_e) => write!(f, "failed write file,: {}", io_e),
+            AppError::FailedReadDirectory(dir, io_e) => write!(f, "failed read directory {},: {}", dir, io_e),
+            AppError::InvalidGlobPattern(pattern, msg) => write!(f, "{} is invalid pattern,: {}", pattern, msg),
+            AppError::NoMigFile(input) => write!(f, "no mig file is found in {}", input),
+            AppError::OutputFileForManyFiles =>
+                write!(f, "output file cannot be specified for many files. use --out-dir"),
+            AppError::DuplicatedTable(name) => write!(f, "table {} is already defined in other file", name),
+            AppError::CyclicReference(name) =>
+                write!(f, "table {} cannot be ordered because of cyclic references", name),
+            AppError::MigrationIsExist(path) =>
+                write!(f, "migration {} already exists. use --force to overwrite it", path.display()),
+            AppError::NewMigrationIsExist(path) =>
+                write!(f, "migration {} already exists. nothing is written", path.display()),
+            AppError::Config(e) => write!(f, "{}", e),
+            AppError::NoInput => write!(f, "input file is needed. specify -I or schema_dir in mig.toml"),
+            AppError::NoTarget => write!(f, "target FrameWork is needed. specify --target or target in mig.toml"),
+            AppError::OutputFileForManyTargets =>
+                write!(f, "output file cannot be specified for many targets. use --out-dir"),
+            AppError::FailedWatch(dir, n_e) => write!(f, "failed watch directory {},: {}", dir, n_e),
+            AppError::NotFormatted => write!(f, "file is not formatted. run mig fmt"),
+            AppError::FailedLanguageServer(msg) => write!(f, "language server is stopped,: {}", msg),
+            AppError::FailedGitShow(path, msg) => write!(f, "failed read {} from git,: {}", path, msg),
+            AppError::NoAlteration(name) => write!(f, "{} cannot alter the schema. it exports the schema only", name),
+            AppError::InvalidRename(hint) =>
+                write!(f, "{} is invalid rename. it is old=new of the table or table.old=new of the column", hint),
+            AppError::InvalidState(path, msg) => write!(f, "snapshot {} cannot be read,: {}", path.display(), msg),
+            AppError::NoImportFile(input) => write!(f, "no migration to import is found in {}", input),
+            AppError::FailedReadDatabase(path, s_e) => write!(f, "failed read database {},: {}", path, s_e),
+            AppError::Verify(e) => write!(f, "{}", e),
+            AppError::JsonMessageToStdout =>
+                write!(f, "--message-format json cannot be used with -O - because both of them are written to stdout"),
+            AppError::DeniedLints(messages) => {
+                write!(f, "{} lint(s) are denied in mig.toml", messages.len())?;
+                for message in messages.iter() {
+                    write!(f, "\n {}", message)?;
+                }
+                Ok(())
+            }
+            AppError::Converter(e) => write!(f, "{}", e),
+            AppError::InvalidFiles(errors) => {
+                write!(f, "{} file(s) have errors", errors.len())?;
+                for (path, e) in errors.iter() {
+                    write!(f, "\n {}: {}", path, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// how to treat the existing migration of the same table and command
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overwrite {
+    Ask,
+    Force,
+    Refuse,
+}
+
+impl Overwrite {
+    fn from_matches(matches: &ArgMatches<'_>) -> Self {
+        if matches.is_present("FORCE") {
+            return Overwrite::Force;
+        }
+        if matches.is_present("NO_INTERACTIVE") {
+            return Overwrite::Refuse;
         }
+        return Overwrite::Ask;
     }
 }
 
@@ -62,27 +286,323 @@ pub fn mig_app<'a, 'b>() -> App<'a, 'b> {
             "If you have questions, contact to ",
             crate_authors!()
         ))
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("VERBOSE")
+                .short("v")
+                .multiple(true)
+                .global(true)
+                .help("show the progress. -vv shows the analyzed schema and -vvv shows the tokens too."),
+        )
         .arg(
-            Arg::with_name("INPUT")
-                .short("I")
-                .value_name("input-file")
-                .required(true)
-                .help("specify a path to the input file."),
+            Arg::with_name("MESSAGE_FORMAT")
+                .long("message-format")
+                .value_name("format")
+                .possible_values(&["human", "json"])
+                .default_value("human")
+                .global(true)
+                .help("specify the format of the errors. json is one object per line in stdout."),
         )
         .arg(
-            Arg::with_name("TARGET_FW")
-                .short("T")
-                .long("target")
-                .value_name("target-FW")
-                .required(true)
-                .help("specify the name of the target FrameWork."),
+            Arg::with_name("CONFIG")
+                .long("config")
+                .value_name("config-file")
+                .global(true)
+                .help("specify the config file. default is mig.toml in the current directory or its ancestors."),
         )
         .arg(
-            Arg::with_name("OUTPUT")
-                .short("O")
-                .value_name("output-file")
-                .required(true)
-                .help("specify the name of the output file."),
+            Arg::with_name("QUIET")
+                .short("q")
+                .long("quiet")
+                .global(true)
+                .conflicts_with("VERBOSE")
+                .help("show only errors."),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("convert the mig-file to the migration file.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("I")
+                        .value_name("input-file")
+                        .help("specify a path to the input file, or a directory or glob pattern of the input files. default is schema_dir in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("TARGET_FW")
+                        .short("T")
+                        .long("target")
+                        .value_name("target-FW")
+                        .help("specify the names of the target FrameWorks separated by comma. default is target in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("O")
+                        .value_name("output-file")
+                        .help("specify the name of the output file. default is made from the table. e.g. create_members_table. - is stdout"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the output files. default is the directory of the target FrameWork's project. e.g. database/migrations for Laravel"),
+                )
+                .arg(
+                    Arg::with_name("DRY_RUN")
+                        .long("dry-run")
+                        .help("show the paths and contents of the output files without writing them."),
+                )
+                .arg(
+                    Arg::with_name("FORCE")
+                        .long("force")
+                        .help("overwrite the existing migration of the same table without confirmation."),
+                )
+                .arg(
+                    Arg::with_name("NO_INTERACTIVE")
+                        .long("no-interactive")
+                        .help("never confirm. the existing migration of the same table is not overwritten."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("parse and validate the mig-files without writing anything.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("input-file")
+                        .multiple(true)
+                        .help("specify paths to the input files. default is all files in schema_dir of mig.toml"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .about("rewrite the mig-files in the canonical style.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("input-file")
+                        .required(true)
+                        .multiple(true)
+                        .help("specify paths to the input files."),
+                )
+                .arg(
+                    Arg::with_name("CHECK")
+                        .long("check")
+                        .help("fail without writing if the files are not formatted."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("new")
+                .about("make a new mig-file which creates the table.")
+                .arg(
+                    Arg::with_name("NAME")
+                        .value_name("table-name")
+                        .required(true)
+                        .help("specify the name of the table."),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("O")
+                        .value_name("output-file")
+                        .help("specify the path to the new mig-file. default is <table-name>.mig"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("make the migrations which alter the old schema to the new one.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("old new")
+                        .required(true)
+                        .min_values(1)
+                        .max_values(2)
+                        .help("specify the old and the new mig-file, directory or glob pattern. the old one is read from git if only the new mig-file is given."),
+                )
+                .arg(
+                    Arg::with_name("REVISION")
+                        .long("rev")
+                        .value_name("revision")
+                        .default_value("HEAD")
+                        .help("specify the git revision of the old mig-file."),
+                )
+                .arg(
+                    Arg::with_name("RENAME")
+                        .long("rename")
+                        .value_name("old=new")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("specify the renamed table by old=new, or the renamed column by table.old=new. the table is the new name."),
+                )
+                .arg(
+                    Arg::with_name("TARGET_FW")
+                        .short("T")
+                        .long("target")
+                        .value_name("target-FW")
+                        .help("specify the names of the target FrameWorks separated by comma. default is target in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the output files."),
+                )
+                .arg(
+                    Arg::with_name("DRY_RUN")
+                        .long("dry-run")
+                        .help("show the paths and contents of the output files without writing them."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("generate")
+                .about("make the migrations from the snapshot of the last generated schema to the mig-files, and update the snapshot.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("I")
+                        .value_name("input-dir")
+                        .help("specify the directory or glob pattern of the mig-files which describe the current schema. default is schema_dir in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("STATE")
+                        .long("state")
+                        .value_name("state-file")
+                        .help("specify the snapshot file. default is state_file in mig.toml or .mig-state.json"),
+                )
+                .arg(
+                    Arg::with_name("RENAME")
+                        .long("rename")
+                        .value_name("old=new")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("specify the renamed table by old=new, or the renamed column by table.old=new. the table is the new name."),
+                )
+                .arg(
+                    Arg::with_name("TARGET_FW")
+                        .short("T")
+                        .long("target")
+                        .value_name("target-FW")
+                        .help("specify the names of the target FrameWorks separated by comma. default is target in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the output files."),
+                )
+                .arg(
+                    Arg::with_name("DRY_RUN")
+                        .long("dry-run")
+                        .help("show the paths and contents of the output files without writing them and the snapshot."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("make the mig-files from the migrations of the other tool. the constructs which mig cannot express are warned.")
+                .arg(
+                    Arg::with_name("FROM")
+                        .long("from")
+                        .value_name("format")
+                        .required(true)
+                        .possible_values(&["laravel", "sql"])
+                        .help("specify the format of the migrations. sql is the schema dump of postgres, mysql or sqlite."),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("input")
+                        .required(true)
+                        .help("specify the migration file or the directory of the migrations. e.g. database/migrations, schema.sql"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the mig-files. default is schema_dir in mig.toml or the current directory"),
+                )
+                .arg(
+                    Arg::with_name("DRY_RUN")
+                        .long("dry-run")
+                        .help("show the paths and contents of the mig-files without writing them."),
+                )
+                .arg(
+                    Arg::with_name("FORCE")
+                        .long("force")
+                        .help("overwrite the existing mig-files of the same tables."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("introspect")
+                .about("make the mig-files from the tables of the database. the constructs which mig cannot express are warned.")
+                .arg(
+                    Arg::with_name("DATABASE")
+                        .value_name("database")
+                        .required(true)
+                        .possible_values(&["sqlite"])
+                        .help("specify the kind of the database."),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("path")
+                        .required(true)
+                        .help("specify the database file. e.g. database/database.sqlite"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the mig-files. default is schema_dir in mig.toml or the current directory"),
+                )
+                .arg(
+                    Arg::with_name("DRY_RUN")
+                        .long("dry-run")
+                        .help("show the paths and contents of the mig-files without writing them."),
+                )
+                .arg(
+                    Arg::with_name("FORCE")
+                        .long("force")
+                        .help("overwrite the existing mig-files of the same tables."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("apply the generated sql of the mig-files to a database and compare the tables with the mig-files.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("I")
+                        .value_name("input-dir")
+                        .help("specify the directory or glob pattern of the mig-files. default is schema_dir in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("SQLITE")
+                        .long("sqlite")
+                        .required(true)
+                        .help("verify with an in-memory SQLite database. no database server is needed."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("check or convert the mig-files in the directory whenever they are changed.")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .value_name("input-dir")
+                        .help("specify the directory of the mig-files. default is schema_dir in mig.toml"),
+                )
+                .arg(
+                    Arg::with_name("TARGET_FW")
+                        .short("T")
+                        .long("target")
+                        .value_name("target-FW")
+                        .help("specify the names of the target FrameWorks separated by comma. default is target in mig.toml. only check without target"),
+                )
+                .arg(
+                    Arg::with_name("OUT_DIR")
+                        .long("out-dir")
+                        .value_name("output-dir")
+                        .help("specify the directory of the output files."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lsp")
+                .about("run the language server of mig-file on stdio."),
+        )
+        .subcommand(
+            SubCommand::with_name("targets")
+                .about("list the supported target FrameWorks."),
         );
 }
 
@@ -90,71 +610,590 @@ pub fn get_matches_safe<'a>(mig_app: App<'a, '_>) -> Result<ArgMatches<'a>, Erro
     return mig_app.get_matches_safe();
 }
 
-pub fn action_controller(matches: ArgMatches) -> Result<&str, AppError> {
-    println!("checking condition...");
+/// log level of -v and -q. the flags are global, so they can be after the subcommand.
+pub fn log_level(matches: &ArgMatches<'_>) -> LevelFilter {
+    let (verbose, quiet) = match matches.subcommand() {
+        (_, Some(sub_matches)) => (sub_matches.occurrences_of("VERBOSE"), sub_matches.is_present("QUIET")),
+        _ => (matches.occurrences_of("VERBOSE"), matches.is_present("QUIET")),
+    };
+    // watch shows the progress by default because it keeps running
+    let verbose = if matches.subcommand_name() == Some("watch") { verbose + 1 } else { verbose };
+    return to_level_filter(verbose, quiet);
+}
+
+/// `--message-format json`
+pub fn is_json_message(matches: &ArgMatches<'_>) -> bool {
+    return global_value_of(matches, "MESSAGE_FORMAT") == Some("json");
+}
 
-    let input_file_opt = matches.value_of("INPUT");
-    if input_file_opt.is_none() {
-        return Err(AppError::UnreachedError);
+// global options are in the matches of the subcommand
+fn global_value_of<'a>(matches: &'a ArgMatches<'_>, name: &str) -> Option<&'a str> {
+    return match matches.subcommand() {
+        (_, Some(sub_matches)) => sub_matches.value_of(name),
+        _ => matches.value_of(name),
+    };
+}
+
+/// --config or mig.toml found from the current directory. default config if there is no file.
+fn load_project_config(matches: &ArgMatches<'_>) -> Result<Config, AppError> {
+    let path = match global_value_of(matches, "CONFIG") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let current_dir = std::env::current_dir().map_err(|_| AppError::UnreachedError)?;
+            match find_config_file(&current_dir) {
+                Some(path) => path,
+                None => return Ok(Config::default()),
+            }
+        }
+    };
+    debug!("config file: {}", path.display());
+    return load_config(&path).map_err(AppError::Config);
+}
+
+pub fn action_controller(matches: ArgMatches<'_>) -> Result<String, AppError> {
+    let config = load_project_config(&matches)?;
+    return match matches.subcommand() {
+        ("convert", Some(sub_matches)) => convert_action(sub_matches, &config),
+        ("check", Some(sub_matches)) => check_action(sub_matches, &config),
+        ("fmt", Some(sub_matches)) => fmt_action(sub_matches),
+        ("new", Some(sub_matches)) => new_action(sub_matches),
+        ("diff", Some(sub_matches)) => diff_action(sub_matches, &config),
+        ("generate", Some(sub_matches)) => generate_action(sub_matches, &config),
+        ("import", Some(sub_matches)) => import_action(sub_matches, &config),
+        ("introspect", Some(sub_matches)) => introspect_action(sub_matches, &config),
+        ("verify", Some(sub_matches)) => verify_action(sub_matches, &config),
+        ("watch", Some(sub_matches)) => watch_action(sub_matches, &config),
+        ("lsp", Some(_)) => lsp::run_server().map(|_| String::new()),
+        ("targets", Some(_)) => targets_action(),
+        _ => Err(AppError::UnreachedError),
+    };
+}
+
+fn convert_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    debug!("checking condition...");
+
+    // default is schema_dir in mig.toml
+    let input_file_opt_str = match matches.value_of("INPUT") {
+        Some(input) => input.to_string(),
+        None => config.schema_dir.as_ref()
+            .map(|dir| dir.display().to_string())
+            .ok_or(AppError::NoInput)?,
+    };
+    let input_file_opt_str = input_file_opt_str.as_str();
+
+    let targets_opt = matches.value_of("TARGET_FW").map(|t| t.to_string()).or_else(|| config.get_targets());
+    if targets_opt.is_none() {
+        return Err(AppError::NoTarget);
     }
-    // input_file_opt is not none!!!
-    let input_file_opt_str = input_file_opt.unwrap();
-    let input_file_path = PathBuf::from(input_file_opt_str);
+    // targets_opt is not none!!
+    let frameworks = to_framework_types(&targets_opt.unwrap())
+        .map_err(AppError::NotSupportedFrameWork)?;
 
-    let framework_opt = matches.value_of("TARGET_FW");
-    if framework_opt.is_none() {
-        return Err(AppError::UnreachedError);
+    // `-O -` is not a file
+    let mut output_file_opt = matches.value_of("OUTPUT");
+    let mode = if output_file_opt == Some("-") {
+        output_file_opt = None;
+        OutputMode::Stdout
+    } else if matches.is_present("DRY_RUN") {
+        OutputMode::DryRun
+    } else {
+        OutputMode::Write
+    };
+    if frameworks.len() > 1 && output_file_opt.is_some() {
+        return Err(AppError::OutputFileForManyTargets);
     }
-    // framework_opt is not none!!
-    let framework_type = to_framework_type(framework_opt.unwrap());
-    if framework_type.is_none() {
-        return Err(AppError::NotSupportedFrameWork);
+    // the json diagnostics are also in stdout
+    if mode == OutputMode::Stdout && matches.value_of("MESSAGE_FORMAT") == Some("json") {
+        return Err(AppError::JsonMessageToStdout);
     }
 
-    let output_file_opt = matches.value_of("OUTPUT");
+    let now = config.timestamp_timezone.now();
+    let targets: Vec<ConvertOptions> = frameworks.iter()
+        .map(|framework| to_convert_options(matches, config, framework, frameworks.len() > 1, mode, now))
+        .collect();
+
+    // file names are made from the tables
+    if is_batch_input(input_file_opt_str) {
+        if output_file_opt.is_some() {
+            return Err(AppError::OutputFileForManyFiles);
+        }
+        let inputs = collect_mig_files(input_file_opt_str)?;
+        debug!("finish checking condition");
+        return convert_mig_files(input_file_opt_str, &inputs, &targets).map(report);
+    }
+    let input_file_path = PathBuf::from(input_file_opt_str);
+    // the errors have the input for the diagnostics
+    let in_input = |e: AppError| AppError::InvalidFiles(vec!((input_file_opt_str.to_string(), e)));
+    validate_input_file(&input_file_path).map_err(in_input)?;
     if output_file_opt.is_none() {
-        return Err(AppError::UnreachedError);
+        debug!("finish checking condition");
+        return convert_mig_files(input_file_opt_str, &[input_file_path], &targets).map(report);
     }
-    let output_file_path_string =
-        with_timestamp(&output_file_opt.unwrap(), &framework_type.unwrap());
-    let output_file_path = PathBuf::from(output_file_path_string.as_str());
 
-    // check extension
-    if !is_extension(&input_file_path, "mig") {
-        return Err(AppError::NotMigFile);
-    }
+    // only one target has the output file
+    let options = &targets[0];
+    let target_framework = options.framework;
+
+    let output_file_path = with_timestamp(
+        &options.out_dir.join(output_file_opt.unwrap()), &target_framework, &options.now);
 
     // error is never realize!!
-    if !is_extension(
-        &output_file_path,
-        get_extension_for_framework(&framework_type.unwrap()).as_str(),
-    ) {
+    if !is_file_for_framework(&output_file_path, &target_framework) {
         return Err(AppError::UnreachedError);
     }
 
+    let output_file_path = resolve_output_file(output_file_path, &target_framework, options.overwrite, mode)
+        .map_err(in_input)?;
+    debug!("finish checking condition");
 
-    // check these files is existing
-    if !input_file_path.exists() {
+    let file = convert_mig_file(&input_file_path, target_framework, &options.migration_name)
+        .map_err(|e| in_input(e.into()))?;
+    write_generated(&output_file_path, &file, mode).map_err(in_input)?;
+    return Ok(report("Success!! converted!".to_string()));
+}
+
+/// settings of the target. the options are prior to the target in mig.toml and then the top level.
+/// out_dir of many targets is divided by the name of the target.
+fn to_convert_options(
+    matches: &ArgMatches<'_>,
+    config: &Config,
+    framework: &Framework,
+    many_targets: bool,
+    mode: OutputMode,
+    now: NaiveDateTime,
+) -> ConvertOptions {
+    let framework = match config.get_dialect(framework) {
+        Some(dialect) => framework.with_dialect(dialect),
+        None => *framework,
+    };
+    let target_config = config.get_target_config(&framework);
+    let shared_dir = |dir: PathBuf| if many_targets { dir.join(framework.name()) } else { dir };
+
+    // default is mig.toml and then the directory of the framework's project
+    let out_dir = match matches.value_of("OUT_DIR") {
+        Some(out_dir) => shared_dir(PathBuf::from(out_dir)),
+        None if matches.value_of("OUTPUT").is_some() => PathBuf::new(),
+        None => match (target_config.and_then(|target| target.out_dir.clone()), config.out_dir.clone()) {
+            (Some(out_dir), _) => out_dir,
+            (None, Some(out_dir)) => shared_dir(out_dir),
+            (None, None) => PathBuf::from(framework.default_out_dir()),
+        },
+    };
+    return ConvertOptions {
+        framework,
+        out_dir,
+        overwrite: Overwrite::from_matches(matches),
+        mode,
+        now,
+        migration_name: config.get_target_migration_name(&framework).to_string(),
+    };
+}
+
+// summary is shown in stderr without -q
+fn report(summary: String) -> String {
+    print_summary(&summary);
+    return String::new();
+}
+
+// the warnings are shown and the denied lints are the error
+fn to_lint_result(warnings: Vec<(Lint, String)>, config: &Config) -> Result<(), AppError> {
+    let denied = apply_lints(warnings, &config.lint);
+    return if denied.is_empty() { Ok(()) } else { Err(AppError::DeniedLints(denied)) };
+}
+
+fn check_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    // default is all mig-files in schema_dir of mig.toml
+    let inputs: Vec<String> = match matches.values_of("INPUT") {
+        Some(inputs) => inputs.map(|input| input.to_string()).collect(),
+        None => {
+            let schema_dir = config.schema_dir.as_ref().ok_or(AppError::NoInput)?;
+            collect_mig_files(&schema_dir.display().to_string())?.iter()
+                .map(|path| path.display().to_string())
+                .collect()
+        }
+    };
+    return check_mig_files(&inputs, config);
+}
+
+// the lints of [lint] in mig.toml are also checked
+fn check_mig_files(inputs: &[String], config: &Config) -> Result<String, AppError> {
+    let mut errors = vec!();
+    for input in inputs.iter() {
+        let input_file_path = PathBuf::from(input);
+        let res = validate_input_file(&input_file_path)
+            .and_then(|_| check_mig_file(&input_file_path).map_err(AppError::Converter))
+            .and_then(|schema| to_lint_result(lint_schema(&schema), config));
+        if let Err(e) = res {
+            errors.push((input.to_string(), e));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(AppError::InvalidFiles(errors));
+    }
+    return Ok(report(format!("{} file(s) checked. no problem", inputs.len())));
+}
+
+/// the tables of the old schema are renamed by --rename. the hints of the renames are warned.
+fn diff_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    let inputs: Vec<&str> = matches.values_of("INPUT").ok_or(AppError::UnreachedError)?.collect();
+    let frameworks = to_alteration_targets(matches, config)?;
+    let renames = to_renames(matches)?;
+
+    let (old, new) = match inputs.as_slice() {
+        [old, new] => (load_mig_files(old)?, load_mig_files(new)?),
+        [new] => (load_git_mig_file(matches.value_of("REVISION").unwrap_or("HEAD"), new)?, load_mig_files(new)?),
+        _ => return Err(AppError::UnreachedError),
+    };
+    let diff = diff_schemas(&old, &new, &renames);
+    if diff.is_empty() {
+        return Ok(report("no difference".to_string()));
+    }
+    let written = write_diff(matches, config, &frameworks, &diff)?;
+    return Ok(report(format!("{} table(s) are changed. {} file(s) are made", diff.tables.len(), written)));
+}
+
+/// the mig-files are the desired state. the migrations from the snapshot are written and the snapshot is updated.
+fn generate_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    // default is schema_dir in mig.toml
+    let input = match matches.value_of("INPUT") {
+        Some(input) => input.to_string(),
+        None => config.schema_dir.as_ref()
+            .map(|dir| dir.display().to_string())
+            .ok_or(AppError::NoInput)?,
+    };
+    let frameworks = to_alteration_targets(matches, config)?;
+    let renames = to_renames(matches)?;
+    let state_file = match matches.value_of("STATE") {
+        Some(state_file) => PathBuf::from(state_file),
+        None => config.get_state_file(),
+    };
+
+    let new = load_mig_files(&input)?;
+    let old = load_state(&state_file)?;
+    let diff = diff_schemas(&old, &new, &renames);
+    if diff.is_empty() {
+        return Ok(report(format!("{} is up to date", state_file.display())));
+    }
+    let written = write_diff(matches, config, &frameworks, &diff)?;
+    // the snapshot is kept until the migrations are written
+    if !matches.is_present("DRY_RUN") {
+        save_state(&state_file, &new)?;
+    }
+    return Ok(report(format!("{} table(s) are changed. {} file(s) are made", diff.tables.len(), written)));
+}
+
+// json and yaml cannot alter the schema
+fn to_alteration_targets(matches: &ArgMatches<'_>, config: &Config) -> Result<Vec<Framework>, AppError> {
+    let targets = matches.value_of("TARGET_FW").map(|t| t.to_string()).or_else(|| config.get_targets())
+        .ok_or(AppError::NoTarget)?;
+    let frameworks = to_framework_types(&targets).map_err(AppError::NotSupportedFrameWork)?;
+    if let Some(framework) = frameworks.iter().find(|fw| !fw.has_alteration()) {
+        return Err(AppError::NoAlteration(framework.name().to_string()));
+    }
+    return Ok(frameworks);
+}
+
+fn to_renames(matches: &ArgMatches<'_>) -> Result<Renames, AppError> {
+    let mut renames = Renames::default();
+    for hint in matches.values_of("RENAME").into_iter().flatten() {
+        renames.add(hint).map_err(AppError::InvalidRename)?;
+    }
+    return Ok(renames);
+}
+
+/// write the migrations of the diff for the targets. returns the number of the written files.
+fn write_diff(matches: &ArgMatches<'_>, config: &Config, frameworks: &[Framework], diff: &SchemaDiff)
+              -> Result<usize, AppError> {
+    let mut warnings: Vec<(Lint, String)> = diff.hints.iter().map(|hint| (Lint::RenameHint, hint.clone())).collect();
+    // down() of the step throws
+    for reason in diff.tables.iter().filter_map(|step| step.reverse().err()).flatten() {
+        warnings.push((Lint::Irreversible, format!("cannot be rolled back: {}", reason)));
+    }
+    // up of the step fails
+    let sqlite = |framework: &Framework| {
+        return matches!(framework, Framework::Sql(Dialect::Sqlite) | Framework::GolangMigrate(Dialect::Sqlite));
+    };
+    if frameworks.iter().any(sqlite) {
+        for reason in diff.tables.iter().flat_map(sql::sqlite_unsupported) {
+            warnings.push((Lint::SqliteRecreate, reason));
+        }
+    }
+    // nothing is written if a lint is denied
+    to_lint_result(warnings, config)?;
+    let mode = if matches.is_present("DRY_RUN") { OutputMode::DryRun } else { OutputMode::Write };
+    let now = config.timestamp_timezone.now();
+    // all paths are checked before writing not to leave a part of the migrations
+    let mut outputs = vec!();
+    for framework in frameworks.iter() {
+        let options = to_convert_options(matches, config, framework, frameworks.len() > 1, mode, now);
+        let files = generate_diff(options.framework, diff, &options.migration_name);
+        let paths = to_new_migration_paths(&files, &options)?;
+        outputs.extend(files.into_iter().zip(paths));
+    }
+    for (file, path) in outputs.iter() {
+        write_generated(path, file, mode)?;
+    }
+    return Ok(outputs.len());
+}
+
+// the mig-file in the revision of git
+fn load_git_mig_file(revision: &str, input: &str) -> Result<Schema, AppError> {
+    let path = PathBuf::from(input);
+    validate_input_file(&path).map_err(|e| AppError::InvalidFiles(vec!((input.to_string(), e))))?;
+    let content = git_show(revision, &path).map_err(|msg| AppError::FailedGitShow(input.to_string(), msg))?;
+    let schema = parse_str(&content).and_then(|file| analyze(&file))
+        .map_err(|e| AppError::InvalidFiles(vec!((format!("{}:{}", revision, input), e.into()))))?;
+    return Ok(schema);
+}
+
+/// one mig-file for each table. nothing is written if one of the mig-files exists without --force.
+fn import_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    let input = matches.value_of("INPUT").ok_or(AppError::UnreachedError)?;
+    let imported = match matches.value_of("FROM") {
+        Some("laravel") => import::laravel::import_migrations(&read_import_sources(input, "php")?),
+        Some("sql") => import::sql::import_schema(&read_import_sources(input, "sql")?),
+        _ => return Err(AppError::UnreachedError),
+    };
+    return write_imported(imported, matches, config);
+}
+
+fn introspect_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    let input = matches.value_of("INPUT").ok_or(AppError::UnreachedError)?;
+    if !Path::new(input).is_file() {
+        return Err(AppError::InputFileIsNotExist);
+    }
+    let imported = match matches.value_of("DATABASE") {
+        Some("sqlite") => {
+            let flags = rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY;
+            rusqlite::Connection::open_with_flags(input, flags)
+                .and_then(|connection| import::sqlite::introspect(&connection))
+                .map_err(|s_e| AppError::FailedReadDatabase(input.to_string(), s_e))?
+        }
+        _ => return Err(AppError::UnreachedError),
+    };
+    return write_imported(imported, matches, config);
+}
+
+fn verify_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    // default is schema_dir in mig.toml
+    let input = match matches.value_of("INPUT") {
+        Some(input) => input.to_string(),
+        None => config.schema_dir.as_ref()
+            .map(|dir| dir.display().to_string())
+            .ok_or(AppError::NoInput)?,
+    };
+    let schema = load_mig_files(&input)?;
+    verify_sqlite(&schema).map_err(AppError::Verify)?;
+    return Ok(report(format!("{} table(s) are verified with sqlite", schema.tables.len())));
+}
+
+// mig-file of each table in --out-dir with the warnings
+fn write_imported(mut imported: Imported, matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    retain_valid_names(&mut imported);
+
+    let out_dir = match matches.value_of("OUT_DIR") {
+        Some(out_dir) => PathBuf::from(out_dir),
+        None => config.schema_dir.clone().unwrap_or_default(),
+    };
+    let mode = if matches.is_present("DRY_RUN") { OutputMode::DryRun } else { OutputMode::Write };
+    let mut files = vec!();
+    for table in imported.schema.tables.iter() {
+        let path = out_dir.join(format!("{}.mig", table.name));
+        if mode == OutputMode::Write && path.exists() && !matches.is_present("FORCE") {
+            return Err(AppError::MigrationIsExist(path));
+        }
+        files.push((path, to_mig_file(table, &mut imported.warnings)));
+    }
+    for warning in imported.warnings.iter() {
+        warn!("{}", warning);
+    }
+    for (path, content) in files.iter() {
+        write_output(path, content, mode)?;
+    }
+    return Ok(report(format!("{} table(s) are imported. {} warning(s)", files.len(), imported.warnings.len())));
+}
+
+// (file name, content) of the file or the files of the extension in the directory in order of the names
+fn read_import_sources(input: &str, extension: &str) -> Result<Vec<(String, String)>, AppError> {
+    let path = PathBuf::from(input);
+    if !path.exists() {
         return Err(AppError::InputFileIsNotExist);
     }
-    // TODO もし今後似たようなファイルが存在するとき作成するか聞くような仕様にするなら以下を実装
-    /*
-    if output_file_path.exists_with_ignore_timestamp() {
-        // 確認
-        if !confirm("remake the output file?") {
-            return Err("output file exist");
+    let mut paths = vec!();
+    if path.is_dir() {
+        let entries = fs::read_dir(&path).map_err(|e| AppError::FailedReadDirectory(input.to_string(), e))?;
+        for entry in entries {
+            let entry_path = entry.map_err(|e| AppError::FailedReadDirectory(input.to_string(), e))?.path();
+            if entry_path.is_file() && is_extension(&entry_path, extension) {
+                paths.push(entry_path);
+            }
         }
+        paths.sort();
+    } else {
+        paths.push(path);
     }
-    */
+    if paths.is_empty() {
+        return Err(AppError::NoImportFile(input.to_string()));
+    }
+    let mut sources = vec!();
+    for path in paths {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| AppError::Converter(ConverterError::FailedReadInputFile(e)))?;
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        sources.push((file_name, content));
+    }
+    return Ok(sources);
+}
+
+/// the same migration of the table and the command is overwritten, so the timestamp is not changed on every save
+fn watch_action(matches: &ArgMatches<'_>, config: &Config) -> Result<String, AppError> {
+    let dir = match matches.value_of("INPUT") {
+        Some(dir) => PathBuf::from(dir),
+        None => config.schema_dir.clone().ok_or(AppError::NoInput)?,
+    };
+    let input = dir.display().to_string();
+    // only check without target
+    let frameworks = match matches.value_of("TARGET_FW").map(|t| t.to_string()).or_else(|| config.get_targets()) {
+        Some(targets) => to_framework_types(&targets).map_err(AppError::NotSupportedFrameWork)?,
+        None => vec!(),
+    };
+    let json_message = matches.value_of("MESSAGE_FORMAT") == Some("json");
+
+    info!("watching {}", input);
+    watch_mig_files(&dir, || {
+        let res = collect_mig_files(&input).and_then(|inputs| {
+            if frameworks.is_empty() {
+                let inputs: Vec<String> = inputs.iter().map(|path| path.display().to_string()).collect();
+                return check_mig_files(&inputs, config);
+            }
+            let now = config.timestamp_timezone.now();
+            let targets: Vec<ConvertOptions> = frameworks.iter()
+                .map(|framework| ConvertOptions {
+                    overwrite: Overwrite::Force,
+                    ..to_convert_options(matches, config, framework, frameworks.len() > 1, OutputMode::Write, now)
+                })
+                .collect();
+            return convert_mig_files(&input, &inputs, &targets);
+        });
+        match res {
+            Ok(summary) => info!("{}", summary),
+            Err(e) => print_error(&e, json_message),
+        }
+    }).map_err(|e| AppError::FailedWatch(input.clone(), e))?;
+    return Ok(String::new());
+}
+
+fn fmt_action(matches: &ArgMatches<'_>) -> Result<String, AppError> {
+    let inputs: Vec<&str> = matches.values_of("INPUT").ok_or(AppError::UnreachedError)?.collect();
+    let check = matches.is_present("CHECK");
+    let mut errors = vec!();
+    let mut formatted = 0;
+    for input in inputs.iter() {
+        match format_mig_file(&PathBuf::from(input), check) {
+            Ok(true) if check => errors.push((input.to_string(), AppError::NotFormatted)),
+            Ok(true) => formatted += 1,
+            Ok(false) => {}
+            Err(e) => errors.push((input.to_string(), e)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(AppError::InvalidFiles(errors));
+    }
+    return Ok(report(format!("{} of {} file(s) formatted", formatted, inputs.len())));
+}
+
+// rewrite the file only when the style is changed
+/// true if the file is changed. the file is not written for check.
+fn format_mig_file(input_file_path: &Path, check: bool) -> Result<bool, AppError> {
+    validate_input_file(input_file_path)?;
+    let content = fs::read_to_string(input_file_path)
+        .map_err(|e| AppError::Converter(ConverterError::FailedReadInputFile(e)))?;
+    let file = parse_mig_file(input_file_path)?;
+    let formatted = format_file(&file);
+    if formatted == content {
+        return Ok(false);
+    }
+    if check {
+        return Ok(true);
+    }
+    fs::write(input_file_path, formatted).map_err(AppError::FailedWriteFile)?;
+    return Ok(true);
+}
+
+fn new_action(matches: &ArgMatches<'_>) -> Result<String, AppError> {
+    let name = matches.value_of("NAME").ok_or(AppError::UnreachedError)?;
+    // same as Name token of mig-file
+    if !is_mig_name(name) {
+        return Err(AppError::InvalidTableName(name.to_string()));
+    }
+    let output_file_path = match matches.value_of("OUTPUT") {
+        Some(output) => PathBuf::from(output),
+        None => PathBuf::from(format!("{}.mig", name)),
+    };
+    if !is_extension(&output_file_path, "mig") {
+        return Err(AppError::NotMigFile);
+    }
+    if output_file_path.exists() {
+        return Err(AppError::OutputFileIsExist);
+    }
+    let content = format!(":create {} {{\n    id {{ :increments }}\n    :timestamps\n}}\n", name);
+    fs::write(&output_file_path, content).map_err(AppError::FailedWriteFile)?;
+    return Ok(report(format!("created {}", output_file_path.display())));
+}
+
+fn targets_action() -> Result<String, AppError> {
+    let lines: Vec<String> = FRAMEWORKS.iter()
+        .map(|fw| format!(
+            "{:<16}.{:<8}{:<21}{}",
+            fw.name(),
+            get_extension_for_framework(fw),
+            fw.default_out_dir(),
+            fw.description()
+        ))
+        .collect();
+    return Ok(lines.join("\n"));
+}
+
+/// path to write the migration. it is the existing migration of the same table and command
+/// if it is allowed to overwrite.
+pub(crate) fn resolve_output_file(
+    output_file_path: PathBuf,
+    framework: &Framework,
+    overwrite: Overwrite,
+    mode: OutputMode,
+) -> Result<PathBuf, AppError> {
+    if mode == OutputMode::Stdout {
+        return Ok(output_file_path);
+    }
+    let existing = match find_same_migrations(&output_file_path, framework).pop() {
+        Some(existing) => existing,
+        None => return Ok(output_file_path),
+    };
+    let allowed = match overwrite {
+        Overwrite::Force => true,
+        Overwrite::Refuse => false,
+        // nothing is overwritten actually
+        Overwrite::Ask if mode == OutputMode::DryRun => true,
+        Overwrite::Ask => confirm(&format!("{} already exists. overwrite it?", existing.display())),
+    };
+    if !allowed {
+        return Err(AppError::MigrationIsExist(existing));
+    }
+    return Ok(existing);
+}
 
-    // check these files is file
+fn validate_input_file(input_file_path: &Path) -> Result<(), AppError> {
+    // check extension
+    if !is_extension(input_file_path, "mig") {
+        return Err(AppError::NotMigFile);
+    }
+    // check the file is existing
+    if !input_file_path.exists() {
+        return Err(AppError::InputFileIsNotExist);
+    }
+    // check the file is file
     if !input_file_path.is_file() {
         return Err(AppError::NotIsFile);
     }
-    println!("finish checking condition");
-
-    let target_framework = framework_type.unwrap().clone();
-    let res = convert_to_migration_file(input_file_path, output_file_path, target_framework)
-        .map_err(|e| AppError::Converter(e));
-    return res;
+    return Ok(());
 }
@@ -0,0 +1,48 @@
+use std::fs;
+use std::path::Path;
+
+use log::info;
+
+use crate::app::AppError;
+use crate::app::generator::GeneratedFile;
+use crate::app::helper::file_helper::to_down_file;
+
+/// where the generated files go
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputMode {
+    Write,
+    // show the paths and contents without writing
+    DryRun,
+    // `-O -`
+    Stdout,
+}
+
+pub fn write_output(path: &Path, content: &str, mode: OutputMode) -> Result<(), AppError> {
+    match mode {
+        OutputMode::Write => {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir).map_err(AppError::FailedWriteFile)?;
+            }
+            fs::write(path, content).map_err(AppError::FailedWriteFile)?;
+            info!("converted: {}", path.display());
+        }
+        OutputMode::DryRun => {
+            println!("would write {}", path.display());
+            print!("{}", content);
+            if !content.ends_with('\n') {
+                println!();
+            }
+        }
+        OutputMode::Stdout => print!("{}", content),
+    }
+    return Ok(());
+}
+
+/// the migration and its rollback file if the framework has it. stdout has only the migration.
+pub fn write_generated(path: &Path, file: &GeneratedFile, mode: OutputMode) -> Result<(), AppError> {
+    write_output(path, &file.content, mode)?;
+    if let Some(down) = file.down.as_ref().filter(|_| mode != OutputMode::Stdout) {
+        write_output(&to_down_file(path), down, mode)?;
+    }
+    return Ok(());
+}
@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::Path;
+
+use log::info;
+
+use crate::app::AppError;
+use crate::app::converter::mig::Schema;
+use crate::app::generator::export::{EXPORT_VERSION, ExportedSchema, to_json};
+
+// snapshot of the schema which the migrations are generated for.
+// it is the same document as `--target json`, so it can be read by other tools.
+
+/// empty schema if the snapshot does not exist yet. all tables are created at first.
+pub fn load_state(path: &Path) -> Result<Schema, AppError> {
+    if !path.exists() {
+        return Ok(Schema::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::InvalidState(path.to_path_buf(), e.to_string()))?;
+    let exported: ExportedSchema = serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidState(path.to_path_buf(), e.to_string()))?;
+    // older documents are compatible
+    if exported.version > EXPORT_VERSION {
+        return Err(AppError::InvalidState(
+            path.to_path_buf(),
+            format!("version {} is newer than {}", exported.version, EXPORT_VERSION),
+        ));
+    }
+    return Ok(Schema { tables: exported.tables });
+}
+
+pub fn save_state(path: &Path, schema: &Schema) -> Result<(), AppError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(AppError::FailedWriteFile)?;
+    }
+    fs::write(path, to_json(schema)).map_err(AppError::FailedWriteFile)?;
+    info!("updated: {}", path.display());
+    return Ok(());
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use rusqlite::Connection;
+
+use crate::app::converter::mig::{Column, ColumnType, Index, IndexKind, Schema, Table};
+use crate::app::diff::{default_index_name, to_type_option};
+use crate::app::generator::sql::{Dialect, generate_schema};
+use crate::app::import::sqlite::introspect;
+
+// the generated sql is applied to the database and the tables of the database are compared with the mig-files.
+// only the columns, the types, the nullability, the foreign keys and the indexes are compared
+// because sqlite has no comment and the defaults are written in many ways.
+
+/// error of the verification
+#[derive(Debug)]
+pub enum VerifyError {
+    // the database rejects the generated sql
+    FailedApply(rusqlite::Error),
+    FailedIntrospect(rusqlite::Error),
+    // differences between the mig-files and the database
+    Mismatch(Vec<String>),
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            VerifyError::FailedApply(s_e) => write!(f, "generated sql cannot be applied to sqlite,: {}", s_e),
+            VerifyError::FailedIntrospect(s_e) => write!(f, "failed read the tables of sqlite,: {}", s_e),
+            VerifyError::Mismatch(differences) => {
+                write!(f, "{} difference(s) between the mig-files and sqlite", differences.len())?;
+                for difference in differences.iter() {
+                    write!(f, "\n {}", difference)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// apply the sqlite ddl of the schema to an in-memory database and compare the tables with the schema
+pub fn verify_sqlite(schema: &Schema) -> Result<(), VerifyError> {
+    let connection = Connection::open_in_memory().map_err(VerifyError::FailedIntrospect)?;
+    connection.execute_batch(&generate_schema(schema, Dialect::Sqlite)).map_err(VerifyError::FailedApply)?;
+    let actual = introspect(&connection).map_err(VerifyError::FailedIntrospect)?.schema;
+
+    let mut differences = vec!();
+    for table in schema.tables.iter() {
+        match actual.get_table(&table.name) {
+            Some(actual_table) => compare_table(&to_sqlite_table(table), &to_sqlite_table(actual_table), &mut differences),
+            None => differences.push(format!("table {} is not created", table.name)),
+        }
+    }
+    for table in actual.tables.iter().filter(|t| schema.get_table(&t.name).is_none()) {
+        differences.push(format!("table {} is not in the mig-files", table.name));
+    }
+    return if differences.is_empty() { Ok(()) } else { Err(VerifyError::Mismatch(differences)) };
+}
+
+fn compare_table(expected: &Table, actual: &Table, differences: &mut Vec<String>) {
+    let name = &expected.name;
+    for column in expected.columns.iter() {
+        let actual_column = match actual.get_column(&column.name) {
+            Some(actual_column) => actual_column,
+            None => {
+                differences.push(format!("column {}.{} is not created", name, column.name));
+                continue;
+            }
+        };
+        if column.column_type != actual_column.column_type {
+            differences.push(format!(
+                "type of {}.{} is {} but {} in sqlite",
+                name, column.name, to_type_option(&column.column_type), to_type_option(&actual_column.column_type),
+            ));
+        }
+        if column.nullable != actual_column.nullable {
+            let to_null = |nullable: bool| if nullable { "NULL" } else { "NOT NULL" };
+            differences.push(format!(
+                "{}.{} is {} but {} in sqlite",
+                name, column.name, to_null(column.nullable), to_null(actual_column.nullable),
+            ));
+        }
+        if column.references != actual_column.references {
+            differences.push(format!("foreign key of {}.{} is different in sqlite", name, column.name));
+        }
+    }
+    for column in actual.columns.iter().filter(|c| expected.get_column(&c.name).is_none()) {
+        differences.push(format!("column {}.{} is not in the mig-files", name, column.name));
+    }
+    // the columns of :timestamps and :soft-deletes are at the end in the database
+    let to_names = |table: &Table| -> Vec<String> {
+        return table.columns.iter()
+            .map(|c| c.name.clone())
+            .filter(|name| !["created_at", "updated_at", "deleted_at"].contains(&name.as_str()))
+            .collect();
+    };
+    let (names, actual_names) = (to_names(expected), to_names(actual));
+    if names.len() == actual_names.len() && names != actual_names {
+        differences.push(format!("order of the columns of {} is different in sqlite", name));
+    }
+    for index in expected.indexes.iter().filter(|index| !actual.indexes.contains(index)) {
+        differences.push(format!("{} is not created", to_index_description(name, index)));
+    }
+    for index in actual.indexes.iter().filter(|index| !expected.indexes.contains(index)) {
+        differences.push(format!("{} is not in the mig-files", to_index_description(name, index)));
+    }
+}
+
+// the table which sqlite can have. e.g. every integer is INTEGER
+fn to_sqlite_table(table: &Table) -> Table {
+    let mut sqlite_table = table.clone();
+    sqlite_table.columns = table.columns.iter().map(to_sqlite_column).collect();
+    // the columns of the options are compared as the columns
+    if table.timestamps {
+        sqlite_table.columns.push(nullable_timestamp("created_at"));
+        sqlite_table.columns.push(nullable_timestamp("updated_at"));
+    }
+    if table.soft_deletes {
+        sqlite_table.columns.push(nullable_timestamp("deleted_at"));
+    }
+    sqlite_table.timestamps = false;
+    sqlite_table.soft_deletes = false;
+    sqlite_table.indexes = table.indexes.iter()
+        .map(|index| {
+            let is_default = index.kind == IndexKind::Primary
+                || index.name.as_ref() == Some(&default_index_name(&table.name, index));
+            Index { name: if is_default { None } else { index.name.clone() }, ..index.clone() }
+        })
+        .collect();
+    return sqlite_table;
+}
+
+fn to_sqlite_column(column: &Column) -> Column {
+    let column_type = match column.column_type {
+        ColumnType::BigIncrements => ColumnType::Increments,
+        t if t.is_integer() && !t.is_auto_increment() => ColumnType::Integer,
+        ColumnType::Double => ColumnType::Float,
+        ColumnType::Json => ColumnType::Text,
+        ColumnType::String { length: Some(255) } => ColumnType::String { length: None },
+        t => t,
+    };
+    let mut sqlite_column = Column::new(&column.name, column_type);
+    sqlite_column.nullable = column.nullable && !column_type.is_auto_increment();
+    sqlite_column.references = column.references.clone();
+    return sqlite_column;
+}
+
+fn nullable_timestamp(name: &str) -> Column {
+    let mut column = Column::new(name, ColumnType::Timestamp);
+    column.nullable = true;
+    return column;
+}
+
+fn to_index_description(table: &str, index: &Index) -> String {
+    let kind = match index.kind {
+        IndexKind::Index => "index",
+        IndexKind::Unique => "unique index",
+        IndexKind::Primary => "primary key",
+    };
+    return match &index.name {
+        Some(name) => format!("{} {} of {}({})", kind, name, table, index.columns.join(", ")),
+        None => format!("{} of {}({})", kind, table, index.columns.join(", ")),
+    };
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use log::warn;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::app::helper::file_helper::is_extension;
+
+// `mig watch` re-runs on changes of the mig-files in the directory
+
+// events in this time are handled at once. editors write a file in some steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// run at first and whenever a mig-file in the directory is changed.
+/// it returns only when the directory cannot be watched.
+pub fn watch_mig_files<F: FnMut()>(dir: &Path, mut run: F) -> Result<(), notify::Error> {
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    run();
+    while let Ok(event) = rx.recv() {
+        // an error of one event does not stop watching. e.g. the buffer of the events overflows
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("failed watch {},: {}", dir.display(), e);
+                continue;
+            }
+        };
+        if !is_mig_file_event(&event) {
+            continue;
+        }
+        // wait until the editor finishes writing
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        run();
+    }
+    return Ok(());
+}
+
+// reading the file is not change
+fn is_mig_file_event(event: &Event) -> bool {
+    return !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| is_extension(path, "mig"));
+}
@@ -1 +1,41 @@
+#![allow(clippy::needless_return)]
+
+//! mig converts mig-file to migration files of web frameworks.
+//! The library API is `parse_str` -> `analyze` -> `generate`.
+
+use crate::app::converter::parser::lexical_analyzer;
+use crate::app::converter::semantic::semantic_analyzer;
+use crate::app::converter::syntax::syntax_analyzer;
+
+pub use crate::app::converter::ast::File;
+pub use crate::app::converter::ConverterError;
+pub use crate::app::diagnostic::{Diagnostic, Severity};
+pub use crate::app::diff::{AlterTable, Change, diff_schemas, Renames, SchemaDiff, TableDiff};
+pub use crate::app::converter::mig::{Column, ColumnType, ForeignKey, Index, IndexKind, Method, Schema, Table, Value};
+pub use crate::app::framework::{Framework, to_framework_type, to_framework_types};
+pub use crate::app::generator::GeneratedFile;
+pub use crate::app::generator::export::{ExportedSchema, EXPORT_VERSION};
+pub use crate::app::generator::sql::{Dialect, sqlite_unsupported};
+
 pub mod app;
+
+/// lexical and syntax analysis of the content of mig-file
+pub fn parse_str(src: &str) -> Result<File, ConverterError> {
+    let seq = lexical_analyzer(src)?;
+    return Ok(syntax_analyzer(&seq)?);
+}
+
+/// semantic analysis. Schema is serializable with serde.
+pub fn analyze(file: &File) -> Result<Schema, ConverterError> {
+    return Ok(semantic_analyzer(file)?);
+}
+
+/// migration file of the target framework for each table. file names have no timestamp.
+pub fn generate(target: Framework, schema: &Schema) -> Vec<GeneratedFile> {
+    return app::generator::generate(target, schema);
+}
+
+/// migration files which alter the old schema to the new one. the schema of `diff_schemas` is made by `analyze`.
+pub fn generate_diff(target: Framework, diff: &SchemaDiff) -> Vec<GeneratedFile> {
+    return app::generator::generate_diff(target, diff, app::generator::DEFAULT_MIGRATION_NAME);
+}
@@ -1,15 +1,34 @@
-use mig::app::{action_controller, get_matches_safe, mig_app};
+use std::process;
+
+use clap::ErrorKind;
+
+use mig::app::{action_controller, get_matches_safe, is_json_message, log_level, mig_app};
+use mig::app::diagnostic::print_error;
+use mig::app::exit_code;
+use mig::app::logger::init_logger;
 
 fn main() {
     let matches_rs = get_matches_safe(mig_app());
     match matches_rs {
         Err(e) => {
+            // help and version are not error
+            if e.kind == ErrorKind::HelpDisplayed || e.kind == ErrorKind::VersionDisplayed {
+                println!("{}", e.message);
+                return;
+            }
             eprintln!("\nArgumentError:\n {}", e);
+            process::exit(exit_code::ARGUMENT);
         }
         Ok(matches) => {
+            init_logger(log_level(&matches));
+            let json_message = is_json_message(&matches);
             match action_controller(matches) {
+                Ok(msg) if msg.is_empty() => {}
                 Ok(msg) => println!("{}", msg),
-                Err(e) => eprintln!("\nConverterError:\n {}", e),
+                Err(e) => {
+                    print_error(&e, json_message);
+                    process::exit(e.exit_code());
+                }
             };
         }
     }
@@ -0,0 +1,557 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+const MEMBERS: &str = ":create members {
+    id { :increments }
+    name { :string 10 }
+}
+";
+
+// empty directory for each test
+fn work_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mig_cli_test_{}_{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn mig(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mig"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn overwrite_existing_migration() {
+    let dir = work_dir("overwrite");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    let migrations = dir.join("database/migrations");
+    fs::create_dir_all(&migrations).unwrap();
+    let old = migrations.join("2019_07_12_215755_create_members_table.php");
+    fs::write(&old, "old").unwrap();
+
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel", "--no-interactive"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+    assert_eq!(fs::read_to_string(&old).unwrap(), "old");
+
+    // stdin is empty, so it is not confirmed
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel"]);
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(&old).unwrap(), "old");
+
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel", "--force"]);
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&old).unwrap().contains("class CreateMembersTable"));
+    assert_eq!(file_names(&migrations), vec!("2019_07_12_215755_create_members_table.php"));
+}
+
+#[test]
+fn dry_run_and_stdout() {
+    let dir = work_dir("dry_run");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("would write database/migrations/"));
+    assert!(stdout.contains("_create_members_table.php\n<?php\n"));
+    assert_eq!(file_names(&dir), vec!("members.mig"));
+
+    // stdout has only the generated code
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "json", "-O", "-"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["tables"][0]["name"], "members");
+    assert_eq!(file_names(&dir), vec!("members.mig"));
+}
+
+#[test]
+fn verbosity() {
+    let dir = work_dir("verbosity");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+
+    // the summary is in stderr
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "converted 1 table(s) from 1 file(s) into 1 file(s)\n");
+
+    let output = mig(&dir, &["check", "members.mig"]);
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "1 file(s) checked. no problem\n");
+    let output = mig(&dir, &["-q", "check", "members.mig"]);
+    assert!(output.stderr.is_empty());
+
+    let output = mig(&dir, &["check", "members.mig", "-vv"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[debug] schema: Schema"));
+
+    fs::write(dir.join("broken.mig"), ":create members { id }").unwrap();
+    let output = mig(&dir, &["-q", "check", "broken.mig"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("broken.mig"));
+
+    // the summary is shown with the failures
+    let output = mig(&dir, &["convert", "-I", ".", "-T", "json", "--out-dir", "out"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("converted 1 table(s) from 2 file(s) into 1 file(s)"), "{}", stderr);
+    assert!(stderr.contains("broken.mig"));
+}
+
+#[test]
+fn json_diagnostics() {
+    let dir = work_dir("json_diagnostics");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("broken.mig"), ":create members {\n    id { :int :hoge }\n}\n").unwrap();
+
+    let output = mig(&dir, &["check", "members.mig", "broken.mig", "none.mig", "--message-format", "json"]);
+    assert!(!output.status.success());
+    let diagnostics: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout).lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0]["file"], "broken.mig");
+    assert_eq!(diagnostics[0]["line"], 2);
+    assert_eq!(diagnostics[0]["column"], 15);
+    assert_eq!(diagnostics[0]["severity"], "error");
+    assert_eq!(diagnostics[0]["code"], "E0001");
+    assert_eq!(diagnostics[1]["file"], "none.mig");
+    assert_eq!(diagnostics[1]["line"], serde_json::Value::Null);
+    assert_eq!(diagnostics[1]["code"], "E0904");
+
+    // file is the input mig-file, not the output
+    let to_diagnostic = |stdout: &[u8]| -> serde_json::Value { serde_json::from_slice(stdout).unwrap() };
+    let output = mig(&dir, &["convert", "-I", "none.mig", "-T", "laravel", "--message-format", "json"]);
+    assert_eq!(to_diagnostic(&output.stdout)["file"], "none.mig");
+    fs::create_dir_all(dir.join("schema")).unwrap();
+    fs::write(dir.join("schema").join("members.mig"), MEMBERS).unwrap();
+    let args = [
+        "convert", "-I", "schema", "-T", "laravel", "--out-dir", "out", "--no-interactive", "--message-format", "json",
+    ];
+    assert!(mig(&dir, &args).status.success());
+    let diagnostic = to_diagnostic(&mig(&dir, &args).stdout);
+    assert_eq!(diagnostic["file"], Path::new("schema").join("members.mig").display().to_string());
+    assert_eq!(diagnostic["code"], "E0914");
+
+    // the generated code and the diagnostics are not mixed in stdout
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel", "-O", "-", "--message-format", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+    let diagnostic: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diagnostic["code"], "E0929");
+}
+
+#[test]
+fn exit_codes() {
+    let dir = work_dir("exit_codes");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("lexer.mig"), ":create members { id { :int :default 99999 } }").unwrap();
+    fs::write(dir.join("syntax.mig"), ":create members { id { :int }").unwrap();
+    fs::write(dir.join("semantic.mig"), ":create members { id { :hoge } }").unwrap();
+    fs::write(dir.join("decimal.mig"), ":create members { price { :decimal 2 8 } }").unwrap();
+    fs::write(dir.join("unsigned.mig"), ":create members { name { :string :unsigned } }").unwrap();
+    fs::write(dir.join("tables.mig"), ":create a { id { :int } }\n:create b { id { :int } }").unwrap();
+
+    let code = |args: &[&str]| mig(&dir, args).status.code();
+    assert_eq!(code(&["check", "members.mig"]), Some(0));
+    assert_eq!(code(&["convert", "-I", "members.mig"]), Some(2));
+    assert_eq!(code(&["convert", "-I", "members.mig", "-T", "rails"]), Some(2));
+    assert_eq!(code(&["check", "none.mig"]), Some(3));
+    assert_eq!(code(&["check", "lexer.mig"]), Some(4));
+    assert_eq!(code(&["check", "syntax.mig"]), Some(4));
+    assert_eq!(code(&["check", "semantic.mig"]), Some(5));
+    assert_eq!(code(&["check", "syntax.mig", "semantic.mig"]), Some(5));
+    assert_eq!(code(&["check", "decimal.mig"]), Some(5));
+    assert_eq!(code(&["check", "unsigned.mig"]), Some(5));
+    assert_eq!(code(&["convert", "-I", "tables.mig", "-T", "laravel", "-O", "x"]), Some(6));
+}
+
+#[test]
+fn project_config() {
+    let dir = work_dir("config");
+    fs::create_dir_all(dir.join("schema")).unwrap();
+    fs::create_dir_all(dir.join("app")).unwrap();
+    fs::write(dir.join("schema/members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("mig.toml"), "target = \"laravel\"
+schema_dir = \"schema\"
+out_dir = \"migrations\"
+
+[naming]
+migration = \"{table}_{method}\"
+").unwrap();
+
+    // mig.toml is found from the sub directory
+    let output = mig(&dir.join("app"), &["convert"]);
+    assert!(output.status.success());
+    let names = file_names(&dir.join("migrations"));
+    assert_eq!(names.len(), 1);
+    assert!(names[0].ends_with("_members_create.php"));
+    assert!(fs::read_to_string(dir.join("migrations").join(&names[0])).unwrap().contains("class MembersCreate"));
+
+    // the options override mig.toml
+    let output = mig(&dir, &["convert", "-I", "schema/members.mig", "-T", "json", "--out-dir", "out"]);
+    assert!(output.status.success());
+    assert_eq!(file_names(&dir.join("out")), vec!("schema.json"));
+
+    assert!(mig(&dir, &["check"]).status.success());
+
+    fs::write(dir.join("mig.toml"), "target = \"laravel\"\nunknown = 1\n").unwrap();
+    assert_eq!(mig(&dir, &["check", "schema/members.mig"]).status.code(), Some(2));
+}
+
+#[test]
+fn lint_levels() {
+    let dir = work_dir("lint");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("logs.mig"), ":create logs {\n    message { :text }\n}\n").unwrap();
+    let renamed = ":create members {\n    id { :increments }\n    fullname { :string 10 }\n}\n";
+    fs::write(dir.join("renamed.mig"), renamed).unwrap();
+    let shortened = ":create members {\n    id { :increments }\n    name { :string 5 }\n}\n";
+    fs::write(dir.join("shortened.mig"), shortened).unwrap();
+
+    // warn is the default
+    let output = mig(&dir, &["check", "logs.mig"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("table logs has no primary key [no-primary-key]"));
+
+    fs::write(dir.join("mig.toml"), "[lint]\nno-primary-key = \"deny\"\nrename-hint = \"allow\"\nirreversible = \"deny\"\n").unwrap();
+    let output = mig(&dir, &["check", "members.mig", "logs.mig"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("logs.mig: 1 lint(s) are denied in mig.toml"));
+
+    let output = mig(&dir, &["diff", "members.mig", "renamed.mig", "-T", "laravel", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("--rename"));
+
+    // nothing is written
+    let output = mig(&dir, &["diff", "members.mig", "shortened.mig", "-T", "laravel", "--out-dir", "out"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[irreversible]"));
+    assert!(!dir.join("out").exists());
+
+    // only sqlite cannot change the column
+    fs::write(dir.join("mig.toml"), "[lint]\nirreversible = \"allow\"\nsqlite-recreate = \"deny\"\n").unwrap();
+    let output = mig(&dir, &["diff", "members.mig", "shortened.mig", "-T", "sql-sqlite", "--dry-run"]);
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("sqlite cannot change the column name. recreate the table members [sqlite-recreate]"));
+    assert!(mig(&dir, &["diff", "members.mig", "shortened.mig", "-T", "sql-postgres", "--dry-run"]).status.success());
+
+    fs::write(dir.join("mig.toml"), "[lint]\nunknown = \"deny\"\n").unwrap();
+    assert_eq!(mig(&dir, &["check", "members.mig"]).status.code(), Some(2));
+}
+
+#[test]
+fn multiple_targets() {
+    let dir = work_dir("targets");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("mig.toml"), "target = [\"laravel\", \"golang-migrate\"]
+dialect = \"mysql\"
+
+[targets.golang-migrate]
+out_dir = \"worker/migrations\"
+naming = { migration = \"{table}\" }
+").unwrap();
+
+    let output = mig(&dir, &["convert", "-I", "members.mig"]);
+    assert!(output.status.success());
+    assert_eq!(file_names(&dir.join("database/migrations")).len(), 1);
+    let names = file_names(&dir.join("worker/migrations"));
+    assert_eq!(names.len(), 2);
+    assert!(names[0].ends_with("_members.down.sql"));
+    assert!(names[1].ends_with("_members.up.sql"));
+    let content = fs::read_to_string(dir.join("worker/migrations").join(&names[1])).unwrap();
+    assert!(content.contains("AUTO_INCREMENT"));
+    assert_eq!(fs::read_to_string(dir.join("worker/migrations").join(&names[0])).unwrap(), "DROP TABLE `members`;\n");
+
+    // each target has the directory in --out-dir
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "sql-postgres,sql-sqlite", "--out-dir", "out"]);
+    assert!(output.status.success());
+    assert_eq!(file_names(&dir.join("out")), vec!("sql-postgres", "sql-sqlite"));
+    assert_eq!(file_names(&dir.join("out/sql-sqlite")), vec!("schema.sql"));
+
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel,json", "-O", "members"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = mig(&dir, &["convert", "-I", "members.mig", "-T", "laravel,rails"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn watch_regenerates_same_file() {
+    let dir = work_dir("watch");
+    fs::create_dir_all(dir.join("schema")).unwrap();
+    fs::write(dir.join("schema/members.mig"), MEMBERS).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mig"))
+        .current_dir(&dir)
+        .args(["watch", "schema", "-T", "laravel", "--out-dir", "out"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let wait = || std::thread::sleep(Duration::from_millis(1500));
+
+    wait();
+    let names = file_names(&dir.join("out"));
+    assert_eq!(names.len(), 1);
+    fs::write(dir.join("schema/members.mig"), MEMBERS.replace("10", "20")).unwrap();
+    wait();
+    fs::write(dir.join("schema/members.mig"), ":create members { id { :hoge } }").unwrap();
+    wait();
+    child.kill().unwrap();
+
+    // the timestamp is not changed
+    assert_eq!(file_names(&dir.join("out")), names);
+    let content = fs::read_to_string(dir.join("out").join(&names[0])).unwrap();
+    assert!(content.contains("$table->string('name', 20);"));
+    let output = child.wait_with_output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("schema/members.mig: semantic error"));
+}
+
+#[test]
+fn fmt_check() {
+    let dir = work_dir("fmt");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    let unformatted = ":create posts{ id{:increments} # pk\n}\n";
+    fs::write(dir.join("posts.mig"), unformatted).unwrap();
+
+    let output = mig(&dir, &["fmt", "--check", "members.mig", "posts.mig"]);
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("posts.mig: file is not formatted"));
+    assert!(!stderr.contains("members.mig"));
+    assert_eq!(fs::read_to_string(dir.join("posts.mig")).unwrap(), unformatted);
+
+    assert!(mig(&dir, &["fmt", "posts.mig"]).status.success());
+    assert_eq!(fs::read_to_string(dir.join("posts.mig")).unwrap(), ":create posts {\n    id { :increments } # pk\n}\n");
+    assert!(mig(&dir, &["fmt", "--check", "members.mig", "posts.mig"]).status.success());
+}
+
+#[test]
+fn diff_migrations() {
+    let dir = work_dir("diff");
+    fs::write(dir.join("old.mig"), MEMBERS).unwrap();
+    let new = ":create members {\n    id { :increments }\n    fullname { :string 10 }\n}\n";
+    fs::write(dir.join("new.mig"), new).unwrap();
+
+    let output = mig(&dir, &["diff", "old.mig", "new.mig", "-T", "laravel,sql-mysql", "--out-dir", "out"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use --rename members.name=fullname"));
+    let laravel = dir.join("out").join("laravel");
+    let names = file_names(&laravel);
+    assert_eq!(names.len(), 1);
+    assert!(names[0].ends_with("_alter_members_table.php"));
+    let content = fs::read_to_string(laravel.join(&names[0])).unwrap();
+    assert!(content.contains("$table->dropColumn('name');"));
+    let names = file_names(&dir.join("out").join("sql-mysql"));
+    assert_eq!(names.len(), 2);
+    assert!(names[0].ends_with("_diff.down.sql"));
+    assert!(names[1].ends_with("_diff.sql"));
+
+    let output = mig(&dir, &["diff", "old.mig", "new.mig", "-T", "sql-postgres", "--rename", "members.name=fullname", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ALTER TABLE \"members\" RENAME COLUMN \"name\" TO \"fullname\";"));
+
+    let output = mig(&dir, &["diff", "old.mig", "new.mig", "-T", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn diff_with_git() {
+    let dir = work_dir("diff_git");
+    let git = |args: &[&str]| Command::new("git")
+        .current_dir(&dir)
+        .args(["-c", "user.name=mig", "-c", "user.email=mig@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    assert!(git(&["init", "-q"]).status.success());
+    assert!(git(&["add", "members.mig"]).status.success());
+    assert!(git(&["commit", "-q", "-m", "members"]).status.success());
+
+    let output = mig(&dir, &["diff", "members.mig", "-T", "sql-sqlite", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).is_empty());
+
+    fs::write(dir.join("members.mig"), MEMBERS.replace("}\n}", "}\n    :timestamps\n}")).unwrap();
+    let output = mig(&dir, &["diff", "members.mig", "-T", "sql-sqlite", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ALTER TABLE \"members\" ADD COLUMN \"created_at\" TIMESTAMP NULL;"));
+}
+
+#[test]
+fn diff_same_table_twice() {
+    let dir = work_dir("diff_twice");
+    fs::write(dir.join("members.mig"), MEMBERS).unwrap();
+    fs::write(dir.join("deleted.mig"), MEMBERS.replace("}\n}", "}\n    :soft-deletes\n}")).unwrap();
+    for _ in 0..2 {
+        assert!(mig(&dir, &["diff", "members.mig", "deleted.mig", "-T", "laravel", "--out-dir", "out"]).status.success());
+    }
+    // laravel cannot declare the same class twice
+    let names = file_names(&dir.join("out"));
+    assert_eq!(names.len(), 2);
+    for name in names.iter() {
+        let content = fs::read_to_string(dir.join("out").join(name)).unwrap();
+        assert!(content.contains("return new class extends Migration"), "{}", content);
+    }
+}
+
+#[test]
+fn generate_from_state() {
+    let dir = work_dir("generate");
+    fs::create_dir_all(dir.join("schema")).unwrap();
+    fs::write(dir.join("mig.toml"), "target = \"golang-migrate\"\nschema_dir = \"schema\"\n").unwrap();
+    fs::write(dir.join("schema").join("members.mig"), MEMBERS).unwrap();
+
+    assert!(mig(&dir, &["generate"]).status.success());
+    let names = file_names(&dir.join("migrations"));
+    assert_eq!(names.len(), 2);
+    assert!(names[1].ends_with("_create_members_table.up.sql"));
+    let state = fs::read_to_string(dir.join(".mig-state.json")).unwrap();
+    assert!(state.contains("\"name\": \"members\""));
+
+    // nothing is changed
+    assert!(mig(&dir, &["generate"]).status.success());
+    assert_eq!(file_names(&dir.join("migrations")).len(), 2);
+
+    fs::write(dir.join("schema").join("members.mig"), MEMBERS.replace("}\n}", "}\n    :soft-deletes\n}")).unwrap();
+    let output = mig(&dir, &["generate", "--dry-run"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ALTER TABLE \"members\" ADD COLUMN \"deleted_at\" TIMESTAMP NULL;"));
+    assert_eq!(fs::read_to_string(dir.join(".mig-state.json")).unwrap(), state);
+
+    assert!(mig(&dir, &["generate"]).status.success());
+    let names = file_names(&dir.join("migrations"));
+    assert_eq!(names.len(), 4);
+    assert!(names[2].ends_with("_alter_members_table.down.sql"));
+    assert!(names[3].ends_with("_alter_members_table.up.sql"));
+    let down = fs::read_to_string(dir.join("migrations").join(&names[2])).unwrap();
+    assert_eq!(down, "ALTER TABLE \"members\" DROP COLUMN \"deleted_at\";\n");
+    assert!(fs::read_to_string(dir.join(".mig-state.json")).unwrap().contains("\"soft_deletes\": true"));
+
+    // the sql diff is a migration with the timestamp too
+    fs::write(dir.join("mig.toml"), "target = [\"laravel\", \"sql-postgres\"]\nschema_dir = \"schema\"\nout_dir = \"out\"\n").unwrap();
+    for columns in ["}\n    :soft-deletes\n    :timestamps\n}", "}\n    :timestamps\n}"] {
+        fs::write(dir.join("schema").join("members.mig"), MEMBERS.replace("}\n}", columns)).unwrap();
+        let output = mig(&dir, &["generate"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert_eq!(file_names(&dir.join("out").join("laravel")).len(), 2);
+    let names = file_names(&dir.join("out").join("sql-postgres"));
+    assert_eq!(names.len(), 4);
+    assert!(names.iter().all(|name| name.ends_with("_diff.sql") || name.ends_with("_diff.down.sql")));
+    assert!(!fs::read_to_string(dir.join(".mig-state.json")).unwrap().contains("\"soft_deletes\": true"));
+
+    fs::write(dir.join(".mig-state.json"), "{\"version\": 99, \"tables\": []}").unwrap();
+    assert_eq!(mig(&dir, &["generate"]).status.code(), Some(3));
+}
+
+#[test]
+fn import_laravel() {
+    let dir = work_dir("import");
+    fs::create_dir_all(dir.join("migrations")).unwrap();
+    fs::write(dir.join("migrations").join("2020_01_01_000000_create_members_table.php"), "<?php
+class CreateMembersTable extends Migration {
+    public function up() {
+        Schema::create('members', function (Blueprint $table) {
+            $table->increments('id');
+            $table->string('name', 10);
+            $table->morphs('owner');
+        });
+    }
+}
+").unwrap();
+
+    let output = mig(&dir, &["import", "--from", "laravel", "migrations", "--out-dir", "schema"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("morphs of members is not imported"));
+    assert_eq!(fs::read_to_string(dir.join("schema").join("members.mig")).unwrap(), MEMBERS);
+    assert!(mig(&dir, &["check", "schema/members.mig"]).status.success());
+
+    // the mig-file is not overwritten
+    assert_eq!(mig(&dir, &["import", "--from", "laravel", "migrations", "--out-dir", "schema"]).status.code(), Some(3));
+    fs::create_dir_all(dir.join("empty")).unwrap();
+    assert_eq!(mig(&dir, &["import", "--from", "laravel", "empty"]).status.code(), Some(3));
+
+    fs::write(dir.join("schema.sql"), "CREATE TABLE members (id SERIAL PRIMARY KEY, name VARCHAR(10) NOT NULL);\n").unwrap();
+    let output = mig(&dir, &["import", "--from", "sql", "schema.sql", "--out-dir", "schema", "--force"]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(dir.join("schema").join("members.mig")).unwrap(), MEMBERS);
+}
+
+#[test]
+fn introspect_sqlite() {
+    let dir = work_dir("introspect");
+    let connection = rusqlite::Connection::open(dir.join("app.db")).unwrap();
+    connection.execute_batch("CREATE TABLE members (id INTEGER PRIMARY KEY AUTOINCREMENT, name VARCHAR(10) NOT NULL);").unwrap();
+    drop(connection);
+
+    let output = mig(&dir, &["introspect", "sqlite", "app.db", "--out-dir", "schema"]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(dir.join("schema").join("members.mig")).unwrap(), MEMBERS);
+
+    fs::write(dir.join("text.db"), "not a database").unwrap();
+    let output = mig(&dir, &["introspect", "sqlite", "text.db"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed read database text.db"));
+    assert_eq!(mig(&dir, &["introspect", "sqlite", "none.db"]).status.code(), Some(3));
+}
+
+#[test]
+fn verify_sqlite() {
+    let dir = work_dir("verify");
+    fs::create_dir_all(dir.join("schema")).unwrap();
+    fs::write(dir.join("schema").join("members.mig"), MEMBERS).unwrap();
+    assert!(mig(&dir, &["verify", "--sqlite", "-I", "schema"]).status.success());
+
+    // the keyword is quoted
+    fs::write(dir.join("schema").join("items.mig"), ":create items { order { :int } }").unwrap();
+    assert!(mig(&dir, &["verify", "--sqlite", "-I", "schema"]).status.success());
+
+    fs::write(dir.join("schema").join("items.mig"), ":create items { id { :increments :primary } }").unwrap();
+    let output = mig(&dir, &["verify", "--sqlite", "-I", "schema"]);
+    assert_eq!(output.status.code(), Some(6));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("generated sql cannot be applied to sqlite"));
+}
+
+#[test]
+fn language_server() {
+    let dir = work_dir("lsp");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mig"))
+        .current_dir(&dir)
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let messages = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#,
+        r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///members.mig","languageId":"mig","version":1,"text":":create members {\n    name { :string :hoge }\n}"}}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/formatting","params":{"textDocument":{"uri":"file:///members.mig"},"options":{"tabSize":4,"insertSpaces":true}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ];
+    let stdin = child.stdin.as_mut().unwrap();
+    for message in messages.iter() {
+        write!(stdin, "Content-Length: {}\r\n\r\n{}", message.len(), message).unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""capabilities":{"#));
+    assert!(stdout.contains(r#""method":"textDocument/publishDiagnostics""#));
+    assert!(stdout.contains(r#""code":"E0001""#));
+    assert!(stdout.contains(r#""range":{"end":{"character":19,"line":1},"start":{"character":19,"line":1}}"#));
+    assert!(stdout.contains(r#""newText":":create members {\n    name {\n        :string\n        :hoge\n    }\n}\n""#));
+}
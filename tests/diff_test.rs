@@ -0,0 +1,207 @@
+use mig::{analyze, diff_schemas, generate_diff, parse_str, sqlite_unsupported, Change, Dialect, Framework, Renames, Schema,
+          SchemaDiff, TableDiff};
+
+const OLD: &str = ":create members {
+    id { :increments }
+    name { :string 10 }
+    age { :int }
+    :timestamps
+    :index {
+        :target age
+    }
+}
+
+:create tags {
+    id { :increments }
+}
+";
+
+const NEW: &str = ":create members {
+    id { :increments }
+    fullname { :string 10 }
+    age { :int :nullable }
+    team { :int :references teams }
+    :soft-deletes
+}
+
+:create teams {
+    id { :increments }
+    title { :string }
+}
+";
+
+fn schema(src: &str) -> Schema {
+    analyze(&parse_str(src).unwrap()).unwrap()
+}
+
+fn diff_of(old: &str, new: &str, renames: &[&str]) -> SchemaDiff {
+    let mut hints = Renames::default();
+    for rename in renames.iter() {
+        hints.add(rename).unwrap();
+    }
+    diff_schemas(&schema(old), &schema(new), &hints)
+}
+
+fn changes(diff: &SchemaDiff, table: &str) -> Vec<Change> {
+    diff.tables.iter()
+        .find_map(|t| match t {
+            TableDiff::Alter(alter) if alter.name == table => Some(alter.changes.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn same_schema() {
+    let diff = diff_of(OLD, OLD, &[]);
+    assert!(diff.is_empty());
+    assert!(diff.hints.is_empty());
+}
+
+#[test]
+fn diff_tables() {
+    let diff = diff_of(OLD, NEW, &[]);
+    let methods: Vec<(&str, &str)> = diff.tables.iter().map(|t| (t.method(), t.table_name())).collect();
+    // referenced table is created first and dropped table is the last
+    assert_eq!(methods, vec!(("create", "teams"), ("alter", "members"), ("drop", "tags")));
+
+    let changes = changes(&diff, "members");
+    // name of the variant
+    let names: Vec<String> = changes.iter()
+        .map(|c| format!("{:?}", c).split([' ', '(']).next().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!(
+        "DropIndex", "DropColumn", "DropTimestamps", "AddColumn", "AddColumn", "ChangeColumn",
+        "AddSoftDeletes", "AddForeignKey",
+    ));
+    assert_eq!(diff.hints, vec!("column members.name is dropped and fullname is added. use --rename members.name=fullname if it is renamed"));
+}
+
+#[test]
+fn rename_column() {
+    let diff = diff_of(OLD, NEW, &["members.name=fullname"]);
+    let changes = changes(&diff, "members");
+    assert!(changes.contains(&Change::RenameColumn { from: "name".to_string(), to: "fullname".to_string() }));
+    assert!(!changes.iter().any(|c| matches!(c, Change::DropColumn(column) if column.name == "name")));
+    assert!(diff.hints.is_empty());
+
+    let mut renames = Renames::default();
+    assert!(renames.add("members.name").is_err());
+    assert!(renames.add("members.name=full-name").is_err());
+}
+
+#[test]
+fn rename_table() {
+    let old = ":create members {\n    id { :increments }\n    name { :string :unique }\n}\n";
+    let new = ":create users {\n    id { :increments }\n    name { :string :unique }\n}\n";
+    let diff = diff_of(old, new, &[]);
+    assert_eq!(diff.hints, vec!("table members is dropped and users is created. use --rename members=users if it is renamed"));
+
+    let diff = diff_of(old, new, &["members=users"]);
+    assert_eq!(changes(&diff, "users"), vec!(Change::RenameTable { from: "members".to_string() }));
+    let files = generate_diff(Framework::GolangMigrate(Dialect::Mysql), &diff);
+    assert_eq!(files[0].content, "ALTER TABLE `members` RENAME TO `users`;\n");
+    assert_eq!(files[0].down.as_deref(), Some("ALTER TABLE `users` RENAME TO `members`;\n"));
+}
+
+#[test]
+fn generate_laravel_diff() {
+    let files = generate_diff(Framework::Laravel, &diff_of(OLD, NEW, &["members.name=fullname"]));
+    let names: Vec<&str> = files.iter().map(|f| f.file_name.as_str()).collect();
+    assert_eq!(names, vec!("create_teams_table.php", "alter_members_table.php", "drop_tags_table.php"));
+
+    let content = &files[1].content;
+    // the migration of the same table can be made again
+    assert!(content.contains("\nreturn new class extends Migration\n{\n"));
+    assert!(content.ends_with("    }\n};\n"));
+    assert!(content.contains("Schema::table('members', function (Blueprint $table) {\n"));
+    assert!(content.contains("            $table->dropIndex(['age']);\n"));
+    assert!(content.contains("            $table->renameColumn('name', 'fullname');\n"));
+    assert!(content.contains("            $table->dropTimestamps();\n"));
+    assert!(content.contains("            $table->integer('team');\n"));
+    assert!(content.contains("            $table->integer('age')->nullable()->change();\n"));
+    assert!(content.contains("            $table->softDeletes();\n"));
+    assert!(content.contains("            $table->foreign('team')->references('id')->on('teams');\n"));
+    assert!(files[2].content.contains("Schema::dropIfExists('tags');"));
+
+    // down() has the reverse steps
+    let down = content.split("public function down()").nth(1).unwrap();
+    assert!(down.contains("            $table->dropForeign(['team']);\n"));
+    assert!(down.contains("            $table->integer('age')->change();\n"));
+    assert!(down.contains("            $table->dropColumn('team');\n"));
+    assert!(down.contains("            $table->timestamps();\n"));
+    assert!(down.contains("            $table->renameColumn('fullname', 'name');\n"));
+    assert!(down.contains("            $table->index(['age']);\n"));
+    let down = files[2].content.split("public function down()").nth(1).unwrap();
+    assert!(down.contains("Schema::create('tags', function (Blueprint $table) {\n"));
+}
+
+#[test]
+fn irreversible_change() {
+    let old = ":create members {\n    name { :string 10 }\n    age { :int }\n}\n";
+    let new = ":create members {\n    name { :string 5 }\n    age { :big-int }\n}\n";
+    let diff = diff_of(old, new, &[]);
+    assert_eq!(diff.tables[0].reverse().unwrap_err(), vec!(
+        "members.name is changed from :string 10 to :string 5 and the data may be lost",
+    ));
+
+    let content = &generate_diff(Framework::Laravel, &diff)[0].content;
+    assert!(content.contains("throw new \\RuntimeException('alter_members_table cannot be rolled back. members.name is changed"));
+
+    let files = generate_diff(Framework::GolangMigrate(Dialect::Postgres), &diff);
+    let down = files[0].down.as_ref().unwrap();
+    assert!(down.contains("-- members.name is changed from :string 10 to :string 5 and the data may be lost\n"));
+    assert!(down.contains("DO $$ BEGIN RAISE EXCEPTION 'members cannot be rolled back'; END $$;"));
+    let down = generate_diff(Framework::GolangMigrate(Dialect::Mysql), &diff)[0].down.clone().unwrap();
+    assert!(down.contains("SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'members cannot be rolled back';"));
+    let down = generate_diff(Framework::Sql(Dialect::Sqlite), &diff)[0].down.clone().unwrap();
+    assert!(down.contains("SELECT RAISE(ABORT, 'members cannot be rolled back');"));
+
+    // :big-int to :int is lossy too
+    assert!(diff_of(new, old, &[]).tables[0].reverse().is_err());
+    // widening is reversible
+    assert!(diff_of(":create members {\n    age { :int }\n}\n", ":create members {\n    age { :big-int }\n}\n", &[])
+        .tables[0].reverse().is_ok());
+}
+
+#[test]
+fn generate_sql_diff() {
+    let diff = diff_of(OLD, NEW, &["members.name=fullname"]);
+    let files = generate_diff(Framework::Sql(Dialect::Postgres), &diff);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_name, "diff.sql");
+    let content = &files[0].content;
+    assert!(content.contains("CREATE TABLE \"teams\" (\n"));
+    assert!(content.contains("DROP INDEX \"members_age_index\";\n"));
+    assert!(content.contains("ALTER TABLE \"members\" RENAME COLUMN \"name\" TO \"fullname\";\n"));
+    assert!(content.contains("ALTER TABLE \"members\" DROP COLUMN \"created_at\";\n"));
+    assert!(content.contains("ALTER TABLE \"members\" ADD COLUMN \"team\" INTEGER NOT NULL;\n"));
+    assert!(content.contains("ALTER TABLE \"members\" ALTER COLUMN \"age\" DROP NOT NULL;\n"));
+    assert!(content.contains("ALTER TABLE \"members\" ADD CONSTRAINT \"members_team_foreign\" FOREIGN KEY (\"team\") REFERENCES \"teams\" (\"id\");\n"));
+    assert!(content.ends_with("DROP TABLE \"tags\";\n"));
+    // diff.down.sql has the reverse steps in reverse order
+    let down = files[0].down.as_ref().unwrap();
+    assert!(down.starts_with("CREATE TABLE \"tags\" (\n"));
+    assert!(down.contains("ALTER TABLE \"members\" RENAME COLUMN \"fullname\" TO \"name\";\n"));
+    assert!(down.ends_with("DROP TABLE \"teams\";\n"));
+
+    let content = &generate_diff(Framework::Sql(Dialect::Mysql), &diff)[0].content;
+    assert!(content.contains("DROP INDEX `members_age_index` ON `members`;\n"));
+    assert!(content.contains("ALTER TABLE `members` MODIFY COLUMN `age` INT NULL;\n"));
+
+    let files = generate_diff(Framework::GolangMigrate(Dialect::Sqlite), &diff);
+    assert_eq!(files[1].file_name, "alter_members_table.up.sql");
+    assert!(files[1].content.starts_with("SELECT RAISE(ABORT, 'members must be recreated');\n"));
+    assert!(files[1].content.contains("-- sqlite cannot change the column age. recreate the table members\n"));
+    assert_eq!(sqlite_unsupported(&diff.tables[1]), vec!(
+        "sqlite cannot change the column age. recreate the table members",
+        "sqlite cannot add the foreign key of team. recreate the table members",
+    ));
+    // sqlite can add the column
+    let added = ":create members {\n    id { :increments }\n    name { :string 10 }\n    age { :int }\n    team { :int :nullable }\n}\n";
+    let diff = diff_of(OLD, added, &[]);
+    assert!(sqlite_unsupported(&diff.tables[0]).is_empty());
+    assert!(!generate_diff(Framework::Sql(Dialect::Sqlite), &diff)[0].content.contains("RAISE"));
+
+    assert!(generate_diff(Framework::Json, &diff).is_empty());
+}
@@ -0,0 +1,105 @@
+use mig::app::converter::parser::lexical_analyzer;
+use mig::app::formatter::format_file;
+use mig::parse_str;
+
+#[test]
+fn format_canonical_style() {
+    let s = ":create members{id{:increments}
+name   {
+:string 10 :default \"guest\"}
+   :timestamps :unique-index{:target name :name \"hoge\"}}
+:create posts {}";
+    let expected = ":create members {
+    id { :increments }
+    name {
+        :string 10
+        :default \"guest\"
+    }
+    :timestamps
+    :unique-index {
+        :target name
+        :name \"hoge\"
+    }
+}
+
+:create posts {}
+";
+    assert_eq!(format_file(&parse_str(s).unwrap()), expected);
+}
+
+#[test]
+fn format_long_column() {
+    let s = ":create members { name { :string 10 :comment \"very very very very very very very very long comment\" } }";
+    let expected = ":create members {
+    name {
+        :string 10
+        :comment \"very very very very very very very very long comment\"
+    }
+}
+";
+    assert_eq!(format_file(&parse_str(s).unwrap()), expected);
+}
+
+#[test]
+fn format_keeps_tokens() {
+    let s = ":create members {
+    a { :double :default 2.0 }
+    b { :decimal 8 2 :default -1.25 }
+    c { :time :default 23:59:60.5 }
+    d { :datetime :default 2019-07-12_21:57:55Z }
+    e { :datetime :default 2019-07-12_21:57:55-09:30 }
+    f { :date :default 2019-07-12 }
+}";
+    let formatted = format_file(&parse_str(s).unwrap());
+    assert_eq!(
+        lexical_analyzer(s).unwrap().get_tokens(),
+        lexical_analyzer(formatted.as_str()).unwrap().get_tokens()
+    );
+    // idempotent
+    assert_eq!(format_file(&parse_str(&formatted).unwrap()), formatted);
+}
+
+#[test]
+fn format_keeps_comments() {
+    let s = "# members of the site
+:create members{ # table
+id{:increments}  # primary key
+    name{:string 10
+# shown in the page
+:default \"guest\"}
+  # end of members
+}
+
+# posts
+:create posts { }
+# end of file
+";
+    let expected = "# members of the site
+:create members { # table
+    id { :increments } # primary key
+    name {
+        :string 10
+        # shown in the page
+        :default \"guest\"
+    }
+    # end of members
+}
+
+# posts
+:create posts {}
+# end of file
+";
+    let formatted = format_file(&parse_str(s).unwrap());
+    assert_eq!(formatted, expected);
+    assert_eq!(format_file(&parse_str(&formatted).unwrap()), formatted);
+
+    // comment in the column block is not inlined
+    let s = ":create members { id { :increments # primary key\n} }";
+    let expected = ":create members {
+    id {
+        :increments # primary key
+    }
+}
+";
+    assert_eq!(format_file(&parse_str(s).unwrap()), expected);
+}
@@ -0,0 +1,224 @@
+use chrono::{NaiveDate, NaiveTime};
+
+use mig::{analyze, generate, parse_str, ColumnType, Dialect, ExportedSchema, ForeignKey, Framework, IndexKind, to_framework_type, to_framework_types, Schema, Value, EXPORT_VERSION};
+
+const MEMBERS: &str = ":create members {
+    id { :increments }
+    name {
+        :string 10
+        :default \"guest: mig\"
+        :comment \"it's name\"
+    }
+    old { :tiny-int :unsigned :default 0 }
+    birthday { :date :nullable }
+    :timestamps
+    :unique-index {
+        :target name old
+        :name \"hoge\"
+    }
+}";
+
+#[test]
+fn analyze_schema() {
+    let schema = analyze(&parse_str(MEMBERS).unwrap()).unwrap();
+    assert_eq!(schema.tables.len(), 1);
+    let table = &schema.tables[0];
+    assert_eq!(table.name, "members");
+    let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!("id", "name", "old", "birthday"));
+    assert_eq!(table.columns[1].column_type, ColumnType::String { length: Some(10) });
+    assert_eq!(table.columns[1].default, Some(Value::String("guest: mig".to_string())));
+    assert!(table.columns[2].unsigned);
+    assert!(table.columns[3].nullable);
+    assert!(table.timestamps);
+    assert_eq!(table.indexes[0].kind, IndexKind::Unique);
+    assert_eq!(table.indexes[0].columns, vec!("name", "old"));
+    assert_eq!(table.indexes[0].name, Some("hoge".to_string()));
+}
+
+#[test]
+fn serialize_schema() {
+    let schema = analyze(&parse_str(MEMBERS).unwrap()).unwrap();
+    let json = serde_json::to_string(&schema).unwrap();
+    let deserialized: Schema = serde_json::from_str(&json).unwrap();
+    assert_eq!(schema, deserialized);
+}
+
+#[test]
+fn generate_laravel() {
+    let schema = analyze(&parse_str(MEMBERS).unwrap()).unwrap();
+    let files = generate(Framework::Laravel, &schema);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_name, "create_members_table.php");
+    let content = &files[0].content;
+    assert!(content.contains("class CreateMembersTable extends Migration"));
+    assert!(content.contains("Schema::create('members', function (Blueprint $table) {"));
+    assert!(content.contains("$table->increments('id');"));
+    assert!(content.contains("$table->string('name', 10)->default('guest: mig')->comment('it\\'s name');"));
+    assert!(content.contains("$table->tinyInteger('old')->unsigned()->default(0);"));
+    assert!(content.contains("$table->date('birthday')->nullable();"));
+    assert!(content.contains("$table->timestamps();"));
+    assert!(content.contains("$table->unique(['name', 'old'], 'hoge');"));
+    assert!(content.contains("Schema::dropIfExists('members');"));
+}
+
+#[test]
+fn invalid_schema() {
+    let s_1 = ":create members { name { :nullable } }";
+    let s_2 = ":create members { name { :string :int } }";
+    let s_3 = ":create members { name { :string :default 1 } }";
+    let s_4 = ":create members { name { :string } :index { :target age } }";
+    let s_5 = ":create members { name { :string :hoge } }";
+    let s_6 = ":create members { price { :decimal 2 8 } }";
+    let s_7 = ":create members { name { :string :unsigned } }";
+
+    for s in [s_1, s_2, s_3, s_4, s_5, s_6, s_7].iter() {
+        assert!(analyze(&parse_str(s).unwrap()).is_err(), "{}", s);
+    }
+    // the scale is over the precision
+    assert_eq!(analyze(&parse_str(s_6).unwrap()).unwrap_err().code(), "E0007");
+    assert_eq!(analyze(&parse_str(s_7).unwrap()).unwrap_err().code(), "E0013");
+}
+
+#[test]
+fn old_forms() {
+    // :ymd is :date and the value after the type is the default
+    let s = ":create members {\n    birthday { :ymd 2019-07-12 }\n    check { :time 21:57:55 :nullable }\n}";
+    let schema = analyze(&parse_str(s).unwrap()).unwrap();
+    let columns = &schema.tables[0].columns;
+    assert_eq!(columns[0].column_type, ColumnType::Date);
+    assert_eq!(columns[0].default, Some(Value::Date(NaiveDate::from_ymd_opt(2019, 7, 12).unwrap())));
+    assert_eq!(columns[1].default, Some(Value::Time(NaiveTime::from_hms_opt(21, 57, 55).unwrap())));
+    assert!(columns[1].nullable);
+
+    let s_1 = ":create members { birthday { :date 21:57:55 } }";
+    let s_2 = ":create members { birthday { :date 2019-07-12 2019-07-13 } }";
+    for s in [s_1, s_2].iter() {
+        assert!(analyze(&parse_str(s).unwrap()).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn export_schema() {
+    let schema = analyze(&parse_str(MEMBERS).unwrap()).unwrap();
+
+    let files = generate(Framework::Json, &schema);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_name, "schema.json");
+    let exported: ExportedSchema = serde_json::from_str(&files[0].content).unwrap();
+    assert_eq!(exported.version, EXPORT_VERSION);
+    assert_eq!(exported.tables, schema.tables);
+    let value: serde_json::Value = serde_json::from_str(&files[0].content).unwrap();
+    assert_eq!(value["tables"][0]["columns"][1]["type"]["name"], "string");
+    assert_eq!(value["tables"][0]["columns"][2]["unsigned"], true);
+    assert_eq!(value["tables"][0]["indexes"][0]["kind"], "unique");
+
+    let files = generate(Framework::Yaml, &schema);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_name, "schema.yaml");
+    let exported: ExportedSchema = serde_yaml::from_str(&files[0].content).unwrap();
+    assert_eq!(exported.tables, schema.tables);
+}
+
+#[test]
+fn sort_by_references() {
+    let s = ":create posts {
+    id { :increments }
+    author { :int :unsigned :references members }
+    category { :int :unsigned :references categories code }
+}
+:create members { id { :increments } }
+:create categories { code { :string 10 :primary } parent { :string 10 :references categories code } }
+:create tags { id { :increments } post { :int :unsigned :references posts } }";
+    let schema = analyze(&parse_str(s).unwrap()).unwrap();
+    let posts = schema.get_table("posts").unwrap();
+    assert_eq!(posts.columns[2].references, Some(ForeignKey { table: "categories".to_string(), column: "code".to_string() }));
+    assert_eq!(posts.referenced_tables(), vec!("members", "categories"));
+
+    let names: Vec<&str> = schema.sort_by_references().unwrap().iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!("members", "categories", "posts", "tags"));
+
+    let content = &generate(Framework::Laravel, &schema)[0].content;
+    assert!(content.contains("$table->foreign('category')->references('code')->on('categories');"));
+}
+
+#[test]
+fn cyclic_references() {
+    let s = ":create a { id { :increments } b { :int :references b } }
+:create b { id { :increments } a { :int :references a } }
+:create c { id { :increments } }";
+    let schema = analyze(&parse_str(s).unwrap()).unwrap();
+    assert_eq!(schema.sort_by_references().unwrap_err(), vec!("a", "b"));
+
+    let s_1 = ":create a { b { :int :references } }";
+    let s_2 = ":create a { b { :int :references \"b\" } }";
+    let s_3 = ":create a { b { :int :references b id c } }";
+    for s in [s_1, s_2, s_3].iter() {
+        assert!(analyze(&parse_str(s).unwrap()).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn default_out_dir() {
+    assert_eq!(to_framework_type("Laravel").unwrap().default_out_dir(), "database/migrations");
+    assert_eq!(to_framework_type("yml").unwrap().default_out_dir(), ".");
+}
+
+#[test]
+fn generate_sql() {
+    let schema = analyze(&parse_str(MEMBERS).unwrap()).unwrap();
+
+    let files = generate(Framework::Sql(Dialect::Postgres), &schema);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_name, "schema.sql");
+    let content = &files[0].content;
+    assert!(content.contains("CREATE TABLE \"members\" (\n    \"id\" SERIAL PRIMARY KEY,\n"));
+    assert!(content.contains("    \"name\" VARCHAR(10) NOT NULL DEFAULT 'guest: mig',\n"));
+    assert!(content.contains("    \"old\" SMALLINT NOT NULL DEFAULT 0,\n"));
+    assert!(content.contains("    \"birthday\" DATE NULL,\n"));
+    assert!(content.contains("    \"updated_at\" TIMESTAMP NULL\n);\n"));
+    assert!(content.contains("CREATE UNIQUE INDEX \"hoge\" ON \"members\" (\"name\", \"old\");\n"));
+    assert!(content.contains("COMMENT ON COLUMN \"members\".\"name\" IS 'it''s name';\n"));
+
+    let content = &generate(Framework::Sql(Dialect::Mysql), &schema)[0].content;
+    assert!(content.contains("    `id` INT UNSIGNED AUTO_INCREMENT PRIMARY KEY,\n"));
+    assert!(content.contains("    `name` VARCHAR(10) NOT NULL DEFAULT 'guest: mig' COMMENT 'it''s name',\n"));
+    assert!(content.contains("    `old` TINYINT UNSIGNED NOT NULL DEFAULT 0,\n"));
+
+    let files = generate(Framework::GolangMigrate(Dialect::Sqlite), &schema);
+    assert_eq!(files[0].file_name, "create_members_table.up.sql");
+    assert!(files[0].content.contains("    \"id\" INTEGER PRIMARY KEY AUTOINCREMENT,\n"));
+    assert!(files[0].content.contains("    \"old\" INTEGER NOT NULL DEFAULT 0,\n"));
+}
+
+#[test]
+fn quote_keyword_names() {
+    let s = ":create order {
+    id { :increments }
+    check { :boolean :index }
+    user { :int :references select }
+    :unique-index { :target check user }
+}
+:create select { id { :increments } }";
+    let schema = analyze(&parse_str(s).unwrap()).unwrap();
+
+    let content = &generate(Framework::Sql(Dialect::Postgres), &schema)[0].content;
+    assert!(content.contains("CREATE TABLE \"order\" (\n"));
+    assert!(content.contains("    \"check\" BOOLEAN NOT NULL,\n"));
+    assert!(content.contains("    CONSTRAINT \"order_user_foreign\" FOREIGN KEY (\"user\") REFERENCES \"select\" (\"id\")\n"));
+    assert!(content.contains("CREATE INDEX \"order_check_index\" ON \"order\" (\"check\");\n"));
+    assert!(content.contains("CREATE UNIQUE INDEX \"order_check_user_unique\" ON \"order\" (\"check\", \"user\");\n"));
+
+    let content = &generate(Framework::Sql(Dialect::Mysql), &schema)[0].content;
+    assert!(content.contains("CREATE TABLE `order` (\n"));
+    assert!(content.contains("    `check` BOOLEAN NOT NULL,\n"));
+    assert!(content.contains("CREATE INDEX `order_check_index` ON `order` (`check`);\n"));
+}
+
+#[test]
+fn many_targets() {
+    let frameworks = to_framework_types("laravel, sql-postgres,golang-migrate,laravel").unwrap();
+    let names: Vec<&str> = frameworks.iter().map(|fw| fw.name()).collect();
+    assert_eq!(names, vec!("laravel", "sql-postgres", "golang-migrate"));
+    assert_eq!(to_framework_types("laravel,rails").unwrap_err(), "rails");
+}
@@ -0,0 +1,343 @@
+use mig::app::import::laravel::import_migrations;
+use mig::app::import::sql::import_schema;
+use mig::app::import::sqlite::introspect;
+use mig::app::import::{retain_valid_names, to_mig_file, Imported};
+use mig::{analyze, generate, parse_str, ColumnType, Dialect, ForeignKey, Framework, IndexKind, Value};
+
+const CREATE_USERS: &str = "<?php
+
+use Illuminate\\Database\\Migrations\\Migration;
+use Illuminate\\Database\\Schema\\Blueprint;
+use Illuminate\\Support\\Facades\\Schema;
+
+class CreateUsersTable extends Migration
+{
+    public function up()
+    {
+        Schema::create('users', function (Blueprint $table) {
+            $table->id();
+            $table->string('name', 100)->comment(\"user's name\");
+            $table->string('email')->unique();
+            $table->enum('role', ['admin', 'member'])->default('member');
+            $table->unsignedInteger('age')->nullable()->default(20);
+            $table->timestamps();
+        });
+    }
+
+    public function down()
+    {
+        Schema::dropIfExists('users');
+    }
+}
+";
+
+const CREATE_POSTS: &str = "<?php
+return new class extends Migration {
+    public function up(): void
+    {
+        Schema::create('posts', function (Blueprint $table) {
+            $table->increments('id');
+            // comments are skipped
+            $table->foreignId('user_id')->constrained();
+            $table->string('title');
+            $table->date('published_on')->default('2020-01-01');
+            $table->index(['user_id', 'title'], 'posts_user_title');
+            $table->unique(['title'], 'unique_title');
+        });
+        Schema::create('tags', function (Blueprint $table) {
+            $table->increments('id');
+        });
+    }
+};
+";
+
+const ALTER_POSTS: &str = "<?php
+class AlterPostsTable extends Migration {
+    public function up() {
+        Schema::table('posts', function (Blueprint $table) {
+            $table->renameColumn('title', 'headline');
+            $table->dropIndex('posts_user_title');
+            $table->string('headline', 50)->change();
+            $table->integer('views')->default(100000);
+            $table->softDeletes();
+        });
+        Schema::dropIfExists('tags');
+        DB::statement('ALTER TABLE posts ENGINE = InnoDB');
+    }
+}
+";
+
+fn import(sources: &[&str]) -> Imported {
+    let sources: Vec<(String, String)> = sources.iter().enumerate()
+        .map(|(i, content)| (format!("{}.php", i), content.to_string()))
+        .collect();
+    let mut imported = import_migrations(&sources);
+    retain_valid_names(&mut imported);
+    imported
+}
+
+#[test]
+fn import_create() {
+    let imported = import(&[CREATE_USERS]);
+    let users = imported.schema.get_table("users").unwrap();
+    let names: Vec<&str> = users.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!("id", "name", "email", "role", "age"));
+    assert_eq!(users.columns[0].column_type, ColumnType::BigIncrements);
+    assert_eq!(users.columns[1].column_type, ColumnType::String { length: Some(100) });
+    assert_eq!(users.columns[1].comment.as_deref(), Some("user's name"));
+    assert!(users.columns[4].unsigned && users.columns[4].nullable);
+    assert!(users.timestamps);
+    assert_eq!(users.indexes[0].kind, IndexKind::Unique);
+    // down() is not read
+    assert_eq!(imported.warnings, vec!("0.php:15: enum of users.role is imported as :string"));
+}
+
+#[test]
+fn import_alter() {
+    let imported = import(&[CREATE_POSTS, ALTER_POSTS]);
+    assert_eq!(imported.schema.tables.len(), 1);
+    let posts = &imported.schema.tables[0];
+    assert_eq!(posts.get_column("user_id").unwrap().references,
+               Some(ForeignKey { table: "users".to_string(), column: "id".to_string() }));
+    assert_eq!(posts.get_column("headline").unwrap().column_type, ColumnType::String { length: Some(50) });
+    assert!(posts.get_column("title").is_none());
+    assert!(posts.soft_deletes);
+    // the index of the renamed column is kept and the dropped one is removed
+    assert_eq!(posts.indexes.len(), 1);
+    assert_eq!(posts.indexes[0].columns, vec!("headline"));
+    assert_eq!(posts.indexes[0].name.as_deref(), Some("unique_title"));
+    assert_eq!(imported.warnings, vec!("1.php:12: DB::statement is not imported"));
+}
+
+#[test]
+fn write_mig_file() {
+    let mut imported = import(&[CREATE_POSTS, ALTER_POSTS]);
+    let content = to_mig_file(&imported.schema.tables[0], &mut imported.warnings);
+    assert!(content.starts_with(":create posts {\n    id { :increments }\n"));
+    assert!(content.contains("        :references users id\n"));
+    assert!(content.contains("        :default 2020-01-01\n"));
+    assert!(content.contains("        :name \"unique_title\"\n"));
+    // 100000 is out of the integer of mig-file
+    assert!(imported.warnings.contains(&"default of posts.views cannot be written in mig-file".to_string()));
+
+    let schema = analyze(&parse_str(&content).unwrap()).unwrap();
+    let mut expected = imported.schema.tables[0].clone();
+    expected.columns[4].default = None;
+    assert_eq!(schema.tables[0], expected);
+}
+
+#[test]
+fn unsupported_constructs() {
+    let src = "<?php
+class Other extends Migration {
+    public function up() {
+        Schema::create('items', function (Blueprint $table) use ($name) {
+            $table->increments('id');
+            $table->morphs('owner');
+            $table->string('item-code');
+            $table->index(['item-code']);
+            $table->engine = 'InnoDB';
+        });
+        Schema::connection('other')->create('logs', function (Blueprint $table) {
+            $table->increments('id');
+        });
+    }
+}
+";
+    let imported = import(&[src, "<?php echo 1;"]);
+    assert_eq!(imported.schema.tables.len(), 1);
+    assert_eq!(imported.schema.tables[0].columns.len(), 1);
+    assert!(imported.schema.tables[0].indexes.is_empty());
+    assert_eq!(imported.warnings, vec!(
+        "0.php:6: morphs of items is not imported",
+        "0.php:9: statement in the blueprint of items is not imported",
+        "0.php:11: Schema::connection is not imported",
+        "1.php:1: up() is not found",
+        "column items.item-code is not imported. mig name has only ascii alphanumerics and _",
+        "index of items(item-code) is not imported",
+    ));
+}
+
+const PG_DUMP: &str = "--
+-- PostgreSQL database dump
+--
+SET statement_timeout = 0;
+
+CREATE TABLE public.users (
+    id bigint NOT NULL,
+    name character varying(100) NOT NULL,
+    kind character varying(255) DEFAULT 'member'::character varying NOT NULL,
+    score numeric(8,2) DEFAULT 1.5,
+    created_at timestamp(0) without time zone,
+    updated_at timestamp(0) without time zone
+);
+
+CREATE SEQUENCE public.users_id_seq START WITH 1;
+ALTER TABLE ONLY public.users ALTER COLUMN id SET DEFAULT nextval('public.users_id_seq'::regclass);
+ALTER TABLE ONLY public.users
+    ADD CONSTRAINT users_pkey PRIMARY KEY (id),
+    ADD CONSTRAINT users_name_unique UNIQUE (name);
+CREATE INDEX users_lower_name ON public.users USING btree (lower((name)::text));
+COMMENT ON COLUMN public.users.name IS 'user''s name';
+CREATE FUNCTION public.touch() RETURNS trigger AS $$ BEGIN; RETURN NEW; END; $$ LANGUAGE plpgsql;
+";
+
+const MYSQL_DUMP: &str = "/*!40101 SET NAMES utf8mb4 */;
+DROP TABLE IF EXISTS `posts`;
+CREATE TABLE `posts` (
+  `id` int unsigned NOT NULL AUTO_INCREMENT,
+  `user_id` bigint unsigned NOT NULL,
+  `published` tinyint(1) NOT NULL DEFAULT '0',
+  `status` enum('draft','open') NOT NULL DEFAULT 'draft',
+  `touched` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `posts_user_status` (`user_id`,`status`),
+  CONSTRAINT `posts_user_id_foreign` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+INSERT INTO `posts` VALUES (1,1,0,'a;b',NULL);
+";
+
+fn import_sql(sources: &[&str]) -> Imported {
+    let sources: Vec<(String, String)> = sources.iter().enumerate()
+        .map(|(i, content)| (format!("{}.sql", i), content.to_string()))
+        .collect();
+    let mut imported = import_schema(&sources);
+    retain_valid_names(&mut imported);
+    imported
+}
+
+#[test]
+fn import_postgres_dump() {
+    let imported = import_sql(&[PG_DUMP]);
+    let users = imported.schema.get_table("users").unwrap();
+    let names: Vec<&str> = users.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!("id", "name", "kind", "score"));
+    // the serial column and its primary key
+    assert_eq!(users.columns[0].column_type, ColumnType::BigIncrements);
+    assert_eq!(users.columns[1].column_type, ColumnType::String { length: Some(100) });
+    assert_eq!(users.columns[1].comment.as_deref(), Some("user's name"));
+    assert_eq!(users.columns[2].column_type, ColumnType::String { length: None });
+    assert_eq!(users.columns[2].default, Some(Value::String("member".to_string())));
+    assert_eq!(users.columns[3].column_type, ColumnType::Decimal { precision: 8, scale: 2 });
+    assert!(users.columns[3].nullable && !users.columns[1].nullable);
+    assert!(users.timestamps);
+    // the name of the unique index is the default
+    assert_eq!(users.indexes.len(), 1);
+    assert_eq!(users.indexes[0].name, None);
+    assert_eq!(imported.warnings, vec!(
+        "0.sql:20: index of users with the expressions is not imported",
+        "0.sql:22: CREATE FUNCTION is not imported",
+    ));
+}
+
+#[test]
+fn import_mysql_dump() {
+    let imported = import_sql(&[MYSQL_DUMP]);
+    let posts = &imported.schema.tables[0];
+    assert_eq!(posts.columns[0].column_type, ColumnType::Increments);
+    assert!(posts.columns[1].unsigned);
+    assert_eq!(posts.columns[1].references,
+               Some(ForeignKey { table: "users".to_string(), column: "id".to_string() }));
+    assert_eq!(posts.columns[2].column_type, ColumnType::Boolean);
+    assert_eq!(posts.columns[2].default, Some(Value::Integer(0)));
+    assert_eq!(posts.columns[3].column_type, ColumnType::String { length: None });
+    assert_eq!(posts.columns[4].default, None);
+    assert_eq!(posts.indexes.len(), 1);
+    assert_eq!(posts.indexes[0].columns, vec!("user_id", "status"));
+    assert_eq!(posts.indexes[0].name.as_deref(), Some("posts_user_status"));
+    assert_eq!(imported.warnings, vec!(
+        "0.sql:7: enum('draft','open') of posts.status is imported as :string",
+        "0.sql:8: default of posts.touched is not imported",
+        "0.sql:11: ON DELETE of the foreign key to users is not imported",
+    ));
+}
+
+#[test]
+fn import_generated_sql() {
+    let src = ":create teams { id { :big-increments } name { :string :unique } }
+:create members {
+    id { :increments }
+    name { :string 10 :default \"guest: mig\" :comment \"it's name\" }
+    old { :tiny-int :unsigned :default 0 }
+    score { :decimal 5 1 :nullable }
+    team_id { :big-int :unsigned :references teams id :index }
+    :timestamps
+    :soft-deletes
+    :unique-index { :target name old :name \"hoge\" }
+}";
+    let schema = analyze(&parse_str(src).unwrap()).unwrap();
+    let sql = &generate(Framework::Sql(Dialect::Mysql), &schema)[0].content;
+    let imported = import_sql(&[sql]);
+    assert_eq!(imported.schema, schema);
+    assert!(imported.warnings.is_empty());
+
+    // every integer is INTEGER in sqlite
+    let sql = &generate(Framework::Sql(Dialect::Sqlite), &schema)[0].content;
+    let imported = import_sql(&[sql]);
+    let members = imported.schema.get_table("members").unwrap();
+    assert_eq!(members.columns[2].column_type, ColumnType::Integer);
+    assert_eq!(imported.schema.tables[0].columns[0].column_type, ColumnType::Increments);
+    assert!(members.timestamps && members.soft_deletes);
+}
+
+#[test]
+fn introspect_sqlite() {
+    let connection = rusqlite::Connection::open_in_memory().unwrap();
+    connection.execute_batch("
+        CREATE TABLE teams (id INTEGER PRIMARY KEY AUTOINCREMENT, name VARCHAR(100) NOT NULL UNIQUE);
+        CREATE TABLE members (
+            id integer primary key autoincrement not null,
+            team_id integer not null references teams(id) on delete cascade,
+            nickname varchar default 'guest',
+            flag tinyint(1) not null default '0',
+            memo,
+            created_at timestamp null,
+            updated_at timestamp null
+        );
+        CREATE INDEX members_team_flag on members (team_id, flag);
+        CREATE INDEX members_lower on members (lower(nickname));
+        CREATE TABLE pairs (a varchar(10) not null, b integer, primary key (a, b));
+        CREATE TABLE rowids (id integer primary key, v text default 'AUTOINCREMENT');
+        CREATE TABLE logs (id integer, message text, primary key (id autoincrement));
+    ").unwrap();
+    let mut imported = introspect(&connection).unwrap();
+    retain_valid_names(&mut imported);
+    let names: Vec<&str> = imported.schema.tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!("teams", "members", "pairs", "rowids", "logs"));
+
+    let teams = &imported.schema.tables[0];
+    assert_eq!(teams.columns[0].column_type, ColumnType::Increments);
+    assert_eq!(teams.columns[1].column_type, ColumnType::String { length: Some(100) });
+    // the index of UNIQUE has no name
+    assert_eq!(teams.indexes[0].kind, IndexKind::Unique);
+    assert_eq!(teams.indexes[0].name, None);
+
+    let members = &imported.schema.tables[1];
+    let names: Vec<&str> = members.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!("id", "team_id", "nickname", "flag"));
+    assert_eq!(members.columns[1].references,
+               Some(ForeignKey { table: "teams".to_string(), column: "id".to_string() }));
+    assert!(members.columns[2].nullable && !members.columns[3].nullable);
+    assert_eq!(members.columns[2].default, Some(Value::String("guest".to_string())));
+    assert_eq!(members.columns[3].column_type, ColumnType::Boolean);
+    assert!(members.timestamps);
+    assert_eq!(members.indexes.len(), 1);
+    assert_eq!(members.indexes[0].name.as_deref(), Some("members_team_flag"));
+
+    let pairs = &imported.schema.tables[2];
+    assert_eq!(pairs.indexes[0].kind, IndexKind::Primary);
+    assert_eq!(pairs.indexes[0].columns, vec!("a", "b"));
+    assert!(!pairs.columns[0].nullable && pairs.columns[1].nullable);
+    // INTEGER PRIMARY KEY without AUTOINCREMENT is not the increments
+    let rowids = &imported.schema.tables[3];
+    assert_eq!(rowids.columns[0].column_type, ColumnType::Integer);
+    assert_eq!(rowids.indexes[0].kind, IndexKind::Primary);
+    let logs = &imported.schema.tables[4];
+    assert_eq!(logs.columns[0].column_type, ColumnType::Increments);
+    assert!(logs.indexes.is_empty());
+    assert_eq!(imported.warnings, vec!(
+        "column members.memo without the type is not imported",
+        "index members_lower of members with the expressions is not imported",
+        "ON DELETE of the foreign key to teams is not imported",
+    ));
+}
@@ -1,10 +1,9 @@
 extern crate mig;
 
-use chrono::Date;
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
 
-use mig::app;
 use mig::app::converter::parser::{lexical_analyzer, ParserError};
-use mig::app::converter::token::Token;
+use mig::app::converter::token::{Comment, Token};
 
 #[test]
 fn name_colon_parser() {
@@ -12,13 +11,13 @@ fn name_colon_parser() {
     let s_2 = ":create";
     let s_3 = "create";
 
-    let p_1 = lexical_analyzer(s_1.to_string());
+    let p_1 = lexical_analyzer(s_1);
     assert_eq!(p_1.unwrap().get_token(0), Token::NameColon("hoge".to_string()));
 
-    let p_2 = lexical_analyzer(s_2.to_string());
+    let p_2 = lexical_analyzer(s_2);
     assert_eq!(p_2.unwrap().get_token(0), Token::NameColon("create".to_string()));
 
-    let p_3 = lexical_analyzer(s_3.to_string());
+    let p_3 = lexical_analyzer(s_3);
     assert_ne!(p_3.unwrap().get_token(0), Token::NameColon("create".to_string()));
 }
 
@@ -27,11 +26,15 @@ fn user_string_parser() {
     let s_1 = "member";
     let s_2 = "\"member\"";
 
-    let p_1 = lexical_analyzer(s_1.to_string());
+    let p_1 = lexical_analyzer(s_1);
     assert_eq!(p_1.unwrap().get_token(0), Token::Name("member".to_string()));
 
-    let p_2 = lexical_analyzer(s_2.to_string());
+    let p_2 = lexical_analyzer(s_2);
     assert_ne!(p_2.unwrap().get_token(0), Token::Name("member".to_string()));
+
+    // digits and _ after the first alphabet
+    let p_3 = lexical_analyzer("user_id2 { :int }");
+    assert_eq!(p_3.unwrap().get_token(0), Token::Name("user_id2".to_string()));
 }
 
 #[test]
@@ -48,7 +51,7 @@ fn white_spaces() {
 
     let set = [s_1, s_2, s_3, s_4, s_5, s_6, s_7, s_8, s_9];
     for s in set.iter() {
-        let p = lexical_analyzer((*s).to_string()).unwrap();
+        let p = lexical_analyzer(*s).unwrap();
         let tokens = p.get_tokens();
         let l = p.get_tokens().len();
         assert_eq!(l, 2);
@@ -62,10 +65,10 @@ fn table_option_parser() {
     let s_1 = ":timestamps";
     let s_2 = ":unique-index {\n:target group member} ";
 
-    let p_1 = lexical_analyzer(s_1.to_string());
+    let p_1 = lexical_analyzer(s_1);
     assert_eq!(p_1.unwrap().get_token(0), Token::NameColon("timestamps".to_string()));
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
+    let p_2 = lexical_analyzer(s_2).unwrap();
     assert_eq!(p_2.get_tokens().len(), 6);
     assert_eq!(p_2.get_token(0), Token::NameColon("unique-index".to_string()));
     assert_eq!(p_2.get_token(1), Token::LMidParen);
@@ -81,13 +84,13 @@ fn column_option_parser() {
     let s_2 = "uniqueindex {\n:target group member \n :hoge \"fuga\"} ";
     let s_3 = "unique-index {\n:target group member} ";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
+    let p_1 = lexical_analyzer(s_1).unwrap();
     assert_eq!(p_1.get_tokens().len(), 3);
     assert_eq!(p_1.get_token(0), Token::Name("timestamps".to_string()));
     assert_eq!(p_1.get_token(1), Token::LMidParen);
     assert_eq!(p_1.get_token(2), Token::RMidParen);
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
+    let p_2 = lexical_analyzer(s_2).unwrap();
     assert_eq!(p_2.get_tokens().len(), 8);
     assert_eq!(p_2.get_token(0), Token::Name("uniqueindex".to_string()));
     assert_eq!(p_2.get_token(1), Token::LMidParen);
@@ -98,7 +101,7 @@ fn column_option_parser() {
     assert_eq!(p_2.get_token(6), Token::String("fuga".to_string()));
     assert_eq!(p_2.get_token(7), Token::RMidParen);
 
-    let p_3 = lexical_analyzer(s_3.to_string());
+    let p_3 = lexical_analyzer(s_3);
     match p_3 {
         Ok(_) => assert_eq!(1, 2), // not pass this test
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 7))
@@ -113,22 +116,22 @@ fn integer_parser() {
     let s_4 = "12a";
     let s_5 = "12 a";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
+    let p_1 = lexical_analyzer(s_1).unwrap();
     assert_eq!(p_1.get_token(0), Token::Integer(4323));
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
+    let p_2 = lexical_analyzer(s_2).unwrap();
     assert_eq!(p_2.get_token(0), Token::Integer(-4323));
 
-    let p_3 = lexical_analyzer(s_3.to_string()).unwrap();
+    let p_3 = lexical_analyzer(s_3).unwrap();
     assert_eq!(p_3.get_token(0), Token::Integer(1));
 
-    let p_4 = lexical_analyzer(s_4.to_string());
+    let p_4 = lexical_analyzer(s_4);
     match p_4 {
         Ok(_) => assert_eq!(1, 2), // not pass this test
         Err(e) => assert_eq!(e, ParserError::NotANumber(1, 2)),
     }
 
-    let p_5 = lexical_analyzer(s_5.to_string()).unwrap();
+    let p_5 = lexical_analyzer(s_5).unwrap();
     let v_5 = p_5.get_tokens();
     assert_eq!(v_5.len(), 2);
     assert_eq!(p_5.get_token(0), Token::Integer(12));
@@ -144,30 +147,30 @@ fn double_parser() {
     let s_6 = "12.9 a";
     let s_7 = "12.";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
+    let p_1 = lexical_analyzer(s_1).unwrap();
     assert_eq!(p_1.get_token(0), Token::Double(0.01));
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
+    let p_2 = lexical_analyzer(s_2).unwrap();
     assert_eq!(p_2.get_token(0), Token::Double(432.3));
 
-    let p_3 = lexical_analyzer(s_3.to_string()).unwrap();
+    let p_3 = lexical_analyzer(s_3).unwrap();
     assert_eq!(p_3.get_token(0), Token::Double(-432.3));
 
-    let p_4 = lexical_analyzer(s_4.to_string()).unwrap();
+    let p_4 = lexical_analyzer(s_4).unwrap();
     assert_eq!(p_4.get_token(0), Token::Double(1.0));
 
-    let p_5 = lexical_analyzer(s_5.to_string());
+    let p_5 = lexical_analyzer(s_5);
     match p_5 {
         Ok(_) => assert_ne!(1, 2), // not pass this test
         Err(e) => assert_eq!(e, ParserError::NotANumber(1, 4)),
     }
 
-    let p_6 = lexical_analyzer(s_6.to_string()).unwrap();
+    let p_6 = lexical_analyzer(s_6).unwrap();
     let v_6 = p_6.get_tokens();
     assert_eq!(v_6.len(), 2);
     assert_eq!(p_6.get_token(0), Token::Double(12.9));
 
-    let p_7 = lexical_analyzer(s_7.to_string());
+    let p_7 = lexical_analyzer(s_7);
     match p_7 {
         Ok(_) => assert_ne!(1, 2), // not pass this test
         Err(e) => assert_eq!(e, ParserError::NotANumber(1, 3)),
@@ -184,37 +187,40 @@ fn ymd_parser() {
     let s_6 = "1996-13-12";
     let s_7 = "1996-07-33";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
-    assert_eq!(p_1.get_token(0), Token::Ymd(1996, 7, 12));
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::Ymd(NaiveDate::from_ymd_opt(1996, 7, 12).unwrap()));
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
-    assert_eq!(p_2.get_token(0), Token::Ymd(1, 12, 14));
+    let p_2 = lexical_analyzer(s_2);
+    match p_2 {
+        Ok(_) => assert_eq!(1, 2), // not pass test
+        Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 10))
+    }
 
-    let p_3 = lexical_analyzer(s_3.to_string());
+    let p_3 = lexical_analyzer(s_3);
     match p_3 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NotANumber(1, 2))
     }
 
-    let p_4 = lexical_analyzer(s_4.to_string());
+    let p_4 = lexical_analyzer(s_4);
     match p_4 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 11))
     }
 
-    let p_5 = lexical_analyzer(s_5.to_string());
+    let p_5 = lexical_analyzer(s_5);
     match p_5 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 11))
     }
 
-    let p_6 = lexical_analyzer(s_6.to_string());
+    let p_6 = lexical_analyzer(s_6);
     match p_6 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 10))
     }
 
-    let p_7 = lexical_analyzer(s_7.to_string());
+    let p_7 = lexical_analyzer(s_7);
     match p_7 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 10))
@@ -233,46 +239,46 @@ fn time_parser() {
     let s_8 = "1111:11:11";
     let s_9 = "00:0s:00";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
-    assert_eq!(p_1.get_token(0), Token::Time(12, 12, 12));
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::Time(NaiveTime::from_hms_opt(12, 12, 12).unwrap()));
 
-    let p_2 = lexical_analyzer(s_2.to_string()).unwrap();
-    assert_eq!(p_2.get_token(0), Token::Time(22, 22, 22));
+    let p_2 = lexical_analyzer(s_2).unwrap();
+    assert_eq!(p_2.get_token(0), Token::Time(NaiveTime::from_hms_opt(22, 22, 22).unwrap()));
 
-    let p_3 = lexical_analyzer(s_3.to_string()).unwrap();
-    assert_eq!(p_3.get_token(0), Token::Time(0, 0, 0));
+    let p_3 = lexical_analyzer(s_3).unwrap();
+    assert_eq!(p_3.get_token(0), Token::Time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()));
 
-    let p_4 = lexical_analyzer(s_4.to_string());
+    let p_4 = lexical_analyzer(s_4);
     match p_4 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 8))
     }
 
-    let p_5 = lexical_analyzer(s_5.to_string());
+    let p_5 = lexical_analyzer(s_5);
     match p_5 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 8))
     }
 
-    let p_6 = lexical_analyzer(s_6.to_string());
+    let p_6 = lexical_analyzer(s_6);
     match p_6 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 8))
     }
 
-    let p_7 = lexical_analyzer(s_7.to_string());
+    let p_7 = lexical_analyzer(s_7);
     match p_7 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 8))
     }
 
-    let p_8 = lexical_analyzer(s_8.to_string());
+    let p_8 = lexical_analyzer(s_8);
     match p_8 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::NotANumber(1, 4))
     }
 
-    let p_9 = lexical_analyzer(s_9.to_string());
+    let p_9 = lexical_analyzer(s_9);
     match p_9 {
         Ok(_) => assert_eq!(1, 2), // not pass test
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 4))
@@ -287,30 +293,133 @@ fn datetime_parser() {
     let s_4 = "1996-07-12_";
     let s_5 = "1996-07-12_1";
 
-    let p_1 = lexical_analyzer(s_1.to_string()).unwrap();
-    assert_eq!(p_1.get_token(0), Token::DateTime(1996, 7, 12, 23, 59, 59));
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::DateTime(NaiveDate::from_ymd_opt(1996, 7, 12).unwrap().and_hms_opt(23, 59, 59).unwrap(), None));
 
-    let p_2 = lexical_analyzer(s_2.to_string());
+    let p_2 = lexical_analyzer(s_2);
     match p_2 {
         Ok(_) => assert_eq!(1, 2),
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 11))
     }
 
-    let p_3 = lexical_analyzer(s_3.to_string());
+    let p_3 = lexical_analyzer(s_3);
     match p_3 {
         Ok(_) => assert_eq!(1, 2),
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 20))
     }
 
-    let p_4 = lexical_analyzer(s_4.to_string());
+    let p_4 = lexical_analyzer(s_4);
     match p_4 {
         Ok(_) => assert_eq!(1, 2),
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 11))
     }
 
-    let p_5 = lexical_analyzer(s_5.to_string());
+    let p_5 = lexical_analyzer(s_5);
     match p_5 {
         Ok(_) => assert_eq!(1, 2),
         Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 12))
     }
 }
+
+#[test]
+fn calendar_parser() {
+    let s_1 = "2020-02-29";
+    let s_2 = "2019-02-29";
+    let s_3 = "2019-04-31";
+    let s_4 = "2019-00-10";
+    let s_5 = "2019-01-00";
+
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::Ymd(NaiveDate::from_ymd_opt(2020, 2, 29).unwrap()));
+
+    for s in [s_2, s_3, s_4, s_5].iter() {
+        match lexical_analyzer(s) {
+            Ok(_) => assert_eq!(1, 2), // not pass test
+            Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 10))
+        }
+    }
+}
+
+#[test]
+fn leap_second_and_fraction_parser() {
+    let s_1 = "23:59:60";
+    let s_2 = "12:12:12.5";
+    let s_3 = "12:12:12.123456789";
+    let s_4 = "12:12:12.";
+    let s_5 = "12:12:12.1234567890";
+    let s_6 = "23:58:60";
+
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::Time(NaiveTime::from_hms_milli_opt(23, 59, 59, 1_000).unwrap()));
+
+    let p_2 = lexical_analyzer(s_2).unwrap();
+    assert_eq!(p_2.get_token(0), Token::Time(NaiveTime::from_hms_milli_opt(12, 12, 12, 500).unwrap()));
+
+    let p_3 = lexical_analyzer(s_3).unwrap();
+    assert_eq!(p_3.get_token(0), Token::Time(NaiveTime::from_hms_nano_opt(12, 12, 12, 123_456_789).unwrap()));
+
+    let p_4 = lexical_analyzer(s_4);
+    match p_4 {
+        Ok(_) => assert_eq!(1, 2), // not pass test
+        Err(e) => assert_eq!(e, ParserError::NotANumber(1, 9))
+    }
+
+    let p_5 = lexical_analyzer(s_5);
+    match p_5 {
+        Ok(_) => assert_eq!(1, 2), // not pass test
+        Err(e) => assert_eq!(e, ParserError::NotANumber(1, 19))
+    }
+
+    let p_6 = lexical_analyzer(s_6);
+    match p_6 {
+        Ok(_) => assert_eq!(1, 2), // not pass test
+        Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 8))
+    }
+}
+
+#[test]
+fn datetime_with_offset_parser() {
+    let s_1 = "2019-05-01_21:21:21Z";
+    let s_2 = "2019-05-01_21:21:21+09:00";
+    let s_3 = "2019-05-01_21:21:21.25-05:30";
+    let s_4 = "2019-05-01_21:21:21+0900";
+    let s_5 = "2019-05-01_21:21:21+24:00";
+
+    let dt = NaiveDate::from_ymd_opt(2019, 5, 1).unwrap().and_hms_opt(21, 21, 21).unwrap();
+
+    let p_1 = lexical_analyzer(s_1).unwrap();
+    assert_eq!(p_1.get_token(0), Token::DateTime(dt, FixedOffset::east_opt(0)));
+
+    let p_2 = lexical_analyzer(s_2).unwrap();
+    assert_eq!(p_2.get_token(0), Token::DateTime(dt, FixedOffset::east_opt(9 * 3600)));
+    assert_eq!(p_2.get_token(0).to_date_time_with_offset().unwrap().to_rfc3339(), "2019-05-01T21:21:21+09:00");
+
+    let p_3 = lexical_analyzer(s_3).unwrap();
+    let dt_3 = NaiveDate::from_ymd_opt(2019, 5, 1).unwrap().and_hms_milli_opt(21, 21, 21, 250).unwrap();
+    assert_eq!(p_3.get_token(0), Token::DateTime(dt_3, FixedOffset::west_opt(5 * 3600 + 30 * 60)));
+
+    let p_4 = lexical_analyzer(s_4);
+    match p_4 {
+        Ok(_) => assert_eq!(1, 2),
+        Err(e) => assert_eq!(e, ParserError::UnknownToken(1, 24))
+    }
+
+    let p_5 = lexical_analyzer(s_5);
+    match p_5 {
+        Ok(_) => assert_eq!(1, 2),
+        Err(e) => assert_eq!(e, ParserError::NumberRangeError(1, 25))
+    }
+}
+
+#[test]
+fn comment_parser() {
+    let s = "# head\n:create members { # trailing \n    name { :string \"# not comment\" }\n}";
+    let seq = lexical_analyzer(s).unwrap();
+    assert_eq!(seq.get_tokens().len(), 9);
+    assert_eq!(seq.get_token(6), Token::String("# not comment".to_string()));
+    let comments = seq.get_comments();
+    assert_eq!(comments.len(), 2);
+    assert_eq!(comments[0], Comment { text: " head".to_string(), position: comments[0].position, trailing: false });
+    assert_eq!(comments[1].text, " trailing");
+    assert!(comments[1].trailing);
+}
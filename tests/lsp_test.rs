@@ -0,0 +1,90 @@
+use mig::app::lsp::analysis::{complete, definition, diagnostics, format, hover};
+
+const MEMBERS: &str = ":create members {
+    id { :increments }
+    name { :string 10 }
+    :unique-index {
+        :target name
+    }
+}
+
+:create posts {
+    author { :int :references members id }
+}
+";
+
+fn labels(text: &str, position: (u32, u32)) -> Vec<String> {
+    complete(text, position).into_iter().map(|c| c.label).collect()
+}
+
+#[test]
+fn lsp_diagnostics() {
+    assert!(diagnostics(MEMBERS).is_empty());
+    let errors = diagnostics(":create members {\n    name { :string :hoge }\n}");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, "E0001");
+    assert_eq!((errors[0].line, errors[0].column), (Some(2), Some(20)));
+}
+
+#[test]
+fn lsp_completion() {
+    // column options after `:`
+    let text = ":create members {\n    name { :str }\n}";
+    let names = labels(text, (2, 16));
+    assert!(names.contains(&"string".to_string()));
+    assert!(names.contains(&"nullable".to_string()));
+    assert!(!names.contains(&"timestamps".to_string()));
+
+    // table options
+    let text = ":create members {\n    name { :string }\n    :\n}";
+    let names = labels(text, (3, 6));
+    assert!(names.contains(&"unique-index".to_string()));
+    assert!(!names.contains(&"string".to_string()));
+
+    // index options and the columns of the table in :target
+    let text = ":create members {\n    id { :increments }\n    name { :string }\n    :index { : }\n}";
+    assert_eq!(labels(text, (4, 15)), vec!("target", "name"));
+    let text = ":create members {\n    id { :increments }\n    name { :string }\n    :index { :target n }\n}";
+    assert_eq!(labels(text, (4, 23)), vec!("id", "name"));
+
+    // tables in :references
+    assert_eq!(labels(MEMBERS, (10, 34)), vec!("members", "posts"));
+}
+
+#[test]
+fn lsp_non_ascii() {
+    // the column is in utf-16 code units. "日本" is 6 bytes and "🐘" is 2 units
+    let text = ":create members {\n    name { :comment \"日本🐘\" :string }\n}";
+    assert!(hover(text, (2, 30)).unwrap().contains(":string [<length>]"));
+    // the middle of the surrogate pair and over the line
+    assert!(hover(text, (2, 25)).is_none());
+    assert!(hover(text, (2, 99)).is_none());
+    for col in 1..40 {
+        hover(text, (2, col));
+        complete(text, (2, col));
+    }
+}
+
+#[test]
+fn lsp_hover() {
+    let doc = hover(MEMBERS, (3, 14)).unwrap();
+    assert!(doc.contains(":string [<length>]"));
+    assert!(doc.contains("variable length string"));
+    assert!(hover(MEMBERS, (3, 5)).is_none());
+}
+
+#[test]
+fn lsp_definition() {
+    // :target name -> column name
+    assert_eq!(definition(MEMBERS, (5, 18)), Some(((3, 5), 4)));
+    // :references members id -> table and column
+    assert_eq!(definition(MEMBERS, (10, 32)), Some(((1, 1), 7)));
+    assert_eq!(definition(MEMBERS, (10, 40)), Some(((2, 5), 2)));
+    assert_eq!(definition(MEMBERS, (3, 5)), None);
+}
+
+#[test]
+fn lsp_format() {
+    assert_eq!(format(":create members{id{:increments}}").unwrap(), ":create members {\n    id { :increments }\n}\n");
+    assert!(format(":create members {").is_none());
+}
@@ -1,14 +1,16 @@
+use chrono::NaiveDate;
+
 use mig::app::converter::parser::lexical_analyzer;
 use mig::app::converter::token::Token::*;
 
 #[test]
 fn success_1() {
     let s = ":create members{\n\tname{\n\t:date 1996-07-12 }\n}";
-    let seq = lexical_analyzer(s.to_string()).unwrap();
+    let seq = lexical_analyzer(s).unwrap();
     println!("{:?}", seq);
-    let parsed = vec!(NameColon("create".to_string()), Name("members".to_string()), LMidParen, Name("name".to_string()), LMidParen, NameColon("date".to_string()), Ymd(1996, 7, 12), RMidParen, RMidParen);
-    for i in 0..seq.get_tokens().len() {
-        assert_eq!(seq.get_token(i), parsed[i]);
+    let parsed = [NameColon("create".to_string()), Name("members".to_string()), LMidParen, Name("name".to_string()), LMidParen, NameColon("date".to_string()), Ymd(NaiveDate::from_ymd_opt(1996, 7, 12).unwrap()), RMidParen, RMidParen];
+    for (i, token) in seq.get_tokens().iter().enumerate() {
+        assert_eq!(*token, parsed[i]);
     }
     assert!(seq.analyze_syntax().is_ok());
 }
@@ -16,11 +18,11 @@ fn success_1() {
 #[test]
 fn failed_1() {
     let s = ":create members{\n}";
-    let seq = lexical_analyzer(s.to_string()).unwrap();
+    let seq = lexical_analyzer(s).unwrap();
     println!("{:?}", seq);
-    let parsed = vec!(NameColon("create".to_string()), Name("members".to_string()), LMidParen, RMidParen);
-    for i in 0..seq.get_tokens().len() {
-        assert_eq!(seq.get_token(i), parsed[i]);
+    let parsed = [NameColon("create".to_string()), Name("members".to_string()), LMidParen, RMidParen];
+    for (i, token) in seq.get_tokens().iter().enumerate() {
+        assert_eq!(*token, parsed[i]);
     }
     assert!(seq.analyze_syntax().is_err());
 }
@@ -28,11 +30,11 @@ fn failed_1() {
 #[test]
 fn failed_2() {
     let s = ":create{\n}";
-    let seq = lexical_analyzer(s.to_string()).unwrap();
+    let seq = lexical_analyzer(s).unwrap();
     println!("{:?}", seq);
-    let parsed = vec!(NameColon("create".to_string()), LMidParen, RMidParen);
-    for i in 0..seq.get_tokens().len() {
-        assert_eq!(seq.get_token(i), parsed[i]);
+    let parsed = [NameColon("create".to_string()), LMidParen, RMidParen];
+    for (i, token) in seq.get_tokens().iter().enumerate() {
+        assert_eq!(*token, parsed[i]);
     }
     assert!(seq.analyze_syntax().is_err());
 }
@@ -40,11 +42,11 @@ fn failed_2() {
 #[test]
 fn failed_3() {
     let s = "members{\n}";
-    let seq = lexical_analyzer(s.to_string()).unwrap();
+    let seq = lexical_analyzer(s).unwrap();
     println!("{:?}", seq);
-    let parsed = vec!(Name("members".to_string()), LMidParen, RMidParen);
-    for i in 0..seq.get_tokens().len() {
-        assert_eq!(seq.get_token(i), parsed[i]);
+    let parsed = [Name("members".to_string()), LMidParen, RMidParen];
+    for (i, token) in seq.get_tokens().iter().enumerate() {
+        assert_eq!(*token, parsed[i]);
     }
     assert!(seq.analyze_syntax().is_err());
 }
@@ -52,12 +54,36 @@ fn failed_3() {
 #[test]
 fn failed_4() {
     let s = ":create members{\nname {}}";
-    let seq = lexical_analyzer(s.to_string()).unwrap();
+    let seq = lexical_analyzer(s).unwrap();
     println!("{:?}", seq);
-    let parsed = vec!(NameColon("create".to_string()), Name("members".to_string()), LMidParen, Name("name".to_string()), LMidParen, RMidParen, RMidParen);
-    for i in 0..seq.get_tokens().len() {
-        assert_eq!(seq.get_token(i), parsed[i]);
+    let parsed = [NameColon("create".to_string()), Name("members".to_string()), LMidParen, Name("name".to_string()), LMidParen, RMidParen, RMidParen];
+    for (i, token) in seq.get_tokens().iter().enumerate() {
+        assert_eq!(*token, parsed[i]);
     }
     assert!(seq.analyze_syntax().is_err());
 }
 
+
+// distinct names for the columns. e.g. a, b, ..., z, aa, ba, ...
+fn to_alphabetic(mut i: usize) -> std::string::String {
+    let mut s = std::string::String::new();
+    loop {
+        s.push((b'a' + (i % 26) as u8) as char);
+        i /= 26;
+        if i == 0 {
+            return s;
+        }
+    }
+}
+
+#[test]
+fn many_columns() {
+    let mut s = ":create members{\n".to_string();
+    for i in 0..5000 {
+        s.push_str(&format!("\tname{}{{\n\t\t:string 10\n\t\t:default \"guest\"\n\t}}\n", to_alphabetic(i)));
+    }
+    s.push_str("\t:timestamps\n}");
+    let seq = lexical_analyzer(&s).unwrap();
+    assert_eq!(seq.get_tokens().len(), 3 + 5000 * 7 + 1 + 1);
+    assert!(seq.analyze_syntax().is_ok());
+}
@@ -0,0 +1,137 @@
+use mig::app::converter::ast::{Node, OptionNode};
+use mig::app::converter::ConverterError;
+use mig::app::converter::semantic::SemanticError;
+use mig::app::converter::parser::lexical_analyzer;
+use mig::app::converter::syntax::{syntax_analyzer, SyntaxError};
+use mig::app::converter::token::Token;
+
+fn as_option(node: &Node) -> &OptionNode {
+    match node {
+        Node::Option(o) => o,
+        _ => panic!("not option: {:?}", node),
+    }
+}
+
+#[test]
+fn nested_blocks() {
+    let s = ":create members {\n\
+    \tname { :string 10 }\n\
+    \t:timestamps\n\
+    \t:index {\n\
+    \t\t:unique {\n\
+    \t\t\t:target name old\n\
+    \t\t\t:where { :not-null name }\n\
+    \t\t}\n\
+    \t}\n\
+    }";
+    let seq = lexical_analyzer(s).unwrap();
+    let file = syntax_analyzer(&seq).unwrap();
+    assert_eq!(file.commands.len(), 1);
+    let command = &file.commands[0];
+    assert_eq!(command.name, "create");
+    assert_eq!(command.table_name, "members");
+    assert_eq!(command.position, (1, 1));
+    assert_eq!(command.body.nodes.len(), 3);
+
+    match &command.body.nodes[0] {
+        Node::Column(c) => {
+            assert_eq!(c.name, "name");
+            assert_eq!(c.position, (2, 2));
+            let string = as_option(&c.body.nodes[0]);
+            assert_eq!(string.name, "string");
+            assert_eq!(string.args[0].value, Token::Integer(10));
+            assert!(string.body.is_none());
+        }
+        n => panic!("not column: {:?}", n),
+    }
+
+    let timestamps = as_option(&command.body.nodes[1]);
+    assert_eq!(timestamps.name, "timestamps");
+    assert!(timestamps.args.is_empty());
+    assert!(timestamps.body.is_none());
+
+    let index = as_option(&command.body.nodes[2]);
+    let unique = as_option(&index.body.as_ref().unwrap().nodes[0]);
+    let target = as_option(&unique.body.as_ref().unwrap().nodes[0]);
+    assert_eq!(target.args.len(), 2);
+    assert_eq!(target.args[1].value, Token::Name("old".to_string()));
+    assert_eq!(target.args[1].position, (6, 17));
+    let where_node = as_option(&unique.body.as_ref().unwrap().nodes[1]);
+    let not_null = as_option(&where_node.body.as_ref().unwrap().nodes[0]);
+    assert_eq!(not_null.name, "not-null");
+}
+
+#[test]
+fn many_commands() {
+    let s = ":create members { name { :string } }\n:create groups { title { :string } }";
+    let seq = lexical_analyzer(s).unwrap();
+    let file = syntax_analyzer(&seq).unwrap();
+    assert_eq!(file.commands.len(), 2);
+    assert_eq!(file.commands[1].table_name, "groups");
+    assert_eq!(file.commands[1].position, (2, 1));
+
+    let schema = seq.analyze_syntax().unwrap();
+    assert_eq!(schema.tables.len(), 2);
+}
+
+#[test]
+fn error_positions() {
+    let s_1 = ":create members {\n\tname { :string 10 }\n";
+    let s_2 = ":create members {\n\tname { :string 10 } }\n}";
+    let s_3 = ":create members {\n\tname { 10 }\n}";
+    let s_4 = ":create members {\n\tname { :string hoge }\n}";
+    let s_5 = ":create members {\n\t:index { :unique { :target name } }\n}";
+    let s_6 = ":drop members {\n\tname { :string }\n}";
+
+    match syntax_analyzer(&lexical_analyzer(s_1).unwrap()) {
+        Err(SyntaxError::NotClosedBlock(1, 17)) => {}
+        r => panic!("{:?}", r),
+    }
+    match syntax_analyzer(&lexical_analyzer(s_2).unwrap()) {
+        Err(SyntaxError::UnexpectedToken(Token::RMidParen, 3, 1)) => {}
+        r => panic!("{:?}", r),
+    }
+    match syntax_analyzer(&lexical_analyzer(s_3).unwrap()) {
+        Err(SyntaxError::UnexpectedToken(Token::Integer(10), 2, 9)) => {}
+        r => panic!("{:?}", r),
+    }
+    match lexical_analyzer(s_4).unwrap().analyze_syntax() {
+        Err(ConverterError::Semantic(SemanticError::InvalidParams(_, 2, 17))) => {}
+        r => panic!("{:?}", r),
+    }
+    match lexical_analyzer(s_5).unwrap().analyze_syntax() {
+        Err(ConverterError::Semantic(SemanticError::UnexpectedBlock(_, 2, 19))) => {}
+        r => panic!("{:?}", r),
+    }
+    match lexical_analyzer(s_6).unwrap().analyze_syntax() {
+        Err(ConverterError::Semantic(SemanticError::UnknownCommand(_, 1, 1))) => {}
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn many_lines() {
+    // the rows are over u16
+    let s = format!("{}:create members {{\n\tname {{ :string :hoge }}\n}}", "\n".repeat(80_000));
+    let e = mig::analyze(&mig::parse_str(&s).unwrap()).unwrap_err();
+    assert_eq!(e.get_position(), Some((80_002, 17)));
+}
+
+#[test]
+fn error_codes() {
+    let s_1 = ":create members { name { :string :hoge } }";
+    let s_2 = ":create members { name { :string }";
+    let s_3 = ":create members { name { :string :default 99999 } }";
+
+    let e_1 = mig::analyze(&mig::parse_str(s_1).unwrap()).unwrap_err();
+    assert_eq!((e_1.code(), e_1.get_position()), ("E0001", Some((1, 34))));
+    let e_2 = mig::parse_str(s_2).unwrap_err();
+    assert_eq!((e_2.code(), e_2.get_position()), ("E0202", Some((1, 17))));
+    let e_3 = mig::parse_str(s_3).unwrap_err();
+    assert_eq!(e_3.code(), "E0105");
+
+    let diagnostic = mig::Diagnostic::from_converter_error(Some("members.mig"), &e_1);
+    assert_eq!(diagnostic.line, Some(1));
+    assert_eq!(diagnostic.column, Some(34));
+    assert_eq!(diagnostic.severity, mig::Severity::Error);
+}
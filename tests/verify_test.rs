@@ -0,0 +1,48 @@
+use mig::app::verify::{verify_sqlite, VerifyError};
+use mig::{analyze, parse_str, Schema};
+
+fn schema(src: &str) -> Schema {
+    analyze(&parse_str(src).unwrap()).unwrap()
+}
+
+#[test]
+fn verify_generated_sql() {
+    let src = ":create teams {
+    id { :big-increments }
+    name { :string 255 :unique }
+}
+:create members {
+    id { :increments }
+    name { :string 10 :default \"guest: mig\" :comment \"it's name\" }
+    old { :tiny-int :unsigned :default 0 }
+    score { :double :nullable }
+    profile { :json :nullable }
+    team_id { :big-int :unsigned :references teams id :index }
+    :timestamps
+    :soft-deletes
+    :unique-index { :target name old :name \"hoge\" }
+}
+:create pairs {
+    a { :char 4 }
+    b { :int }
+    :primary-key { :target a b :name \"pairs_key\" }
+}";
+    assert!(verify_sqlite(&schema(src)).is_ok());
+}
+
+#[test]
+fn verify_keyword_names() {
+    // input_test.mig has the column check
+    let src = std::fs::read_to_string("input_test.mig").unwrap();
+    assert!(verify_sqlite(&schema(&src)).is_ok());
+    assert!(verify_sqlite(&schema(":create order {\n    id { :increments }\n    select { :string }\n}\n")).is_ok());
+}
+
+#[test]
+fn verify_invalid_sql() {
+    let result = verify_sqlite(&schema(":create items { id { :increments :primary } }"));
+    match result {
+        Err(VerifyError::FailedApply(e)) => assert!(e.to_string().contains("more than one primary key"), "{}", e),
+        _ => panic!("the primary key is duplicated"),
+    }
+}